@@ -0,0 +1,61 @@
+// Tiled N-body evaluation of the Biot–Savart sum over get_source(0) .. get_source(source_count - 1),
+// which the including shader has to define together with local_size_x = TILE_SIZE. Blocks of
+// TILE_SIZE sources are staged in shared memory by the whole workgroup, so every invocation has
+// to reach each call in uniform control flow, the ones without a target with a dummy position.
+// The source at index skip is left out, pass NO_SOURCE to sum all of them.
+const uint TILE_SIZE = 256;
+const uint NO_SOURCE = 0xFFFFFFFFu;
+
+shared vec4 tile_positions[TILE_SIZE];
+shared vec4 tile_normals[TILE_SIZE];
+shared vec4 tile_vorticities[TILE_SIZE];
+shared vec4 tile_lifetimes[TILE_SIZE];
+
+// Stages the sources from tile_start on and returns how many of them the tile holds. Shaders
+// summing more than the velocity over the sources walk the tiles themselves:
+//     uint tile_start = 0;
+//     while(tile_start < source_count){
+//         uint tile_count = begin_tile(tile_start, source_count);
+//         ... get_tile_source(0) .. get_tile_source(tile_count - 1) ...
+//         end_tile();
+//         tile_start += tile_count;
+//     }
+uint begin_tile(uint tile_start, uint source_count){
+    uint source_index = tile_start + gl_LocalInvocationID.x;
+    if(source_index < source_count){
+        Vortex source = get_source(source_index);
+        tile_positions[gl_LocalInvocationID.x] = source.position;
+        tile_normals[gl_LocalInvocationID.x] = source.normal;
+        tile_vorticities[gl_LocalInvocationID.x] = source.vorticity;
+        tile_lifetimes[gl_LocalInvocationID.x] = source.lifetime;
+    }
+    barrier();
+    return min(TILE_SIZE, source_count - tile_start);
+}
+
+// Source i of the staged tile.
+Vortex get_tile_source(uint i){
+    return Vortex(tile_positions[i], tile_normals[i], tile_vorticities[i], tile_lifetimes[i]);
+}
+
+// Waits for the whole workgroup before the next tile overwrites this one.
+void end_tile(){
+    barrier();
+}
+
+vec3 get_tiled_velocity(vec3 position, uint source_count, uint skip){
+    vec3 velocity = vec3(0.0f);
+    uint tile_start = 0;
+    while(tile_start < source_count){
+        uint tile_count = begin_tile(tile_start, source_count);
+        for(uint i = 0; i < tile_count; i++){
+            if(tile_start + i == skip){
+                continue;
+            }
+            velocity += get_velocity(vec4(position, 1.0f), get_tile_source(i));
+        }
+        end_tile();
+        tile_start += tile_count;
+    }
+    return velocity;
+}
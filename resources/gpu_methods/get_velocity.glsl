@@ -16,10 +16,101 @@
 //     return cross(b.vorticity.xyz, diff / distance);
 // }
 
+struct KernelInfo{
+    uint smoothing;
+    float core_radius;
+};
+
+layout(std430, binding=60) buffer kernel_info_data{
+    KernelInfo kernel;
+};
+
+const uint SMOOTHING_SINGULAR = 0;
+const uint SMOOTHING_ROSENHEAD_MOORE = 1;
+const uint SMOOTHING_GAUSSIAN = 2;
+const uint SMOOTHING_WINCKELMANS_LEONARD = 3;
+
+// Abramowitz-Stegun 7.1.26, the same approximation physics::kernel::erf uses.
+float kernel_erf(float x){
+    float t = 1.0f / (1.0f + 0.3275911f * abs(x));
+    float polynomial = t * (0.2548296f + t * (-0.28449672f + t * (1.4214138f + t * (-1.453152f + t * 1.0614054f))));
+    float value = 1.0f - polynomial * exp(-x * x);
+    return x < 0.0f ? -value : value;
+}
+
+// q(r / sigma) / r^3, finite at r = 0 for the regularized kernels.
+float kernel_factor(float distance){
+    float r2 = distance * distance;
+    float s2 = kernel.core_radius * kernel.core_radius;
+    if(kernel.smoothing == SMOOTHING_ROSENHEAD_MOORE){
+        return 1.0f / pow(r2 + s2, 1.5f);
+    }
+    if(kernel.smoothing == SMOOTHING_WINCKELMANS_LEONARD){
+        return (r2 + 2.5f * s2) / pow(r2 + s2, 2.5f);
+    }
+    if(kernel.smoothing == SMOOTHING_GAUSSIAN){
+        float rho = distance / kernel.core_radius;
+        float sigma3 = s2 * kernel.core_radius;
+        if(rho < 0.5f){
+            float rho2 = rho * rho;
+            return sqrt(2.0f / 3.1415926535897932384626433832795f) * (1.0f / 3.0f - rho2 / 10.0f + rho2 * rho2 / 56.0f - rho2 * rho2 * rho2 / 432.0f) / sigma3;
+        }
+        float q = kernel_erf(rho / sqrt(2.0f)) - sqrt(2.0f / 3.1415926535897932384626433832795f) * rho * exp(-rho * rho / 2.0f);
+        return q / (r2 * distance);
+    }
+    return 1.0f / (r2 * distance);
+}
+
+// f'(r) / r of kernel_factor, needed for the velocity gradient.
+float kernel_factor_derivative(float distance){
+    float r2 = distance * distance;
+    float s2 = kernel.core_radius * kernel.core_radius;
+    if(kernel.smoothing == SMOOTHING_ROSENHEAD_MOORE){
+        return -3.0f / pow(r2 + s2, 2.5f);
+    }
+    if(kernel.smoothing == SMOOTHING_WINCKELMANS_LEONARD){
+        return 2.0f * (1.0f / pow(r2 + s2, 2.5f) - 2.5f * (r2 + 2.5f * s2) / pow(r2 + s2, 3.5f));
+    }
+    if(kernel.smoothing == SMOOTHING_GAUSSIAN){
+        float rho = distance / kernel.core_radius;
+        float sigma5 = s2 * s2 * kernel.core_radius;
+        if(rho < 0.5f){
+            float rho2 = rho * rho;
+            return sqrt(2.0f / 3.1415926535897932384626433832795f) * (-1.0f / 5.0f + rho2 / 14.0f - rho2 * rho2 / 72.0f) / sigma5;
+        }
+        float gauss = sqrt(2.0f / 3.1415926535897932384626433832795f) * exp(-rho * rho / 2.0f);
+        float q = kernel_erf(rho / sqrt(2.0f)) - gauss * rho;
+        return (gauss * rho * rho * rho - 3.0f * q) / (r2 * r2 * distance);
+    }
+    return -3.0f / (r2 * r2 * distance);
+}
+
+bool get_panel_velocity(vec3 a, Vortex b, out vec3 velocity);
+
+// Near a panel of panel.glsl, which every shader including this one includes too, its exact
+// velocity instead.
 vec3 get_velocity(vec4 a, Vortex b){
     if(length(b.vorticity.xyz) < 0.0001f) return vec3(0.0f, 0.0f, 0.0f);
+    vec3 panel_velocity;
+    if(get_panel_velocity(a.xyz, b, panel_velocity)) return panel_velocity;
     vec3 diff = (b.position - a).xyz;
     float distance = length(diff);
     if(distance < 0.0001f) return vec3(0.0f, 0.0f, 0.0f);
-    return cross(b.vorticity.xyz, diff / (distance * distance * distance));
+    return cross(b.vorticity.xyz, diff) * kernel_factor(distance);
+}
+
+// Jacobian d(u_i) / d(a_j) of get_velocity with respect to the evaluation point.
+mat3 get_velocity_gradient(vec4 a, Vortex b){
+    vec3 vorticity = b.vorticity.xyz;
+    if(length(vorticity) < 0.0001f) return mat3(0.0f);
+    vec3 diff = (b.position - a).xyz;
+    float distance = length(diff);
+    if(distance < 0.0001f) return mat3(0.0f);
+    mat3 cross_matrix = mat3(
+        vec3(0.0f, vorticity.z, -vorticity.y),
+        vec3(-vorticity.z, 0.0f, vorticity.x),
+        vec3(vorticity.y, -vorticity.x, 0.0f)
+    );
+    mat3 outer = outerProduct(cross(vorticity, diff), diff);
+    return -(cross_matrix * kernel_factor(distance) + outer * kernel_factor_derivative(distance));
 }
\ No newline at end of file
@@ -0,0 +1,40 @@
+// Advances position by dt through the velocity field returned by get_total_velocity,
+// which the including shader has to define. The schemes match physics::integrator.
+const uint INTEGRATOR_EULER = 0;
+const uint INTEGRATOR_MIDPOINT = 1;
+const uint INTEGRATOR_RUNGE_KUTTA_4 = 2;
+const uint INTEGRATOR_LOW_STORAGE_RUNGE_KUTTA_3 = 3;
+
+// integrate with velocity, get_total_velocity(position), already summed, like a pass that
+// gathers it together with other terms over the same sources.
+vec3 integrate_from(vec3 position, vec3 velocity, float dt, uint integrator){
+    if(integrator == INTEGRATOR_MIDPOINT){
+        return position + get_total_velocity(position + velocity * dt * 0.5f) * dt;
+    }
+    if(integrator == INTEGRATOR_RUNGE_KUTTA_4){
+        vec3 k1 = velocity;
+        vec3 k2 = get_total_velocity(position + k1 * dt * 0.5f);
+        vec3 k3 = get_total_velocity(position + k2 * dt * 0.5f);
+        vec3 k4 = get_total_velocity(position + k3 * dt);
+        return position + (k1 + 2.0f * k2 + 2.0f * k3 + k4) * dt / 6.0f;
+    }
+    if(integrator == INTEGRATOR_LOW_STORAGE_RUNGE_KUTTA_3){
+        const float a[3] = float[3](0.0f, -5.0f / 9.0f, -153.0f / 128.0f);
+        const float b[3] = float[3](1.0f / 3.0f, 15.0f / 16.0f, 8.0f / 15.0f);
+        vec3 q = vec3(0.0f);
+        for(int i = 0; i < 3; i++){
+            vec3 k = velocity;
+            if(i > 0){
+                k = get_total_velocity(position);
+            }
+            q = a[i] * q + k * dt;
+            position += b[i] * q;
+        }
+        return position;
+    }
+    return position + velocity * dt;
+}
+
+vec3 integrate(vec3 position, float dt, uint integrator){
+    return integrate_from(position, get_total_velocity(position), dt, integrator);
+}
@@ -0,0 +1,87 @@
+// Constant vortex sheet panels on triangles, see physics::panel. A boundary vortex with a
+// normal and its corners in lifetime is one, anything else a point vortex.
+
+// physics::panel::NEAR_FIELD_RADII
+const float PANEL_NEAR_FIELD_RADII = 10.0f;
+
+// Orthonormal tangents of a unit normal, the ones physics::panel::tangents builds.
+void panel_tangents(vec3 normal, out vec3 t1, out vec3 t2){
+    float sign = normal.z >= 0.0f ? 1.0f : -1.0f;
+    float a = -1.0f / (sign + normal.z);
+    float b = normal.x * normal.y * a;
+    t1 = vec3(1.0f + sign * normal.x * normal.x * a, sign * b, -sign * normal.x);
+    t2 = vec3(b, sign + normal.y * normal.y * a, -normal.y);
+}
+
+// The lifetime holding the corners a and b, relative to the centroid, of a panel along normal.
+vec4 pack_panel_corners(vec3 normal, vec3 a, vec3 b){
+    vec3 t1;
+    vec3 t2;
+    panel_tangents(normalize(normal), t1, t2);
+    return vec4(dot(a, t1), dot(a, t2), dot(b, t1), dot(b, t2));
+}
+
+// The corners of a panel relative to its position, false for point vorticies.
+bool get_panel_corners(Vortex vortex, out vec3 a, out vec3 b, out vec3 c){
+    a = vec3(0.0f);
+    b = vec3(0.0f);
+    c = vec3(0.0f);
+    if(dot(vortex.normal.xyz, vortex.normal.xyz) == 0.0f || vortex.lifetime == vec4(0.0f)){
+        return false;
+    }
+    vec3 t1;
+    vec3 t2;
+    panel_tangents(normalize(vortex.normal.xyz), t1, t2);
+    a = t1 * vortex.lifetime.x + t2 * vortex.lifetime.y;
+    b = t1 * vortex.lifetime.z + t2 * vortex.lifetime.w;
+    c = -a - b;
+    return true;
+}
+
+// Integral of (x - y) / |x - y|^3 over the triangle p0 p1 p2, physics::panel::influence.
+vec3 panel_influence(vec3 point, vec3 p0, vec3 p1, vec3 p2){
+    vec3 normal = normalize(cross(p1 - p0, p2 - p0));
+    vec3 r[3] = vec3[3](p0 - point, p1 - point, p2 - point);
+    float d[3] = float[3](length(r[0]), length(r[1]), length(r[2]));
+
+    vec3 tangential = vec3(0.0f);
+    for(uint i = 0; i < 3; i++){
+        uint j = (i + 1) % 3;
+        vec3 edge = r[j] - r[i];
+        float edge_length = length(edge);
+        // Points on the edge itself get no contribution from it.
+        float denominator = d[i] + d[j] - edge_length;
+        if(denominator <= 1e-6f * edge_length){
+            continue;
+        }
+        vec3 outward = cross(edge, normal) / edge_length;
+        tangential += outward * log((d[i] + d[j] + edge_length) / denominator);
+    }
+
+    float triple = dot(r[0], cross(r[1], r[2]));
+    float denominator = d[0] * d[1] * d[2] + dot(r[0], r[1]) * d[2] + dot(r[0], r[2]) * d[1] + dot(r[1], r[2]) * d[0];
+    float solid_angle = 0.0f;
+    if(abs(triple) > 1e-6f * d[0] * d[1] * d[2]){
+        solid_angle = -2.0f * atan(triple, denominator);
+    }
+    return tangential + normal * solid_angle;
+}
+
+// The velocity at a of the panel b if a is in its near field, physics::panel::get_velocity.
+bool get_panel_velocity(vec3 a, Vortex b, out vec3 velocity){
+    velocity = vec3(0.0f);
+    vec3 c0;
+    vec3 c1;
+    vec3 c2;
+    if(!get_panel_corners(b, c0, c1, c2)){
+        return false;
+    }
+    float radius = sqrt(max(dot(c0, c0), max(dot(c1, c1), dot(c2, c2))));
+    vec3 center = b.position.xyz;
+    if(length(a - center) >= PANEL_NEAR_FIELD_RADII * radius){
+        return false;
+    }
+    vec3 sheet = b.vorticity.xyz / b.normal.w;
+    velocity = -cross(sheet, panel_influence(a, center + c0, center + c1, center + c2));
+    return true;
+}
@@ -1,10 +1,10 @@
 #version 460 core
 out vec4 final_color;
 
-uniform vec4 color;
+layout(location = 1) flat in vec4 vertex_color;
 
 void main() {
-  vec4 new_color = color;
+  vec4 new_color = vertex_color;
   float opacity = 1.0f;
   final_color = vec4(new_color.xyz, opacity);
 }
\ No newline at end of file
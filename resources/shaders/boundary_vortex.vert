@@ -2,6 +2,12 @@
 layout (location = 0) in vec3 pos;
 
 uniform mat4 viewProjectionMatrix;
+uniform vec4 color;
+// `ColorMode` of `BoundaryVorticies`
+uniform uint color_mode;
+
+const uint UNIFORM = 0;
+const uint PRESSURE = 1;
 
 struct Vortex{
     vec4 position;
@@ -10,27 +16,23 @@ struct Vortex{
     vec4 lifetime;
 };
 
-layout(std430, binding=10) buffer vorticies_data{
+// The current vorticies of every boundary body in the world frame.
+layout(std430, binding=11) buffer vorticies_data{
     Vortex vorticies[];
 };
 
-struct BoundaryInfo{
-    uint current_index;
-    uint current_count;
-    uint count;
-};
-
-layout(std430, binding=50) buffer boundary_info_data{
-    BoundaryInfo info;
+// Pressure coefficients of the vorticies above, while `SurfacePressure` is enabled.
+layout(std430, binding=13) buffer pressure_data{
+    float pressure_coefficients[];
 };
 
-
 flat out uint index;
+layout(location = 1) flat out vec4 vertex_color;
 
 mat4 getRotationMatrix(vec3 a, vec3 b);
 
 void main() {
-    uint idx = gl_InstanceID + info.current_index * info.current_count;
+    uint idx = gl_InstanceID;
     mat4 translationMatrix = mat4(1.0f);
     translationMatrix[3] = vorticies[idx].position;
     mat4 scaleMatrix = mat4(1.0f);
@@ -41,11 +43,18 @@ void main() {
     scaleMatrix[0][0] = 0.2f;
     scaleMatrix[1][1] = 0.2f;
     scaleMatrix[2][2] = 0.2f;
-    mat4 rotationMatrix = getRotationMatrix(vec3(0.0f, 1.0f, 0.0f), normalize(vorticies[gl_InstanceID].vorticity.xyz));
+    mat4 rotationMatrix = getRotationMatrix(vec3(0.0f, 1.0f, 0.0f), normalize(vorticies[idx].vorticity.xyz));
     mat4 modelMatrix = translationMatrix * scaleMatrix * rotationMatrix;
     vec4 ndcPos = vec4(pos, 1) * transpose(modelMatrix) * viewProjectionMatrix;
 	gl_Position = ndcPos;
     index = idx;
+    vertex_color = color;
+    if(color_mode == PRESSURE){
+        float cp = clamp(pressure_coefficients[idx], -1.0f, 1.0f);
+        vertex_color = cp < 0.0f
+            ? vec4(mix(vec3(1.0f), vec3(0.0f, 0.2f, 1.0f), -cp), 1.0f)
+            : vec4(mix(vec3(1.0f), vec3(1.0f, 0.1f, 0.0f), cp), 1.0f);
+    }
 }
 
 mat4 getRotationMatrix(vec3 a, vec3 b){
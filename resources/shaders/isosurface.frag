@@ -0,0 +1,19 @@
+#version 460 core
+out vec4 final_color;
+
+uniform vec4 color;
+
+layout(location = 0) in vec3 world_position;
+layout(location = 1) in vec3 world_normal;
+
+const vec3 LIGHT_DIRECTION = vec3(0.3, 0.8, 0.5);
+
+void main() {
+  // The interpolated vertex normals from the field gradient, or the facet normal from the
+  // screen space derivatives of the position where the gradient vanishes.
+  vec3 normal = length(world_normal) > 1e-6f
+    ? normalize(world_normal)
+    : normalize(cross(dFdx(world_position), dFdy(world_position)));
+  float diffuse = abs(dot(normal, normalize(LIGHT_DIRECTION)));
+  final_color = vec4(color.xyz * (0.3 + 0.7 * diffuse), color.w);
+}
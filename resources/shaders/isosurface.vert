@@ -0,0 +1,14 @@
+#version 460 core
+layout (location = 0) in vec4 pos;
+layout (location = 1) in vec4 normal;
+
+uniform mat4 mvp;
+
+layout(location = 0) out vec3 world_position;
+layout(location = 1) out vec3 world_normal;
+
+void main() {
+	world_position = pos.xyz;
+	world_normal = normal.xyz;
+	gl_Position = pos * mvp;
+}
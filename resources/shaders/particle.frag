@@ -3,12 +3,18 @@ out vec4 final_color;
 
 // uniform vec4 color;
 uniform sampler2D texture0;
+// Tints the particles with the first three scalar channels, like a dye colour.
+uniform bool scalar_coloring;
 
 flat in int index;
 flat in float opacity;
+flat in vec4 scalars;
 in vec2 texCoords;
 
 void main() {
   vec4 color = texture(texture0, texCoords);
+  if(scalar_coloring){
+    color.xyz *= scalars.xyz;
+  }
   final_color = vec4(color.xyz, color.w * opacity * 0.25f);
 }
\ No newline at end of file
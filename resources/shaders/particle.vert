@@ -8,6 +8,7 @@ struct Particle{
     vec4 position;
     vec4 lifetime;
     vec4 velocity;
+    vec4 scalars;
 };
 
 layout(std430, binding=1) buffer particles_data{
@@ -16,6 +17,7 @@ layout(std430, binding=1) buffer particles_data{
 
 flat out int index;
 flat out float opacity;
+flat out vec4 scalars;
 out vec2 texCoords;
 
 const float PI = 3.14159265359f;
@@ -39,5 +41,6 @@ void main() {
         opacity = cos(PI  * frac-(PI/2.0f));
     }
 
+    scalars = particle.scalars;
     texCoords = pos.xy * 0.5f + 0.5f;
 }
\ No newline at end of file
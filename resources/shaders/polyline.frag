@@ -0,0 +1,8 @@
+#version 460 core
+out vec4 final_color;
+
+uniform vec4 color;
+
+void main() {
+  final_color = color;
+}
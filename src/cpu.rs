@@ -0,0 +1,3 @@
+pub mod active_vorticies;
+pub mod field_sampler;
+pub mod particles;
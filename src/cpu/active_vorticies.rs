@@ -0,0 +1,355 @@
+use std::{cell::RefCell, ops::Range, rc::Rc};
+
+use cgmath::{InnerSpace, Vector3, Zero};
+use rayon::prelude::*;
+
+use crate::{
+    objects::active_vorticies::ActiveVorticies,
+    physics::{
+        buoyancy::Buoyancy,
+        diffusion::Diffusion,
+        integrator::Integrator,
+        kernel::Kernel,
+        shedding::{Shedding, SHED},
+        stretching::StretchingScheme,
+        treecode::{Evaluator, VelocityField},
+    },
+    structures::vortex::Vortex,
+    support::camera::PerspectiveCamera,
+    traits::{drawable::Drawable, steppable::Steppable},
+    util::{random_inside_sphere, random_inside_unit_sphere, random_on_unit_sphere},
+};
+
+pub type SharedVorticies = Rc<RefCell<Vec<Vortex>>>;
+
+/// CPU counterpart of `ActiveVorticies`, stepping the same dynamics as `vortex.comp`
+/// without an OpenGL context.
+pub struct CpuActiveVorticies {
+    pub vorticies: SharedVorticies,
+    pub mirror_number: usize,
+    pub number_of_vorticies: usize,
+    pub fading_enabled: bool,
+    pub min_lifetime: f32,
+    pub max_lifetime: f32,
+    pub min_vorticity: f32,
+    pub max_vorticity: f32,
+    pub kernel: Kernel,
+    pub stretching_scheme: StretchingScheme,
+    pub diffusion: Diffusion,
+    pub buoyancy: Buoyancy,
+    pub integrator: Integrator,
+    pub evaluator: Evaluator,
+    /// Non mirror slots reserved for `Shedding`
+    pub shedding_slots: Range<usize>,
+}
+
+impl CpuActiveVorticies {
+    pub fn new(
+        vorticies: Vec<Vortex>,
+        min_lifetime: f32,
+        max_lifetime: f32,
+        min_vorticity: f32,
+        max_vorticity: f32,
+        mirror_number: usize,
+    ) -> CpuActiveVorticies {
+        let mut vorticies = vorticies;
+        vorticies.resize(
+            (mirror_number + 1) * vorticies.len(),
+            Vortex {
+                ..Default::default()
+            },
+        );
+
+        let number_of_vorticies = vorticies.len();
+
+        CpuActiveVorticies {
+            vorticies: Rc::new(RefCell::new(vorticies)),
+            mirror_number,
+            number_of_vorticies,
+            fading_enabled: true,
+            min_lifetime,
+            max_lifetime,
+            min_vorticity,
+            max_vorticity,
+            kernel: Kernel::default(),
+            stretching_scheme: StretchingScheme::default(),
+            diffusion: Diffusion::default(),
+            buoyancy: Buoyancy::default(),
+            integrator: Integrator::default(),
+            evaluator: Evaluator::default(),
+            shedding_slots: 0..0,
+        }
+    }
+
+    pub fn new_random(
+        number_of_vorticies: usize,
+        min_lifetime: f32,
+        max_lifetime: f32,
+        min_vorticity: f32,
+        max_vorticity: f32,
+        mirror_number: usize,
+    ) -> CpuActiveVorticies {
+        let vorticies = ActiveVorticies::get_random_vorticies(
+            number_of_vorticies,
+            min_lifetime,
+            max_lifetime,
+            min_vorticity,
+            max_vorticity,
+        );
+
+        CpuActiveVorticies::new(
+            vorticies,
+            min_lifetime,
+            max_lifetime,
+            min_vorticity,
+            max_vorticity,
+            mirror_number,
+        )
+    }
+
+    pub fn with_fading(mut self, fading_enabled: bool) -> CpuActiveVorticies {
+        self.fading_enabled = fading_enabled;
+        self
+    }
+
+    pub fn with_kernel(mut self, kernel: Kernel) -> CpuActiveVorticies {
+        self.kernel = kernel;
+        self
+    }
+
+    pub fn with_stretching(mut self, stretching_scheme: StretchingScheme) -> CpuActiveVorticies {
+        self.stretching_scheme = stretching_scheme;
+        self
+    }
+
+    pub fn with_diffusion(mut self, diffusion: Diffusion) -> CpuActiveVorticies {
+        self.diffusion = diffusion;
+        self
+    }
+
+    pub fn with_buoyancy(mut self, buoyancy: Buoyancy) -> CpuActiveVorticies {
+        self.buoyancy = buoyancy;
+        self
+    }
+
+    pub fn with_integrator(mut self, integrator: Integrator) -> CpuActiveVorticies {
+        self.integrator = integrator;
+        self
+    }
+
+    pub fn with_evaluator(mut self, evaluator: Evaluator) -> CpuActiveVorticies {
+        self.evaluator = evaluator;
+        self
+    }
+
+    /// Appends `capacity` dormant slots to the non mirror vorticies for `Shedding` to fill,
+    /// see `shedding_slots`.
+    pub fn with_shedding_capacity(mut self, capacity: usize) -> CpuActiveVorticies {
+        let number_of_non_mirror = self.number_of_non_mirror();
+        {
+            let mut vorticies = self.vorticies.borrow_mut();
+            vorticies.truncate(number_of_non_mirror);
+            vorticies.extend(vec![Shedding::dormant_vortex(); capacity]);
+            self.shedding_slots = number_of_non_mirror..vorticies.len();
+            let len = vorticies.len();
+            vorticies.resize((self.mirror_number + 1) * len, Vortex::default());
+            self.number_of_vorticies = vorticies.len();
+        }
+        self
+    }
+
+    /// Writes shed vorticies into their slots, like `boundary_shedding.comp`.
+    pub fn insert_shed(&self, shed: &[(usize, Vortex)]) {
+        let mut vorticies = self.vorticies.borrow_mut();
+        for (slot, vortex) in shed {
+            vorticies[*slot] = *vortex;
+        }
+    }
+
+    /// Handle to the vortex storage, to be passed to `CpuParticles` the same way
+    /// binding 2 is shared between `vortex.comp` and `particle.comp`.
+    pub fn shared_vorticies(&self) -> SharedVorticies {
+        Rc::clone(&self.vorticies)
+    }
+
+    pub fn get_vorticies(&self) -> Vec<Vortex> {
+        self.vorticies.borrow().clone()
+    }
+
+    fn number_of_non_mirror(&self) -> usize {
+        self.number_of_vorticies / (self.mirror_number + 1)
+    }
+
+    fn update_mirror(mirror_vortex: &Vortex) -> Vortex {
+        let p = mirror_vortex.position.truncate();
+        let random_on_sphere = random_on_unit_sphere();
+        let mirror_plane_normal = (random_on_sphere - p).normalize();
+        Vortex {
+            position: mirror_position(p, random_on_sphere, mirror_plane_normal).extend(1.0),
+            vorticity: mirror_direction(mirror_vortex.vorticity.truncate(), mirror_plane_normal)
+                .extend(1.0),
+            ..*mirror_vortex
+        }
+    }
+
+    fn dynamics(&self) -> Dynamics {
+        Dynamics {
+            stretching_scheme: self.stretching_scheme,
+            diffusion: self.diffusion,
+            buoyancy: self.buoyancy,
+            integrator: self.integrator,
+        }
+    }
+}
+
+/// Core radii within which neighbours exchange strength and heat.
+const NEIGHBOUR_RADII: f32 = 6.0;
+
+/// The `Sync` part of `CpuActiveVorticies` the worker threads need.
+#[derive(Clone, Copy)]
+struct Dynamics {
+    stretching_scheme: StretchingScheme,
+    diffusion: Diffusion,
+    buoyancy: Buoyancy,
+    integrator: Integrator,
+}
+
+impl Dynamics {
+    /// Reach of the particle strength exchange and the conduction, whose Gaussian kernels
+    /// fall below `1e-7` of their peak beyond it.
+    fn neighbour_radius(&self) -> f32 {
+        let core_radius = match (self.diffusion.enabled(), self.buoyancy.enabled()) {
+            (true, true) => self.diffusion.core_radius.max(self.buoyancy.core_radius),
+            (true, false) => self.diffusion.core_radius,
+            _ => self.buoyancy.core_radius,
+        };
+        NEIGHBOUR_RADII * core_radius
+    }
+
+    fn interact(
+        &self,
+        vortex: &Vortex,
+        index: usize,
+        vorticies: &[Vortex],
+        field: &VelocityField,
+        dt: f32,
+    ) -> Vortex {
+        let position = vortex.position.truncate();
+        let vorticity = vortex.vorticity.truncate();
+        let gradient = field.get_velocity_gradient(position, Some(index));
+        let mut diffusion = Vector3::zero();
+        let mut temperature_gradient = Vector3::zero();
+        let mut conduction = 0.0;
+        if self.diffusion.enabled() || self.buoyancy.enabled() {
+            field.for_each_neighbour(position, self.neighbour_radius(), |i| {
+                let other = &vorticies[i];
+                if i == index || Shedding::is_dormant(other) {
+                    return;
+                }
+                let dist = (other.position.truncate() - position).magnitude();
+                if dist < 0.0001 {
+                    return;
+                }
+                if self.diffusion.enabled() {
+                    diffusion += self.diffusion.get_exchange(vortex, other);
+                }
+                if self.buoyancy.enabled() {
+                    temperature_gradient += self.buoyancy.get_gradient(vortex, other);
+                    conduction += self.buoyancy.get_conduction(vortex, other);
+                }
+            });
+        }
+        let stretching = self.stretching_scheme.stretching(gradient, vorticity);
+        let position = self
+            .integrator
+            .integrate(position, dt, |point| field.get_velocity(point, Some(index)));
+        if Shedding::is_dormant(vortex) {
+            return Vortex {
+                position: position.extend(1.0),
+                ..*vortex
+            };
+        }
+        let baroclinic = if self.buoyancy.enabled() {
+            self.buoyancy.baroclinic(temperature_gradient)
+        } else {
+            Vector3::zero()
+        };
+        let heating = self
+            .buoyancy
+            .heat_source
+            .heating(vortex.position.truncate());
+        let temperature = Buoyancy::temperature(vortex) + (conduction + heating) * dt;
+        Vortex {
+            position: position.extend(1.0),
+            vorticity: (vorticity + (stretching + diffusion + baroclinic) * dt).extend(1.0),
+            lifetime: vortex.lifetime.truncate().extend(temperature),
+            ..*vortex
+        }
+    }
+}
+
+impl Drawable for CpuActiveVorticies {
+    fn draw(&self, _camera: &PerspectiveCamera) {}
+}
+
+impl Steppable for CpuActiveVorticies {
+    fn step(&mut self, dt: f32, _camera: &PerspectiveCamera) {
+        let number_of_non_mirror = self.number_of_non_mirror();
+        let mut vorticies = self.vorticies.borrow_mut();
+
+        let lifetimes = (self.min_lifetime, self.max_lifetime);
+        let vorticities = (self.min_vorticity, self.max_vorticity);
+        let (non_mirrors, mirrors) = vorticies.split_at_mut(number_of_non_mirror);
+        non_mirrors
+            .par_iter_mut()
+            .for_each(|vortex| update_non_mirror(vortex, dt, lifetimes, vorticities));
+        mirrors.par_iter_mut().enumerate().for_each(|(i, vortex)| {
+            *vortex = CpuActiveVorticies::update_mirror(&non_mirrors[i % number_of_non_mirror]);
+        });
+
+        let dynamics = self.dynamics();
+        let snapshot = vorticies.clone();
+        let field = self.evaluator.prepare(&snapshot, &self.kernel);
+        vorticies
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(index, vortex)| {
+                *vortex = dynamics.interact(vortex, index, &snapshot, &field, dt);
+            });
+    }
+}
+
+fn reset(vortex: &mut Vortex, lifetimes: (f32, f32), vorticities: (f32, f32)) {
+    let (min_lifetime, max_lifetime) = lifetimes;
+    let (min_vorticity, max_vorticity) = vorticities;
+    let random_lifetime = rand::random::<f32>() * (max_lifetime - min_lifetime) + min_lifetime;
+    vortex.position = random_inside_unit_sphere().extend(1.0);
+    vortex.vorticity = random_inside_sphere(min_vorticity, max_vorticity).extend(1.0);
+    vortex.lifetime.x = vortex.lifetime.y;
+    vortex.lifetime.y = random_lifetime;
+    vortex.lifetime.w = 0.0;
+}
+
+fn update_non_mirror(vortex: &mut Vortex, dt: f32, lifetimes: (f32, f32), vorticities: (f32, f32)) {
+    vortex.lifetime.x -= dt;
+    if vortex.lifetime.x <= 0.0 {
+        if vortex.lifetime.z == SHED {
+            vortex.vorticity = Vector3::zero().extend(0.0);
+            vortex.lifetime.x = 0.0;
+        } else {
+            reset(vortex, lifetimes, vorticities);
+        }
+    }
+}
+
+fn mirror_position(
+    p: Vector3<f32>,
+    point_on_plane: Vector3<f32>,
+    normal: Vector3<f32>,
+) -> Vector3<f32> {
+    p - 2.0 * (p - point_on_plane).dot(normal) * normal
+}
+
+fn mirror_direction(d: Vector3<f32>, normal: Vector3<f32>) -> Vector3<f32> {
+    d - 2.0 * d.dot(normal) * normal
+}
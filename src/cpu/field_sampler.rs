@@ -0,0 +1,62 @@
+use cgmath::{Matrix3, Vector3, Zero};
+use rayon::prelude::*;
+
+use crate::{
+    physics::{kernel::Kernel, treecode::Evaluator, vortex_identification::GridSamples},
+    structures::{field_sample::FieldSample, grid::Grid, vortex::Vortex},
+};
+
+/// CPU counterpart of `FieldSampler`, evaluating the flow of any set of vorticies, usually the
+/// active vorticies followed by the current boundary vorticies in the world frame.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CpuFieldSampler {
+    pub kernel: Kernel,
+    /// Used for the velocities, the gradients are always summed directly
+    pub evaluator: Evaluator,
+}
+
+impl CpuFieldSampler {
+    pub fn new(kernel: Kernel) -> CpuFieldSampler {
+        CpuFieldSampler {
+            kernel,
+            evaluator: Evaluator::default(),
+        }
+    }
+
+    pub fn with_evaluator(mut self, evaluator: Evaluator) -> CpuFieldSampler {
+        self.evaluator = evaluator;
+        self
+    }
+
+    pub fn velocities(&self, points: &[Vector3<f32>], sources: &[Vortex]) -> Vec<Vector3<f32>> {
+        self.evaluator.get_velocities(points, sources, &self.kernel)
+    }
+
+    pub fn gradients(&self, points: &[Vector3<f32>], sources: &[Vortex]) -> Vec<Matrix3<f32>> {
+        points
+            .par_iter()
+            .map(|point| {
+                sources.iter().fold(Matrix3::zero(), |gradient, source| {
+                    gradient + self.kernel.get_velocity_gradient(*point, source)
+                })
+            })
+            .collect()
+    }
+
+    /// Velocity and velocity gradient at every point of `points`.
+    pub fn sample(&self, points: &[Vector3<f32>], sources: &[Vortex]) -> Vec<FieldSample> {
+        self.velocities(points, sources)
+            .into_iter()
+            .zip(self.gradients(points, sources))
+            .map(|(velocity, gradient)| FieldSample { velocity, gradient })
+            .collect()
+    }
+
+    /// `sample` at every point of `grid`.
+    pub fn sample_grid(&self, grid: Grid, sources: &[Vortex]) -> GridSamples {
+        GridSamples {
+            grid,
+            samples: self.sample(&grid.points(), sources),
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use cgmath::{InnerSpace, Vector3, Vector4, Zero};
+use rayon::prelude::*;
+
+use crate::{
+    objects::particles::Particles,
+    physics::{
+        diffusion::ScalarDiffusion, inertia::Inertia, integrator::Integrator, kernel::Kernel,
+        treecode::Evaluator,
+    },
+    structures::{particle::Particle, particle_emitter::ParticleEmitter, vortex::Vortex},
+    support::camera::PerspectiveCamera,
+    traits::{drawable::Drawable, steppable::Steppable},
+    util::random_inside_unit_sphere,
+};
+
+use super::active_vorticies::SharedVorticies;
+
+/// CPU counterpart of `Particles`, advecting the tracers like `particle.comp` does.
+pub struct CpuParticles {
+    pub particles: Vec<Particle>,
+    pub vorticies: SharedVorticies,
+    pub number_of_particles: usize,
+    pub resetting_enabled: bool,
+    pub fading_enabled: bool,
+    pub min_lifetime: f32,
+    pub max_lifetime: f32,
+    pub kernel: Kernel,
+    pub integrator: Integrator,
+    pub evaluator: Evaluator,
+    pub emitters: Vec<ParticleEmitter>,
+    pub scalar_diffusion: ScalarDiffusion,
+    pub inertia: Inertia,
+}
+
+impl CpuParticles {
+    pub fn new(particles: Vec<Particle>, vorticies: SharedVorticies) -> CpuParticles {
+        CpuParticles {
+            number_of_particles: particles.len(),
+            particles,
+            vorticies,
+            resetting_enabled: true,
+            fading_enabled: true,
+            min_lifetime: 0.0,
+            max_lifetime: 10.0,
+            kernel: Kernel::default(),
+            integrator: Integrator::default(),
+            evaluator: Evaluator::default(),
+            emitters: vec![],
+            scalar_diffusion: ScalarDiffusion::default(),
+            inertia: Inertia::default(),
+        }
+    }
+
+    pub fn new_inside_unit_sphere(n: usize, vorticies: SharedVorticies) -> CpuParticles {
+        CpuParticles::new(
+            Particles::get_random_particles_inside_unit_sphere(n),
+            vorticies,
+        )
+    }
+
+    pub fn new_on_unit_sphere(n: usize, vorticies: SharedVorticies) -> CpuParticles {
+        CpuParticles::new(Particles::get_random_particles_on_unit_sphere(n), vorticies)
+    }
+
+    pub fn with_resetting(mut self, resetting_enabled: bool) -> CpuParticles {
+        self.resetting_enabled = resetting_enabled;
+        self
+    }
+
+    pub fn with_fading(mut self, fading_enabled: bool) -> CpuParticles {
+        self.fading_enabled = fading_enabled;
+        self
+    }
+
+    pub fn with_kernel(mut self, kernel: Kernel) -> CpuParticles {
+        self.kernel = kernel;
+        self
+    }
+
+    pub fn with_integrator(mut self, integrator: Integrator) -> CpuParticles {
+        self.integrator = integrator;
+        self
+    }
+
+    pub fn with_evaluator(mut self, evaluator: Evaluator) -> CpuParticles {
+        self.evaluator = evaluator;
+        self
+    }
+
+    pub fn with_lifetime(mut self, min_lifetime: f32, max_lifetime: f32) -> CpuParticles {
+        self.min_lifetime = min_lifetime;
+        self.max_lifetime = max_lifetime;
+        self
+    }
+
+    pub fn with_emitters(mut self, emitters: Vec<ParticleEmitter>) -> CpuParticles {
+        self.emitters = emitters;
+        self
+    }
+
+    pub fn with_scalar_diffusion(mut self, scalar_diffusion: ScalarDiffusion) -> CpuParticles {
+        self.scalar_diffusion = scalar_diffusion;
+        self
+    }
+
+    pub fn with_inertia(mut self, inertia: Inertia) -> CpuParticles {
+        self.inertia = inertia;
+        self
+    }
+
+    /// The scalars after one step of particle strength exchange, all read from before it.
+    fn get_diffused_scalars(&self, dt: f32) -> Vec<Vector4<f32>> {
+        let diffusion = &self.scalar_diffusion;
+        let particles = self.particles.as_slice();
+        particles
+            .par_iter()
+            .map(|a| {
+                let exchange = particles.iter().fold(Vector4::zero(), |sum, b| {
+                    let distance = (b.position - a.position).truncate().magnitude();
+                    sum + (b.scalars - a.scalars) * diffusion.exchange_factor(distance)
+                });
+                a.scalars + exchange * dt
+            })
+            .collect()
+    }
+}
+
+impl Drawable for CpuParticles {
+    fn draw(&self, _camera: &PerspectiveCamera) {}
+}
+
+impl Steppable for CpuParticles {
+    fn step(&mut self, dt: f32, _camera: &PerspectiveCamera) {
+        let vorticies = self.vorticies.borrow();
+        let vorticies = vorticies.as_slice();
+        let resetting_enabled = self.resetting_enabled;
+        let lifetimes = (self.min_lifetime, self.max_lifetime);
+        let integrator = self.integrator;
+        let field = self.evaluator.prepare(vorticies, &self.kernel);
+        let emitters = self.emitters.as_slice();
+        let inertia = self.inertia;
+
+        if self.scalar_diffusion.enabled() {
+            let scalars = self.get_diffused_scalars(dt);
+            for (particle, scalars) in self.particles.iter_mut().zip(scalars) {
+                particle.scalars = scalars;
+            }
+        }
+
+        self.particles.par_iter_mut().for_each(|particle| {
+            if resetting_enabled {
+                particle.lifetime.x -= dt;
+                if particle.lifetime.x <= 0.0 {
+                    reset(particle, lifetimes, emitters, inertia.enabled());
+                    return;
+                }
+            }
+
+            let particle_position = particle.position.truncate();
+            if resetting_enabled && is_too_close(particle_position, vorticies) {
+                reset(particle, lifetimes, emitters, inertia.enabled());
+                return;
+            }
+
+            if inertia.enabled() {
+                let (new_position, velocity) = inertia.integrate(
+                    integrator,
+                    particle_position,
+                    particle.velocity.truncate(),
+                    dt,
+                    |point| field.get_velocity(point, None),
+                );
+                particle.velocity = velocity.extend(0.0);
+                particle.position = new_position.extend(1.0);
+                return;
+            }
+
+            let new_position = integrator.integrate(particle_position, dt, |point| {
+                field.get_velocity(point, None)
+            });
+
+            particle.velocity = ((new_position - particle_position) / dt).extend(0.0);
+            particle.position = new_position.extend(1.0);
+        });
+    }
+}
+
+/// Inertial particles respawn at rest.
+fn reset(
+    particle: &mut Particle,
+    lifetimes: (f32, f32),
+    emitters: &[ParticleEmitter],
+    inertial: bool,
+) {
+    let (min_lifetime, max_lifetime) = lifetimes;
+    let new_lifetime = rand::random::<f32>() * (max_lifetime - min_lifetime) + min_lifetime;
+    let mut position = random_inside_unit_sphere();
+    if !emitters.is_empty() {
+        let emitter = emitters[rand::random::<usize>() % emitters.len()];
+        position = emitter.center() + position * emitter.radius();
+        particle.scalars = emitter.scalars;
+    }
+    particle.position = position.extend(1.0);
+    if inertial {
+        particle.velocity = Vector4::zero();
+    }
+    particle.lifetime.x = new_lifetime;
+    particle.lifetime.y = new_lifetime;
+}
+
+fn is_too_close(particle_position: Vector3<f32>, vorticies: &[Vortex]) -> bool {
+    vorticies
+        .iter()
+        .any(|vortex| (vortex.position.truncate() - particle_position).magnitude() < 0.01)
+}
@@ -7,7 +7,10 @@ use crate::gl;
 
 pub struct Geometry {
     vao: u32,
+    vbo: u32,
     ibo: u32,
+    /// Vertex normals at attribute location 1, for meshes that have them
+    nbo: Option<u32>,
     index_length: usize,
 }
 
@@ -19,11 +22,27 @@ impl Geometry {
         Geometry::load_data_to_ibo(vao, ibo, indices.clone());
         Geometry {
             vao,
+            vbo,
             ibo,
+            nbo: None,
             index_length: indices.len(),
         }
     }
 
+    /// Replaces the vertices and indices, reusing the buffers, for meshes that change every
+    /// frame.
+    pub fn update(&mut self, vertices: Vec<Vector4<f32>>, indices: Vec<u32>) {
+        self.index_length = indices.len();
+        Geometry::load_data_to_vbo(self.vao, self.vbo, vertices);
+        Geometry::load_data_to_ibo(self.vao, self.ibo, indices);
+    }
+
+    /// Sets the normals of the vertices, in the order of the last vertices given.
+    pub fn update_normals(&mut self, normals: Vec<Vector4<f32>>) {
+        let nbo = *self.nbo.get_or_insert_with(Geometry::create_buffer);
+        Geometry::load_data_to_attribute(self.vao, nbo, 1, normals);
+    }
+
     pub fn new_square(vao: u32) -> Geometry {
         let vertices: Vec<Vector4<f32>> = vec![
             Vector4::new(-1., -1., 0.999999f32, 1.),
@@ -48,24 +67,28 @@ impl Geometry {
     }
 
     fn load_data_to_vbo(vao: u32, vbo: u32, vertices: Vec<Vector4<f32>>) {
+        Geometry::load_data_to_attribute(vao, vbo, 0, vertices);
+    }
+
+    fn load_data_to_attribute(vao: u32, buffer: u32, location: u32, data: Vec<Vector4<f32>>) {
         unsafe {
             gl::BindVertexArray(vao);
-            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+            gl::BindBuffer(gl::ARRAY_BUFFER, buffer);
             gl::BufferData(
                 gl::ARRAY_BUFFER,
-                (vertices.len() * std::mem::size_of::<Vector4<f32>>()) as isize,
-                vertices.as_ptr().cast(),
+                (data.len() * std::mem::size_of::<Vector4<f32>>()) as isize,
+                data.as_ptr().cast(),
                 gl::STATIC_DRAW,
             );
             gl::VertexAttribPointer(
-                0,
+                location,
                 4,
                 gl::FLOAT,
                 gl::FALSE,
                 (size_of::<Vector4<f32>>()).try_into().unwrap(),
                 std::ptr::null(),
             );
-            gl::EnableVertexAttribArray(0);
+            gl::EnableVertexAttribArray(location);
             gl::BindBuffer(gl::ARRAY_BUFFER, 0);
             gl::BindVertexArray(0);
         }
@@ -8,6 +8,7 @@ use crate::{
         active_vorticies::ActiveVorticies,
         particles::{self},
     },
+    physics::kernel::Kernel,
     support::camera::PerspectiveCamera,
     traits::{drawable::Drawable, steppable::Steppable},
 };
@@ -21,9 +22,11 @@ pub mod gl {
 }
 
 pub mod compute_shader_program;
+pub mod cpu;
 pub mod extensions;
 pub mod geometry;
 pub mod objects;
+pub mod physics;
 pub mod shader_program;
 pub mod structures;
 pub mod support;
@@ -38,7 +41,8 @@ fn main() {
 
     let boundary_model_path = "resources/models/cube_normal";
 
-    let mut scene = objects::scene::Scene::new();
+    let kernel = Kernel::default();
+    let mut scene = objects::scene::Scene::new().with_kernel(kernel);
     // scene.add(
     //     Particles::new(
     //         positions
@@ -58,7 +62,11 @@ fn main() {
             .with_resetting(true),
     );
     // scene.add(objects::boundary_vorticies::BoundaryVorticies::new(
+    //     &scene,
     //     boundary_model_path,
+    //     objects::boundary_vorticies::BoundaryDiscretization::default(),
+    //     physics::boundary_solver::BoundarySolver::default(),
+    //     physics::boundary_condition::BoundaryCondition::default(),
     // ));
     // scene.add(
     //     vorticies::Vorticies::new_random(1024, 5.0, 10.0, 0.0, 0.1)
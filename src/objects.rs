@@ -1,9 +1,15 @@
 pub mod active_vorticies;
+pub mod boundary_body;
 pub mod boundary_info_stepper;
 pub mod boundary_mesh;
 pub mod boundary_vorticies;
 pub mod cube_geometry;
+pub mod field_sampler;
+pub mod flow_lines;
+pub mod isosurface;
 pub mod particles;
 pub mod scene;
+pub mod surface_pressure;
 pub mod test_sphere;
 pub mod texture;
+pub mod vortex_shedder;
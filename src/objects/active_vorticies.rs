@@ -1,11 +1,16 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fs, ops::Range};
 
 use cgmath::{Array, Vector3, Vector4};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 use crate::{
     compute_shader_program::ComputeShaderProgram,
     geometry::Geometry,
     gl,
+    physics::{
+        buoyancy::Buoyancy, diffusion::Diffusion, integrator::Integrator, shedding::Shedding,
+        stretching::StretchingScheme,
+    },
     shader_program::ShaderProgram,
     structures::vortex::Vortex,
     support::camera::PerspectiveCamera,
@@ -13,10 +18,18 @@ use crate::{
     util::{self, random_inside_sphere, random_inside_unit_sphere, random_on_unit_sphere},
 };
 
+/// Stages of `vortex.comp`, each reading the state the previous one wrote.
+const SPAWN: u32 = 0;
+const INTERACT: u32 = 1;
+
 pub struct ActiveVorticies {
     pub shader_program: ShaderProgram,
     pub compute_program: ComputeShaderProgram,
-    pub ssbo: u32,
+    /// The state is read from `ssbos[current_ssbo]` at binding 2 and written to the other
+    /// one at binding 4, swapped after every pass.
+    pub ssbos: [u32; 2],
+    pub current_ssbo: usize,
+    pub rng: StdRng,
     pub geometry: Geometry,
     pub mirror_number: usize,
     pub number_of_vorticies: usize,
@@ -25,6 +38,12 @@ pub struct ActiveVorticies {
     pub max_lifetime: f32,
     pub min_vorticity: f32,
     pub max_vorticity: f32,
+    pub stretching_scheme: StretchingScheme,
+    pub diffusion: Diffusion,
+    pub buoyancy: Buoyancy,
+    pub integrator: Integrator,
+    /// Non mirror slots reserved for `Shedding`
+    pub shedding_slots: Range<usize>,
 }
 
 impl ActiveVorticies {
@@ -42,8 +61,35 @@ impl ActiveVorticies {
             "resources/shaders/vortex.frag",
             HashMap::new(),
         );
-        let compute_program_vortex =
-            ComputeShaderProgram::new("resources/shaders/vortex.comp", HashMap::new());
+        let compute_program_vortex = ComputeShaderProgram::new(
+            "resources/shaders/vortex.comp",
+            HashMap::from([
+                (
+                    "get_velocity",
+                    fs::read_to_string("resources/gpu_methods/get_velocity.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+                (
+                    "panel",
+                    fs::read_to_string("resources/gpu_methods/panel.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+                (
+                    "integrate",
+                    fs::read_to_string("resources/gpu_methods/integrate.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+                (
+                    "get_tiled_velocity",
+                    fs::read_to_string("resources/gpu_methods/get_tiled_velocity.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+            ]),
+        );
 
         //Expand the vortices to double the size
         let mut vorticies = vorticies.clone();
@@ -54,8 +100,9 @@ impl ActiveVorticies {
             },
         );
 
-        let ssbo = util::create_buffer();
-        ActiveVorticies::load_data_to_ssbo(ssbo, &vorticies);
+        let ssbos = [util::create_buffer(), util::create_buffer()];
+        ActiveVorticies::load_data_to_ssbo(ssbos[0], &vorticies, 2);
+        ActiveVorticies::load_data_to_ssbo(ssbos[1], &vorticies, 4);
 
         let vao = util::create_vao();
 
@@ -67,7 +114,9 @@ impl ActiveVorticies {
             shader_program: vortex_program,
             geometry,
             compute_program: compute_program_vortex,
-            ssbo,
+            ssbos,
+            current_ssbo: 0,
+            rng: StdRng::from_entropy(),
             mirror_number,
             number_of_vorticies,
             fading_enabled: true,
@@ -75,6 +124,11 @@ impl ActiveVorticies {
             max_lifetime,
             min_vorticity,
             max_vorticity,
+            stretching_scheme: StretchingScheme::default(),
+            diffusion: Diffusion::default(),
+            buoyancy: Buoyancy::default(),
+            integrator: Integrator::default(),
+            shedding_slots: 0..0,
         }
     }
 
@@ -104,7 +158,7 @@ impl ActiveVorticies {
         )
     }
 
-    fn get_random_vorticies(
+    pub fn get_random_vorticies(
         n: usize,
         min_lifetime: f32,
         max_lifetime: f32,
@@ -132,7 +186,7 @@ impl ActiveVorticies {
             .collect()
     }
 
-    fn load_data_to_ssbo(ssbo: u32, vorticies: &Vec<Vortex>) {
+    fn load_data_to_ssbo(ssbo: u32, vorticies: &Vec<Vortex>, binding: u32) {
         unsafe {
             gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, ssbo);
             gl::BufferData(
@@ -141,14 +195,86 @@ impl ActiveVorticies {
                 vorticies.as_ptr().cast(),
                 gl::DYNAMIC_DRAW,
             );
-            gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, 2, ssbo);
+            gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, binding, ssbo);
+        }
+    }
+
+    fn swap_ssbos(&mut self) {
+        self.current_ssbo = 1 - self.current_ssbo;
+        unsafe {
+            gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, 2, self.ssbos[self.current_ssbo]);
+            gl::BindBufferBase(
+                gl::SHADER_STORAGE_BUFFER,
+                4,
+                self.ssbos[1 - self.current_ssbo],
+            );
         }
     }
 
+    pub fn get_vorticies(&self) -> Vec<Vortex> {
+        let mut vorticies = vec![Vortex::default(); self.number_of_vorticies];
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.ssbos[self.current_ssbo]);
+            gl::GetBufferSubData(
+                gl::SHADER_STORAGE_BUFFER,
+                0,
+                (vorticies.len() * std::mem::size_of::<Vortex>()) as isize,
+                vorticies.as_mut_ptr().cast(),
+            );
+        }
+        vorticies
+    }
+
     pub fn with_fading(mut self, fading_enabled: bool) -> ActiveVorticies {
         self.fading_enabled = fading_enabled;
         self
     }
+
+    pub fn with_stretching(mut self, stretching_scheme: StretchingScheme) -> ActiveVorticies {
+        self.stretching_scheme = stretching_scheme;
+        self
+    }
+
+    pub fn with_diffusion(mut self, diffusion: Diffusion) -> ActiveVorticies {
+        self.diffusion = diffusion;
+        self
+    }
+
+    pub fn with_buoyancy(mut self, buoyancy: Buoyancy) -> ActiveVorticies {
+        self.buoyancy = buoyancy;
+        self
+    }
+
+    pub fn with_integrator(mut self, integrator: Integrator) -> ActiveVorticies {
+        self.integrator = integrator;
+        self
+    }
+
+    /// Appends `capacity` dormant slots to the non mirror vorticies for `Shedding` to fill,
+    /// see `shedding_slots`.
+    pub fn with_shedding_capacity(mut self, capacity: usize) -> ActiveVorticies {
+        let number_of_non_mirror = self.number_of_vorticies / (self.mirror_number + 1);
+        let mut vorticies = self.get_vorticies();
+        vorticies.truncate(number_of_non_mirror);
+        vorticies.extend(vec![Shedding::dormant_vortex(); capacity]);
+        self.shedding_slots = number_of_non_mirror..vorticies.len();
+        vorticies.resize(
+            (self.mirror_number + 1) * vorticies.len(),
+            Vortex::default(),
+        );
+
+        self.current_ssbo = 0;
+        ActiveVorticies::load_data_to_ssbo(self.ssbos[0], &vorticies, 2);
+        ActiveVorticies::load_data_to_ssbo(self.ssbos[1], &vorticies, 4);
+        self.number_of_vorticies = vorticies.len();
+        self
+    }
+
+    /// Seeds the random mirror planes, so runs from the same initial state are reproducible.
+    pub fn with_seed(mut self, seed: u64) -> ActiveVorticies {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
 }
 
 impl Drawable for ActiveVorticies {
@@ -179,9 +305,9 @@ impl Steppable for ActiveVorticies {
     fn step(&mut self, dt: f32, _camera: &PerspectiveCamera) {
         unsafe {
             let random_vector = Vector3::<f32> {
-                x: rand::random::<f32>(),
-                y: rand::random::<f32>(),
-                z: rand::random::<f32>(),
+                x: self.rng.gen::<f32>(),
+                y: self.rng.gen::<f32>(),
+                z: self.rng.gen::<f32>(),
             };
             self.compute_program.use_program();
 
@@ -196,8 +322,30 @@ impl Steppable for ActiveVorticies {
             gl::Uniform1f(4, self.max_lifetime);
             gl::Uniform1f(5, self.min_vorticity);
             gl::Uniform1f(6, self.max_vorticity);
-            gl::DispatchCompute((self.number_of_vorticies / 256).max(1) as u32, 1, 1);
-            gl::MemoryBarrier(gl::SHADER_IMAGE_ACCESS_BARRIER_BIT);
+            gl::Uniform1ui(7, self.stretching_scheme as u32);
+            gl::Uniform1f(8, self.diffusion.viscosity);
+            gl::Uniform1f(9, self.diffusion.core_radius);
+            gl::Uniform1f(10, self.diffusion.particle_volume);
+            gl::Uniform1ui(11, self.integrator as u32);
+            let buoyancy = self.buoyancy.gravity * self.buoyancy.expansion;
+            gl::Uniform3fv(13, 1, buoyancy.as_ptr());
+            gl::Uniform1f(14, self.buoyancy.core_radius);
+            gl::Uniform1f(15, self.buoyancy.particle_volume);
+            gl::Uniform1f(16, self.buoyancy.diffusivity);
+            let heat_source = self.buoyancy.heat_source;
+            gl::Uniform4fv(
+                17,
+                1,
+                heat_source.position.extend(heat_source.radius).as_ptr(),
+            );
+            gl::Uniform1f(18, heat_source.rate);
+
+            for stage in [SPAWN, INTERACT] {
+                gl::Uniform1ui(12, stage);
+                gl::DispatchCompute(util::number_of_workgroups(self.number_of_vorticies), 1, 1);
+                gl::MemoryBarrier(gl::SHADER_STORAGE_BARRIER_BIT);
+                self.swap_ssbos();
+            }
         }
     }
 }
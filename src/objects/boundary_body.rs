@@ -0,0 +1,126 @@
+use cgmath::Vector3;
+
+use crate::{
+    physics::{
+        forces::ForceHistory,
+        kinematics::{BodyVelocity, Kinematics},
+        rigid_body::RigidBody,
+    },
+    structures::{transform::Transform, vortex::Vortex},
+};
+
+use super::boundary_vorticies::{BoundaryDiscretization, BoundaryVorticies};
+
+/// One rigid body of `BoundaryVorticies`, its vortex sets in the body frame and where it is.
+#[derive(Debug, Clone)]
+pub struct BoundaryBody {
+    /// Alternative sets of boundary vorticies, one of them active per step.
+    pub vorticies: Vec<Vec<Vortex>>,
+    /// How `vorticies` were placed, which tells face areas from vertex weights in `normal.w`
+    pub discretization: BoundaryDiscretization,
+    /// Where the body is now
+    pub transform: Transform,
+    /// Where `kinematics` starts from
+    pub initial_transform: Transform,
+    pub kinematics: Kinematics,
+    /// Moves the body with the force of the fluid instead of `kinematics`
+    pub rigid_body: Option<RigidBody>,
+    /// Velocity of the body now, the velocity the boundary condition is relative to
+    pub velocity: BodyVelocity,
+    /// Force and moment of the fluid on the body, recorded every step
+    pub force_history: Option<ForceHistory>,
+}
+
+impl BoundaryBody {
+    /// The sets of the glTF files in `model_path`, see
+    /// `BoundaryVorticies::create_vorticies_from_folder_path`.
+    pub fn new(model_path: &str, discretization: BoundaryDiscretization) -> BoundaryBody {
+        BoundaryBody::from_vorticies(BoundaryVorticies::create_vorticies_from_folder_path(
+            model_path,
+            discretization,
+        ))
+        .with_discretization(discretization)
+    }
+
+    pub fn from_vorticies(vorticies: Vec<Vec<Vortex>>) -> BoundaryBody {
+        BoundaryBody {
+            vorticies,
+            discretization: BoundaryDiscretization::default(),
+            transform: Transform::default(),
+            initial_transform: Transform::default(),
+            kinematics: Kinematics::default(),
+            rigid_body: None,
+            velocity: BodyVelocity::default(),
+            force_history: None,
+        }
+    }
+
+    pub fn with_discretization(mut self, discretization: BoundaryDiscretization) -> BoundaryBody {
+        self.discretization = discretization;
+        self
+    }
+
+    pub fn with_transform(mut self, transform: Transform) -> BoundaryBody {
+        self.initial_transform = transform;
+        self.move_to(0.0);
+        self
+    }
+
+    pub fn with_kinematics(mut self, kinematics: Kinematics) -> BoundaryBody {
+        self.kinematics = kinematics;
+        self.move_to(0.0);
+        self
+    }
+
+    /// The body starts from rest at its transform.
+    pub fn with_rigid_body(mut self, rigid_body: RigidBody) -> BoundaryBody {
+        self.rigid_body = Some(rigid_body);
+        self.velocity = BodyVelocity::default();
+        self
+    }
+
+    pub fn with_force_history(mut self, force_history: ForceHistory) -> BoundaryBody {
+        self.force_history = Some(force_history);
+        self
+    }
+
+    /// Whether the body ever leaves its transform.
+    pub fn is_moving(&self) -> bool {
+        self.rigid_body.is_some() || !self.kinematics.is_static()
+    }
+
+    /// Advances the body to `time`, `dt` after the last call. A `rigid_body` is pushed by
+    /// `vorticies`, its boundary vorticies, and `free`, the active vorticies, in the world
+    /// frame, which also make the sample of `force_history` at the time of the last call.
+    pub fn step(&mut self, vorticies: &[Vortex], free: &[Vortex], time: f32, dt: f32) {
+        if let Some(history) = &mut self.force_history {
+            let (transform, velocity) = (&self.transform, &self.velocity);
+            history.record(time - dt, vorticies, free, transform, velocity, dt);
+        }
+        match &mut self.rigid_body {
+            Some(rigid_body) => {
+                rigid_body.step(vorticies, free, &mut self.transform, &mut self.velocity, dt)
+            }
+            None => self.move_to(time),
+        }
+    }
+
+    /// Places the body where `kinematics` has it at `time`.
+    pub fn move_to(&mut self, time: f32) {
+        self.transform = self.kinematics.transform_at(&self.initial_transform, time);
+        self.velocity = self.kinematics.velocity_at(time);
+    }
+
+    /// Velocity of the body point at the world position `point`.
+    pub fn velocity_at(&self, point: Vector3<f32>) -> Vector3<f32> {
+        self.velocity.at(point, self.transform.translation)
+    }
+
+    /// Set `set` in the world frame.
+    pub fn world_vorticies(&self, set: usize) -> Vec<Vortex> {
+        self.vorticies[set]
+            .iter()
+            .map(|vortex| self.transform.to_world(vortex))
+            .collect()
+    }
+}
@@ -1,7 +1,5 @@
 use std::collections::HashMap;
 
-use nalgebra::DMatrix;
-
 use crate::{
     compute_shader_program::ComputeShaderProgram,
     gl::{self},
@@ -13,35 +11,48 @@ use crate::{
 
 pub struct BoundaryInfoStepper {
     compute_program: ComputeShaderProgram,
+    ssbo: u32,
+    number_of_bodies: usize,
 }
 
 impl BoundaryInfoStepper {
-    pub fn new(matricies: Vec<DMatrix<f32>>) -> BoundaryInfoStepper {
+    /// Uploads the `BoundaryInfo` of every body to binding 50.
+    pub fn new(infos: &[BoundaryInfo]) -> BoundaryInfoStepper {
         let compute_program = ComputeShaderProgram::new(
             "resources/shaders/boundary_info_stepper.comp",
             HashMap::new(),
         );
         let ssbo = util::create_buffer();
-        BoundaryInfoStepper::load_info_to_ssbo(
+        BoundaryInfoStepper::load_info_to_ssbo(ssbo, infos);
+
+        BoundaryInfoStepper {
+            compute_program,
             ssbo,
-            BoundaryInfo {
-                active_index: 0,
-                active_count: matricies.first().unwrap().column_iter().len() as i32,
-                count: matricies.len() as i32,
-            },
-        );
+            number_of_bodies: infos.len(),
+        }
+    }
 
-        BoundaryInfoStepper { compute_program }
+    /// Overwrites binding 50 with `infos`, for bodies moved on the CPU.
+    pub fn load_infos(&self, infos: &[BoundaryInfo]) {
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.ssbo);
+            gl::BufferSubData(
+                gl::SHADER_STORAGE_BUFFER,
+                0,
+                std::mem::size_of_val(infos) as isize,
+                infos.as_ptr().cast(),
+            );
+        }
     }
 
-    fn load_info_to_ssbo(ssbo: u32, info: BoundaryInfo) {
+    fn load_info_to_ssbo(ssbo: u32, infos: &[BoundaryInfo]) {
         unsafe {
             gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, ssbo);
-            let size = std::mem::size_of::<BoundaryInfo>();
+            let size = std::mem::size_of_val(infos);
             gl::BufferData(
                 gl::SHADER_STORAGE_BUFFER,
                 size as isize,
-                &info as *const BoundaryInfo as *const _,
+                infos.as_ptr().cast(),
                 gl::DYNAMIC_DRAW,
             );
             gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, 50, ssbo);
@@ -57,7 +68,7 @@ impl Steppable for BoundaryInfoStepper {
     fn step(&mut self, _dt: f32, _camera: &PerspectiveCamera) {
         self.compute_program.use_program();
         unsafe {
-            gl::DispatchCompute(1, 1, 1);
+            gl::DispatchCompute(util::number_of_workgroups(self.number_of_bodies), 1, 1);
             gl::MemoryBarrier(gl::SHADER_STORAGE_BARRIER_BIT);
         }
     }
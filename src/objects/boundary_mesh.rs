@@ -1,11 +1,12 @@
-use std::{collections::HashMap, fs};
+use std::{cell::RefCell, collections::HashMap, fs, rc::Rc};
 
-use cgmath::{Matrix, Matrix4, Vector3};
+use cgmath::Matrix;
 
 use crate::{
     geometry::Geometry,
     gl,
     shader_program::ShaderProgram,
+    structures::transform::{SharedTransform, Transform},
     support::camera::PerspectiveCamera,
     traits::{drawable::Drawable, steppable::Steppable},
     util::{self},
@@ -16,6 +17,8 @@ pub struct BoundaryMesh {
     pub geometry: Geometry,
     pub vao: u32,
     pub hover: bool,
+    /// Where the mesh is drawn, shared with the body it shows
+    pub transform: SharedTransform,
 }
 
 impl BoundaryMesh {
@@ -36,14 +39,27 @@ impl BoundaryMesh {
             geometry,
             vao,
             hover: false,
+            transform: Rc::new(RefCell::new(Transform::default())),
         }
     }
+
+    pub fn with_transform(mut self, transform: Transform) -> BoundaryMesh {
+        self.transform = Rc::new(RefCell::new(transform));
+        self
+    }
+
+    /// Draws the mesh wherever the body behind `transform` is, see
+    /// `BoundaryVorticies::shared_transform`.
+    pub fn with_shared_transform(mut self, transform: SharedTransform) -> BoundaryMesh {
+        self.transform = transform;
+        self
+    }
 }
 
 impl Drawable for BoundaryMesh {
     fn draw(&self, camera: &PerspectiveCamera) {
         self.program.use_program();
-        let model_matrix = Matrix4::from_translation(Vector3::new(0.0, 0.0, 0.0)).transpose();
+        let model_matrix = self.transform.borrow().matrix().transpose();
         let mvp = model_matrix * camera.view_proj_matrix;
         unsafe {
             self.program.bind_uniform_matrix4fv("mvp", &mvp[0][0]);
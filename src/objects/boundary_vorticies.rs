@@ -1,13 +1,21 @@
-use std::{collections::HashMap, fs, path::Path};
+use std::{cell::RefCell, collections::HashMap, fs, io, ops::Range, path::Path, rc::Rc};
 
-use cgmath::{num_traits::Pow, InnerSpace, Vector3, Vector4, Zero};
+use cgmath::{InnerSpace, Vector3, Vector4, Zero};
 
 use crate::{
     compute_shader_program::ComputeShaderProgram,
     geometry::Geometry,
     gl::{self},
+    physics::{
+        boundary_condition::BoundaryCondition, boundary_solver::BoundarySolver, kernel::Kernel,
+        panel, pressure::Pressure, shedding::Shedding, treecode::Evaluator,
+    },
     shader_program::ShaderProgram,
-    structures::vortex::Vortex,
+    structures::{
+        computed_inverse::ComputedInverse,
+        transform::SharedTransform,
+        vortex::{BoundaryInfo, Vortex},
+    },
     support::camera::PerspectiveCamera,
     traits::{drawable::Drawable, steppable::Steppable},
     util::{self},
@@ -16,29 +24,111 @@ use nalgebra::DMatrix;
 
 use itertools::Itertools;
 
-use super::{boundary_info_stepper::BoundaryInfoStepper, texture::Texture};
+use super::{
+    boundary_body::BoundaryBody, boundary_info_stepper::BoundaryInfoStepper, scene::Scene,
+    surface_pressure::SurfacePressure, texture::Texture, vortex_shedder::VortexShedder,
+};
+
+/// Tolerance of the boundary matrix pseudo-inverse, part of the cache checksum.
+pub const PSEUDO_INVERSE_TOLERANCE: f32 = 0.0001;
+
+/// Where the pseudo-inverses are persisted, one file per checksum.
+pub const INVERSE_CACHE_DIR: &str = "cache/boundary_inverses";
+
+/// Triangles smaller than this are left out of `BoundaryDiscretization::Panels`.
+const MIN_TRIANGLE_AREA: f32 = 1e-8;
+
+/// Where the boundary vorticies of a glTF mesh are placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoundaryDiscretization {
+    /// One vortex per vertex. Vertices duplicated at hard edges give coincident vorticies.
+    #[default]
+    Vertices,
+    /// One constant vortex sheet panel per triangle, collocated at its centroid along the
+    /// face normal, with the face area as its weight, see `physics::panel`.
+    Panels,
+}
+
+/// What the boundary vorticies are drawn with. The discriminants are the values
+/// `boundary_vortex.vert` switches on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorMode {
+    #[default]
+    Uniform = 0,
+    /// `SurfacePressure::coefficients`, blue below zero to red above, saturating at `±1`.
+    /// Bodies with several sets show the pressure of the set solved last.
+    Pressure = 1,
+}
 
+/// Every boundary body of a scene. The bodies share the boundary bindings, so a scene holds
+/// at most one `BoundaryVorticies`: binding 10 has the vortex sets of all bodies in their body
+/// frames, binding 11 the current set of each in the world frame, which `particle.comp`
+/// sees, and binding 50 one `BoundaryInfo` per body.
 pub struct BoundaryVorticies {
     shader_program: ShaderProgram,
     compute_program_error: ComputeShaderProgram,
     compute_program_correction: ComputeShaderProgram,
+    compute_program_transform: ComputeShaderProgram,
     geometry: Geometry,
-    vorticies: Vec<Vec<Vortex>>,
-    active_index: usize,
+    bodies: Vec<BoundaryBody>,
+    /// Where every body is, see `shared_transform`
+    transforms: Vec<SharedTransform>,
+    /// What the shaders see in binding 50, advanced along with `stepper`.
+    infos: Vec<BoundaryInfo>,
     ssbo_errors: u32,
     stepper: BoundaryInfoStepper,
     texture: Texture,
+    kernel: Kernel,
+    solver: BoundarySolver,
+    boundary_condition: BoundaryCondition,
+    ssbo_vorticies: u32,
+    ssbo_current: u32,
+    /// Last corrections of every set of every body, the warm start of
+    /// `BoundarySolver::Iterative`.
+    corrections: Vec<Vec<Vec<Vector3<f32>>>>,
+    /// Time the bodies' `Kinematics` are at
+    time: f32,
+    shedder: Option<VortexShedder>,
+    surface_pressure: Option<SurfacePressure>,
+    color_mode: ColorMode,
 }
 
 //Unit test ami megmondja egy függvény kimenetéről hogy Koumbusz Kristóf életének szövege-e
 
 impl BoundaryVorticies {
-    pub fn new(model_path: &str) -> Self {
-        let vorticies = BoundaryVorticies::create_vorticies_from_folder_path(model_path);
-        BoundaryVorticies::from_vorticies(vorticies)
+    /// The boundary takes the kernel of `scene`, so the precomputed corrections cancel the
+    /// velocity the shaders measure.
+    pub fn new(
+        scene: &Scene,
+        model_path: &str,
+        discretization: BoundaryDiscretization,
+        solver: BoundarySolver,
+        boundary_condition: BoundaryCondition,
+    ) -> Self {
+        let body = BoundaryBody::new(model_path, discretization);
+        BoundaryVorticies::from_bodies(scene, vec![body], solver, boundary_condition)
     }
 
-    pub fn from_vorticies(vorticies: Vec<Vec<Vortex>>) -> Self {
+    pub fn from_vorticies(
+        scene: &Scene,
+        vorticies: Vec<Vec<Vortex>>,
+        solver: BoundarySolver,
+        boundary_condition: BoundaryCondition,
+    ) -> Self {
+        let body = BoundaryBody::from_vorticies(vorticies);
+        BoundaryVorticies::from_bodies(scene, vec![body], solver, boundary_condition)
+    }
+
+    /// Each body cancels the velocity of the active vorticies and of the other bodies at its
+    /// control points. The inverses are computed in the body frames, so bodies sharing a mesh
+    /// share the cached inverse.
+    pub fn from_bodies(
+        scene: &Scene,
+        bodies: Vec<BoundaryBody>,
+        solver: BoundarySolver,
+        boundary_condition: BoundaryCondition,
+    ) -> Self {
+        let kernel = scene.kernel();
         let shader_program = ShaderProgram::new(
             "Boundary Vortex",
             "resources/shaders/boundary_vortex.vert",
@@ -47,30 +137,92 @@ impl BoundaryVorticies {
         );
         let compute_program_error = ComputeShaderProgram::new(
             "resources/shaders/boundary_vortex_error.comp",
-            HashMap::from([(
-                "get_velocity",
-                fs::read_to_string("resources/gpu_methods/get_velocity.glsl")
-                    .unwrap()
-                    .as_str(),
-            )]),
+            HashMap::from([
+                (
+                    "get_velocity",
+                    fs::read_to_string("resources/gpu_methods/get_velocity.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+                (
+                    "panel",
+                    fs::read_to_string("resources/gpu_methods/panel.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+                (
+                    "get_tiled_velocity",
+                    fs::read_to_string("resources/gpu_methods/get_tiled_velocity.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+            ]),
         );
-        let matricies = BoundaryVorticies::create_matricies_from_vorticies(&vorticies);
+        // The iterative solver never needs the dense inverse.
+        let matricies = match solver {
+            BoundarySolver::PseudoInverse => bodies
+                .iter()
+                .flat_map(|body| {
+                    BoundaryVorticies::create_matricies_from_vorticies(
+                        &body.vorticies,
+                        &kernel,
+                        boundary_condition,
+                    )
+                })
+                .collect(),
+            BoundarySolver::Iterative(_) => vec![],
+        };
 
         let compute_program_correction = ComputeShaderProgram::new(
             "resources/shaders/boundary_vortex_correction.comp",
             HashMap::new(),
         );
+        let compute_program_transform = ComputeShaderProgram::new(
+            "resources/shaders/boundary_vortex_transform.comp",
+            HashMap::from([(
+                "panel",
+                fs::read_to_string("resources/gpu_methods/panel.glsl")
+                    .unwrap()
+                    .as_str(),
+            )]),
+        );
+        let infos = BoundaryVorticies::create_infos(&bodies, !matricies.is_empty());
+        let number_of_current = bodies.iter().map(|b| b.vorticies[0].len()).sum();
+
         let ssbo_vorticies = util::create_buffer();
-        BoundaryVorticies::load_vorticies_to_ssbo(ssbo_vorticies, &vorticies);
+        let vorticies = bodies
+            .iter()
+            .flat_map(|body| body.vorticies.iter().cloned())
+            .collect::<Vec<_>>();
+        BoundaryVorticies::load_vorticies_to_ssbo(ssbo_vorticies, &vorticies, 10);
+        let ssbo_current = util::create_buffer();
+        BoundaryVorticies::load_vorticies_to_ssbo(
+            ssbo_current,
+            &[vec![Vortex::default(); number_of_current]],
+            11,
+        );
         let ssbo_matrix = util::create_buffer();
         BoundaryVorticies::load_matrix_to_ssbo(ssbo_matrix, &matricies);
 
         let ssbo_errors = util::create_buffer();
-        BoundaryVorticies::load_errors_to_ssbo(ssbo_errors, vorticies[0].len());
+        BoundaryVorticies::load_errors_to_ssbo(ssbo_errors, number_of_current);
 
         let vao = util::create_vao();
         let geometry = Geometry::from_gltf("resources/models/arrow.glb", vao);
-        let stepper = BoundaryInfoStepper::new(matricies);
+        let stepper = BoundaryInfoStepper::new(&infos);
+        let transforms = bodies
+            .iter()
+            .map(|body| Rc::new(RefCell::new(body.transform)))
+            .collect();
+        let corrections = bodies
+            .iter()
+            .map(|body| {
+                body.vorticies
+                    .iter()
+                    .map(|v| vec![Vector3::zero(); v.len()])
+                    .collect()
+            })
+            .collect();
 
         unsafe {
             let texture = Texture::new();
@@ -78,42 +230,128 @@ impl BoundaryVorticies {
                 .load(Path::new("resources/textures/kor.png"))
                 .unwrap();
 
-            BoundaryVorticies {
+            let boundary_vorticies = BoundaryVorticies {
                 shader_program,
                 geometry,
                 compute_program_error,
                 compute_program_correction,
-                vorticies,
+                compute_program_transform,
+                bodies,
+                transforms,
+                infos,
                 ssbo_errors,
-                active_index: 0,
                 stepper,
                 texture,
+                kernel,
+                solver,
+                boundary_condition,
+                ssbo_vorticies,
+                ssbo_current,
+                corrections,
+                time: 0.0,
+                shedder: None,
+                surface_pressure: None,
+                color_mode: ColorMode::default(),
+            };
+            for body in 0..boundary_vorticies.bodies.len() {
+                boundary_vorticies.step_transform(body);
+            }
+            boundary_vorticies
+        }
+    }
+
+    /// Releases part of the corrected boundary vorticity into the active vorticies every step,
+    /// see `Shedding`.
+    pub fn with_shedding(mut self, shedding: Shedding) -> Self {
+        self.shedder = Some(VortexShedder::new(shedding, &self.bodies));
+        self
+    }
+
+    /// Recovers the surface pressure of the bodies every step, see `SurfacePressure`, and
+    /// colours the boundary vorticies by it. The recovery runs on the CPU with the kernel of
+    /// the boundary and sums over every active vortex, and the surface jumps need
+    /// `BoundaryDiscretization::Panels`.
+    pub fn with_pressure(mut self, pressure: Pressure) -> Self {
+        let pressure = pressure.with_kernel(self.kernel);
+        self.surface_pressure = Some(SurfacePressure::new(pressure, &self.bodies));
+        self.color_mode = ColorMode::Pressure;
+        self
+    }
+
+    pub fn with_color_mode(mut self, color_mode: ColorMode) -> Self {
+        self.color_mode = color_mode;
+        self
+    }
+
+    /// Lays the bodies out one after the other in every binding.
+    fn create_infos(bodies: &[BoundaryBody], with_matricies: bool) -> Vec<BoundaryInfo> {
+        let mut infos = vec![];
+        let (mut offset, mut current_offset, mut matrix_offset) = (0, 0, 0);
+        for body in bodies {
+            let active_count = body.vorticies[0].len();
+            let count = body.vorticies.len();
+            infos.push(BoundaryInfo {
+                transform: body.transform.matrix(),
+                velocity: body.velocity.linear.extend(0.0),
+                angular_velocity: body.velocity.angular.extend(0.0),
+                active_index: 0,
+                active_count: active_count as u32,
+                count: count as u32,
+                offset: offset as u32,
+                current_offset: current_offset as u32,
+                matrix_offset: matrix_offset as u32,
+                padding: [0; 2],
+            });
+            offset += active_count * count;
+            current_offset += active_count;
+            if with_matricies {
+                matrix_offset += 9 * active_count * active_count * count;
             }
         }
+        infos
     }
 
-    pub fn create_vorticies_from_folder_path(folder_path: &str) -> Vec<Vec<Vortex>> {
+    pub fn create_vorticies_from_folder_path(
+        folder_path: &str,
+        discretization: BoundaryDiscretization,
+    ) -> Vec<Vec<Vortex>> {
         std::fs::read_dir(folder_path)
             .unwrap()
             .map(|entry| {
                 let path = entry.unwrap().path();
                 let path = path.to_str().unwrap();
-                let positions = util::get_vertices_and_normals_from_gltf(path);
-                BoundaryVorticies::vorticies_from_positions(positions)
+                match discretization {
+                    BoundaryDiscretization::Vertices => {
+                        let positions = util::get_vertices_and_normals_from_gltf(path);
+                        BoundaryVorticies::vorticies_from_positions(positions)
+                    }
+                    BoundaryDiscretization::Panels => {
+                        let triangles = util::get_triangles_from_gltf(path);
+                        BoundaryVorticies::panels_from_triangles(triangles)
+                    }
+                }
             })
             .collect::<Vec<_>>()
     }
 
-    pub fn create_matricies_from_vorticies(vorticies: &Vec<Vec<Vortex>>) -> Vec<DMatrix<f32>> {
+    pub fn create_matricies_from_vorticies(
+        vorticies: &Vec<Vec<Vortex>>,
+        kernel: &Kernel,
+        boundary_condition: BoundaryCondition,
+    ) -> Vec<DMatrix<f32>> {
         vorticies
             .iter()
             .map(|vorticies| {
-                let matrix = BoundaryVorticies::create_matrix(vorticies);
-                let current_time = std::time::Instant::now();
-                let matrix: DMatrix<f32> = matrix.pseudo_inverse(0.0001f32).unwrap().transpose();
-                // .purify();
-                println!("Inversion time: {}", current_time.elapsed().as_secs_f32());
-                matrix
+                let checksum = ComputedInverse::checksum(
+                    vorticies,
+                    kernel,
+                    boundary_condition,
+                    PSEUDO_INVERSE_TOLERANCE,
+                );
+                let path = Path::new(INVERSE_CACHE_DIR).join(format!("{:08x}.postcard", checksum));
+                ComputedInverse::load_or_compute(&path, checksum, || {
+                    BoundaryVorticies::create_inverse(vorticies, kernel, boundary_condition)
+                })
             })
             .collect::<Vec<DMatrix<f32>>>()
     }
@@ -125,13 +363,45 @@ impl BoundaryVorticies {
             .iter()
             .map(|(position, normal)| Vortex {
                 position: position.extend(1.),
+                // Meshes without normals leave them zero, which `BoundaryCondition::Slip`
+                // does not constrain. `w` is the unit weight of a vertex.
+                normal: if normal.magnitude2() > 0. {
+                    normal.normalize().extend(1.)
+                } else {
+                    Vector4::new(0., 0., 0., 1.)
+                },
                 vorticity: (normal.normalize() / 10.).extend(1.),
                 ..Default::default()
             })
             .collect::<Vec<Vortex>>()
     }
 
-    fn load_vorticies_to_ssbo(ssbo: u32, vorticies: &[Vec<Vortex>]) {
+    /// Panels at the triangle centroids with the face normal, following the winding, the
+    /// face area in `normal.w` and the corners in `lifetime`, see `panel::pack_corners`.
+    pub fn panels_from_triangles(triangles: Vec<[Vector3<f32>; 3]>) -> Vec<Vortex> {
+        triangles
+            .iter()
+            .filter_map(|[a, b, c]| {
+                let cross = (b - a).cross(c - a);
+                let area = cross.magnitude() * 0.5;
+                (area > MIN_TRIANGLE_AREA).then(|| {
+                    let normal = cross.normalize();
+                    let centroid = (a + b + c) / 3.;
+                    Vortex {
+                        position: centroid.extend(1.),
+                        normal: normal.extend(area),
+                        vorticity: (normal / 10.).extend(1.),
+                        lifetime: panel::pack_corners(
+                            normal,
+                            [a - centroid, b - centroid, c - centroid],
+                        ),
+                    }
+                })
+            })
+            .collect::<Vec<Vortex>>()
+    }
+
+    fn load_vorticies_to_ssbo(ssbo: u32, vorticies: &[Vec<Vortex>], binding: u32) {
         unsafe {
             gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, ssbo);
             let vorticies = vorticies.iter().flatten().cloned().collect::<Vec<_>>();
@@ -142,7 +412,7 @@ impl BoundaryVorticies {
                 vorticies.as_ptr().cast(),
                 gl::DYNAMIC_DRAW,
             );
-            gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, 10, ssbo);
+            gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, binding, ssbo);
         }
     }
 
@@ -175,7 +445,17 @@ impl BoundaryVorticies {
         }
     }
 
-    pub fn create_matrix(vorticies: &Vec<Vortex>) -> DMatrix<f32> {
+    /// Negated transpose of the influence matrix `A`, whose block `(b, a)` maps the strength
+    /// per unit weight of `a`, its sheet strength for panels, to the velocity
+    /// `boundary_condition` constrains at the control point of `b`. The pseudo-inverse of
+    /// this layout is what `calculate_corrections` and `boundary_vortex_correction.comp`
+    /// expect, see `create_inverse` for weighted elements. For `NoSlip` and unit weights `A`
+    /// is symmetric.
+    pub fn create_matrix(
+        vorticies: &Vec<Vortex>,
+        kernel: &Kernel,
+        boundary_condition: BoundaryCondition,
+    ) -> DMatrix<f32> {
         let length = vorticies.len();
         let mut effect_matrix = DMatrix::<f32>::zeros(length * 3, length * 3);
         for ((ai, a), (bi, b)) in vorticies
@@ -183,45 +463,80 @@ impl BoundaryVorticies {
             .enumerate()
             .cartesian_product(vorticies.iter().enumerate())
         {
-            // if ai == bi {
-            //     continue;
-            // }
-            for (i, j) in (0..3).cartesian_product(0..3) {
-                if i == j {
-                    continue;
-                }
+            for i in 0..3 {
                 let mut unit = Vector3::zero();
                 unit[i] = 1.0f32;
-                effect_matrix[(ai * 3 + i, bi * 3 + j)] = BoundaryVorticies::get_velocity(
-                    &a,
-                    &Vortex {
-                        vorticity: unit.extend(0.0f32),
-                        ..*b
-                    },
-                )[j];
+                let velocity = boundary_condition.constrained(
+                    BoundaryVorticies::get_velocity(
+                        b,
+                        &Vortex {
+                            vorticity: unit.extend(0.0f32),
+                            ..*a
+                        },
+                        kernel,
+                    ),
+                    b.normal.truncate(),
+                ) * a.normal.w;
+                for j in 0..3 {
+                    effect_matrix[(ai * 3 + i, bi * 3 + j)] = -velocity[j];
+                }
             }
         }
         effect_matrix
     }
 
+    /// Pseudo-inverse of `create_matrix` with its columns scaled by the element weights, so
+    /// `calculate_corrections` gives vortex strengths rather than strengths per unit weight.
+    pub fn create_inverse(
+        vorticies: &Vec<Vortex>,
+        kernel: &Kernel,
+        boundary_condition: BoundaryCondition,
+    ) -> DMatrix<f32> {
+        let mut inverse = BoundaryVorticies::create_matrix(vorticies, kernel, boundary_condition)
+            .pseudo_inverse(PSEUDO_INVERSE_TOLERANCE)
+            .unwrap();
+        for (i, vortex) in vorticies.iter().enumerate() {
+            for j in 0..3 {
+                inverse.column_mut(i * 3 + j).scale_mut(vortex.normal.w);
+            }
+        }
+        inverse
+    }
+
     pub fn create_error_vector(
         vorticies: &[Vortex],
         active_vorticies: &[Vortex],
         should_check_boundary: bool,
+        kernel: &Kernel,
     ) -> Vec<Vector3<f32>> {
-        let mut error: Vec<Vector3<f32>> = vec![];
-        for a in vorticies.iter() {
-            let mut sum = Vector3::new(0.0f32, 0.0f32, 0.0f32);
-            if should_check_boundary {
-                for b in vorticies.iter() {
-                    sum += BoundaryVorticies::get_velocity(a, b);
-                }
-            }
+        BoundaryVorticies::create_error_vector_with_evaluator(
+            vorticies,
+            active_vorticies,
+            should_check_boundary,
+            kernel,
+            &Evaluator::Direct,
+        )
+    }
 
-            for b in active_vorticies {
-                sum += BoundaryVorticies::get_velocity(a, b);
+    /// `create_error_vector` with the Biot–Savart sums done by `evaluator`, for meshes
+    /// and vortex counts where the all-pairs loop is too slow.
+    pub fn create_error_vector_with_evaluator(
+        vorticies: &[Vortex],
+        active_vorticies: &[Vortex],
+        should_check_boundary: bool,
+        kernel: &Kernel,
+        evaluator: &Evaluator,
+    ) -> Vec<Vector3<f32>> {
+        let points = vorticies
+            .iter()
+            .map(|v| v.position.truncate())
+            .collect::<Vec<_>>();
+        let mut error = evaluator.get_velocities(&points, active_vorticies, kernel);
+        if should_check_boundary {
+            let boundary = evaluator.get_velocities(&points, vorticies, kernel);
+            for (e, b) in error.iter_mut().zip(boundary) {
+                *e += b;
             }
-            error.push(sum);
         }
         error
     }
@@ -253,32 +568,128 @@ impl BoundaryVorticies {
         corrections
     }
 
-    fn get_velocity(a: &Vortex, b: &Vortex) -> Vector3<f32> {
-        let diff = (b.position - a.position).truncate();
-        let distance = diff.magnitude();
-        if distance < 0.0001f32 {
-            return Vector3::zero();
-        }
-        b.vorticity.truncate().cross(diff / (distance.pow(3)))
+    fn get_velocity(a: &Vortex, b: &Vortex, kernel: &Kernel) -> Vector3<f32> {
+        kernel.get_velocity(a.position.truncate(), b)
     }
 
-    fn current_length(&self) -> usize {
-        self.vorticies[self.active_index].len()
+    pub fn kernel(&self) -> Kernel {
+        self.kernel
     }
 
+    pub fn boundary_condition(&self) -> BoundaryCondition {
+        self.boundary_condition
+    }
+
+    pub fn bodies(&self) -> &[BoundaryBody] {
+        &self.bodies
+    }
+
+    /// Handle to where `body` is, updated every step, for the `BoundaryMesh` or
+    /// `TestSphere` drawing it.
+    pub fn shared_transform(&self, body: usize) -> SharedTransform {
+        Rc::clone(&self.transforms[body])
+    }
+
+    fn number_of_current(&self) -> usize {
+        self.infos
+            .iter()
+            .map(|info| info.active_count as usize)
+            .sum()
+    }
+
+    /// The errors at the current control points of every body, each in its body frame.
     pub fn get_errors(&self) -> Vec<Vector3<f32>> {
-        let mut errors = vec![Vector4::<f32>::zero(); self.current_length()];
+        self.get_errors_in(0..self.number_of_current())
+    }
+
+    /// The errors of the current vorticies in `range`, reading back only those.
+    pub fn get_errors_in(&self, range: Range<usize>) -> Vec<Vector3<f32>> {
+        let mut errors = vec![Vector4::<f32>::zero(); range.len()];
         unsafe {
+            gl::MemoryBarrier(gl::BUFFER_UPDATE_BARRIER_BIT);
             gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.ssbo_errors);
             gl::GetBufferSubData(
                 gl::SHADER_STORAGE_BUFFER,
-                0,
+                (range.start * std::mem::size_of::<Vector4<f32>>()) as isize,
                 (errors.len() * std::mem::size_of::<Vector4<f32>>()) as isize,
                 errors.as_mut_ptr().cast(),
             );
         }
         errors.iter().map(|v| v.truncate()).collect::<Vec<_>>()
     }
+
+    pub fn surface_pressure(&self) -> Option<&SurfacePressure> {
+        self.surface_pressure.as_ref()
+    }
+
+    /// Writes the `BoundaryBody::force_history` of every body that has one to
+    /// `folder/body_{index}.csv`.
+    pub fn save_force_histories(&self, folder: &Path) -> io::Result<()> {
+        for (index, body) in self.bodies.iter().enumerate() {
+            if let Some(history) = &body.force_history {
+                history.save_csv(&folder.join(format!("body_{index}.csv")))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The current set of every body in the world frame, as in binding 11.
+    pub fn get_current_vorticies(&self) -> Vec<Vortex> {
+        let mut vorticies = vec![Vortex::default(); self.number_of_current()];
+        unsafe {
+            gl::MemoryBarrier(gl::BUFFER_UPDATE_BARRIER_BIT);
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.ssbo_current);
+            gl::GetBufferSubData(
+                gl::SHADER_STORAGE_BUFFER,
+                0,
+                std::mem::size_of_val(vorticies.as_slice()) as isize,
+                vorticies.as_mut_ptr().cast(),
+            );
+        }
+        vorticies
+    }
+
+    /// CPU counterpart of the correction sweep of `step` on the first set of every body:
+    /// in turn each body cancels the velocity of `active_vorticies` and of the current
+    /// strengths of the others relative to its own `BoundaryBody::velocity`. `inverses` are
+    /// the `create_inverse` of the sets in the body frames.
+    pub fn correct_bodies(
+        bodies: &mut [BoundaryBody],
+        inverses: &[DMatrix<f32>],
+        active_vorticies: &[Vortex],
+        kernel: &Kernel,
+        boundary_condition: BoundaryCondition,
+    ) {
+        for body in 0..bodies.len() {
+            let sources = active_vorticies
+                .iter()
+                .cloned()
+                .chain(
+                    bodies
+                        .iter()
+                        .enumerate()
+                        .filter(|(other, _)| *other != body)
+                        .flat_map(|(_, other)| other.world_vorticies(0)),
+                )
+                .collect::<Vec<_>>();
+            let world = bodies[body].world_vorticies(0);
+            let errors = BoundaryVorticies::create_error_vector(&world, &sources, false, kernel)
+                .into_iter()
+                .zip(&world)
+                .map(|(error, vortex)| error - bodies[body].velocity_at(vortex.position.truncate()))
+                .collect::<Vec<_>>();
+            let transform = bodies[body].transform;
+            let errors = boundary_condition
+                .constrain_errors(&world, &errors)
+                .into_iter()
+                .map(|error| transform.direction_to_body(error))
+                .collect();
+            let corrections = BoundaryVorticies::calculate_corrections(&inverses[body], &errors);
+            for (vortex, correction) in bodies[body].vorticies[0].iter_mut().zip(corrections) {
+                vortex.vorticity = correction.extend(vortex.vorticity.w);
+            }
+        }
+    }
 }
 
 impl Drawable for BoundaryVorticies {
@@ -297,43 +708,169 @@ impl Drawable for BoundaryVorticies {
                     w: 1f32,
                 }],
             );
-            self.geometry.draw_instanced(self.current_length() as i32);
+            let color_mode = match self.surface_pressure {
+                Some(_) => self.color_mode,
+                None => ColorMode::Uniform,
+            };
+            self.shader_program
+                .bind_uniform_1ui("color_mode", color_mode as u32);
+            self.geometry
+                .draw_instanced(self.number_of_current() as i32);
         };
     }
 }
 
 impl Steppable for BoundaryVorticies {
     fn step(&mut self, dt: f32, camera: &PerspectiveCamera) {
-        self.step_errors(dt);
-        self.step_correction(dt);
+        // Gauss–Seidel over the bodies, each sees the corrections of the ones before.
+        for body in 0..self.bodies.len() {
+            self.step_errors(body, dt);
+            self.step_correction(body, dt);
+        }
+        if self.surface_pressure.is_some() {
+            let current = self.get_current_vorticies();
+            if let Some(surface_pressure) = &mut self.surface_pressure {
+                surface_pressure.step(self.time, &self.bodies, &self.infos, &current);
+            }
+        }
+        if let Some(shedder) = &mut self.shedder {
+            shedder.shed(&self.infos, dt);
+        }
         // let stats = crate::support::magnitude_statistics::MagnitudeStatistics::from_vectors(
         //     &self.get_errors(),
         // );
         // println!("Stats: {:?}", stats);
         self.stepper.step(dt, camera);
+        for info in &mut self.infos {
+            info.active_index = (info.active_index + 1) % info.count;
+        }
+        self.time += dt;
+        self.move_bodies(dt);
+        for body in 0..self.bodies.len() {
+            self.step_transform(body);
+        }
     }
 }
 
 impl BoundaryVorticies {
-    pub fn step_errors(&mut self, dt: f32) {
+    pub fn step_errors(&mut self, body: usize, dt: f32) {
         unsafe {
             self.compute_program_error.use_program();
 
             gl::Uniform1f(0, dt);
+            gl::Uniform1ui(1, self.boundary_condition as u32);
+            gl::Uniform1ui(2, body as u32);
 
-            gl::DispatchCompute(self.current_length() as u32, 1, 1);
-            gl::MemoryBarrier(gl::SHADER_IMAGE_ACCESS_BARRIER_BIT);
+            let count = self.infos[body].active_count as usize;
+            gl::DispatchCompute(util::number_of_workgroups(count), 1, 1);
+            gl::MemoryBarrier(gl::SHADER_STORAGE_BARRIER_BIT);
         }
     }
 
-    pub fn step_correction(&mut self, dt: f32) {
+    /// Solves for the current set of `body` and refreshes its world frame copy.
+    pub fn step_correction(&mut self, body: usize, dt: f32) {
+        match self.solver {
+            BoundarySolver::PseudoInverse => unsafe {
+                self.compute_program_correction.use_program();
+
+                gl::Uniform1f(0, dt);
+                gl::Uniform1ui(1, body as u32);
+
+                let count = self.infos[body].active_count as usize;
+                gl::DispatchCompute(util::number_of_workgroups(count), 1, 1);
+                gl::MemoryBarrier(gl::SHADER_STORAGE_BARRIER_BIT);
+            },
+            BoundarySolver::Iterative(solver) => {
+                let info = self.infos[body];
+                let set = info.active_index as usize;
+                let start = info.current_offset as usize;
+                let errors = self.get_errors_in(start..start + info.active_count as usize);
+                let solution = solver.solve(
+                    &self.bodies[body].vorticies[set],
+                    &errors,
+                    &self.corrections[body][set],
+                    &self.kernel,
+                    self.boundary_condition,
+                );
+                self.corrections[body][set] = solution.corrections;
+                self.load_corrections_to_ssbo(body);
+            }
+        }
+        self.step_transform(body);
+    }
+
+    /// Moves the bodies with `Kinematics` to the current time, pushes the ones with a
+    /// `RigidBody` with their corrected boundary vorticies and hands their placement and
+    /// velocity to the shaders and the shared transforms.
+    fn move_bodies(&mut self, dt: f32) {
+        let forces =
+            |body: &BoundaryBody| body.rigid_body.is_some() || body.force_history.is_some();
+        if !self
+            .bodies
+            .iter()
+            .any(|body| body.is_moving() || forces(body))
+        {
+            return;
+        }
+        let (current, free) = if self.bodies.iter().any(forces) {
+            (
+                self.get_current_vorticies(),
+                util::get_bound_buffer::<Vortex>(2),
+            )
+        } else {
+            (vec![], vec![])
+        };
+        for ((body, info), transform) in self
+            .bodies
+            .iter_mut()
+            .zip(&mut self.infos)
+            .zip(&self.transforms)
+        {
+            let start = info.current_offset as usize;
+            let vorticies = current
+                .get(start..start + info.active_count as usize)
+                .unwrap_or_default();
+            body.step(vorticies, &free, self.time, dt);
+            *transform.borrow_mut() = body.transform;
+            info.transform = body.transform.matrix();
+            info.velocity = body.velocity.linear.extend(0.0);
+            info.angular_velocity = body.velocity.angular.extend(0.0);
+        }
+        self.stepper.load_infos(&self.infos);
+    }
+
+    /// Writes the current set of `body` in the world frame to binding 11.
+    fn step_transform(&self, body: usize) {
         unsafe {
-            self.compute_program_correction.use_program();
+            self.compute_program_transform.use_program();
 
-            gl::Uniform1f(0, dt);
+            gl::Uniform1ui(0, body as u32);
+
+            let count = self.infos[body].active_count as usize;
+            gl::DispatchCompute(util::number_of_workgroups(count), 1, 1);
+            gl::MemoryBarrier(gl::SHADER_STORAGE_BARRIER_BIT);
+        }
+    }
 
-            gl::DispatchCompute(self.current_length() as u32, 1, 1);
-            gl::MemoryBarrier(gl::SHADER_IMAGE_ACCESS_BARRIER_BIT);
+    /// Writes the corrections of the current set of `body` into its vorticities, like
+    /// `boundary_vortex_correction.comp` does for the pseudo-inverse.
+    fn load_corrections_to_ssbo(&mut self, body: usize) {
+        let info = self.infos[body];
+        let set = info.active_index as usize;
+        let vorticies = &mut self.bodies[body].vorticies[set];
+        for (vortex, correction) in vorticies.iter_mut().zip(&self.corrections[body][set]) {
+            vortex.vorticity = correction.extend(vortex.vorticity.w);
+        }
+        let size = std::mem::size_of::<Vortex>();
+        let first = info.offset as usize + set * vorticies.len();
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.ssbo_vorticies);
+            gl::BufferSubData(
+                gl::SHADER_STORAGE_BUFFER,
+                (first * size) as isize,
+                (vorticies.len() * size) as isize,
+                vorticies.as_ptr().cast(),
+            );
         }
     }
 }
@@ -0,0 +1,115 @@
+use std::{collections::HashMap, fs};
+
+use cgmath::{Matrix3, Vector3, Vector4};
+
+use crate::{
+    compute_shader_program::ComputeShaderProgram,
+    gl::{self},
+    physics::vortex_identification::GridSamples,
+    structures::{field_sample::FieldSample, grid::Grid},
+    util::{self},
+};
+
+/// Evaluates the flow of the scene at arbitrary points with `field_sampler.comp`: the active
+/// vorticies in binding 2 and the current boundary vorticies in binding 11, with the kernel
+/// the scene uploads. Points go through binding 14 and samples come back through binding 15.
+pub struct FieldSampler {
+    compute_program: ComputeShaderProgram,
+    ssbo_points: u32,
+    ssbo_samples: u32,
+}
+
+impl FieldSampler {
+    pub fn new() -> FieldSampler {
+        let compute_program = ComputeShaderProgram::new(
+            "resources/shaders/field_sampler.comp",
+            HashMap::from([
+                (
+                    "get_velocity",
+                    fs::read_to_string("resources/gpu_methods/get_velocity.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+                (
+                    "panel",
+                    fs::read_to_string("resources/gpu_methods/panel.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+            ]),
+        );
+        FieldSampler {
+            compute_program,
+            ssbo_points: util::create_buffer(),
+            ssbo_samples: util::create_buffer(),
+        }
+    }
+
+    /// Velocity and velocity gradient at every point of `points`.
+    pub fn sample(&self, points: &[Vector3<f32>]) -> Vec<FieldSample> {
+        if points.is_empty() {
+            return vec![];
+        }
+        let points = points
+            .iter()
+            .map(|point| point.extend(1.0))
+            .collect::<Vec<_>>();
+        // Every sample is a velocity and three gradient columns.
+        let mut data = vec![Vector4::new(0.0, 0.0, 0.0, 0.0); 4 * points.len()];
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.ssbo_points);
+            gl::BufferData(
+                gl::SHADER_STORAGE_BUFFER,
+                std::mem::size_of_val(points.as_slice()) as isize,
+                points.as_ptr().cast(),
+                gl::DYNAMIC_DRAW,
+            );
+            gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, 14, self.ssbo_points);
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.ssbo_samples);
+            gl::BufferData(
+                gl::SHADER_STORAGE_BUFFER,
+                std::mem::size_of_val(data.as_slice()) as isize,
+                std::ptr::null(),
+                gl::DYNAMIC_READ,
+            );
+            gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, 15, self.ssbo_samples);
+
+            self.compute_program.use_program();
+            gl::Uniform1ui(0, points.len() as u32);
+            gl::DispatchCompute(util::number_of_workgroups(points.len()), 1, 1);
+            gl::MemoryBarrier(gl::BUFFER_UPDATE_BARRIER_BIT);
+
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.ssbo_samples);
+            gl::GetBufferSubData(
+                gl::SHADER_STORAGE_BUFFER,
+                0,
+                std::mem::size_of_val(data.as_slice()) as isize,
+                data.as_mut_ptr().cast(),
+            );
+        }
+        data.chunks(4)
+            .map(|sample| FieldSample {
+                velocity: sample[0].truncate(),
+                gradient: Matrix3::from_cols(
+                    sample[1].truncate(),
+                    sample[2].truncate(),
+                    sample[3].truncate(),
+                ),
+            })
+            .collect()
+    }
+
+    /// `sample` at every point of `grid`.
+    pub fn sample_grid(&self, grid: Grid) -> GridSamples {
+        GridSamples {
+            grid,
+            samples: self.sample(&grid.points()),
+        }
+    }
+}
+
+impl Default for FieldSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
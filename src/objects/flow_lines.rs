@@ -0,0 +1,203 @@
+use std::{collections::HashMap, io, path::Path};
+
+use cgmath::{Vector3, Vector4};
+
+use crate::{
+    gl,
+    physics::flow_lines::{self, Pathlines, Polyline, Streaklines, Streamlines},
+    shader_program::ShaderProgram,
+    support::camera::PerspectiveCamera,
+    traits::{drawable::Drawable, steppable::Steppable},
+    util::{self},
+};
+
+use super::field_sampler::FieldSampler;
+
+/// Which lines `FlowLines` follows.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlowLineKind {
+    /// Retraced from `seeds` every step
+    Streamlines {
+        streamlines: Streamlines,
+        seeds: Vec<Vector3<f32>>,
+    },
+    Pathlines(Pathlines),
+    Streaklines(Streaklines),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LineStyle {
+    #[default]
+    Lines,
+    Tubes {
+        radius: f32,
+        sides: usize,
+    },
+}
+
+/// Streamlines, pathlines or streaklines of the scene, with the velocity from a
+/// `FieldSampler`. It has to step after the vorticies it follows.
+pub struct FlowLines {
+    pub kind: FlowLineKind,
+    pub style: LineStyle,
+    pub color: Vector4<f32>,
+    program: ShaderProgram,
+    sampler: FieldSampler,
+    vao: u32,
+    vbo: u32,
+    ibo: u32,
+    index_count: usize,
+    /// The lines at the last step
+    pub lines: Vec<Polyline>,
+}
+
+impl FlowLines {
+    pub fn new(kind: FlowLineKind) -> FlowLines {
+        let program = ShaderProgram::new(
+            "Flow Lines",
+            "resources/shaders/polyline.vert",
+            "resources/shaders/polyline.frag",
+            HashMap::new(),
+        );
+        let vao = util::create_vao();
+        let vbo = util::create_buffer();
+        let ibo = util::create_buffer();
+        unsafe {
+            gl::BindVertexArray(vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+            gl::VertexAttribPointer(
+                0,
+                4,
+                gl::FLOAT,
+                gl::FALSE,
+                std::mem::size_of::<Vector4<f32>>() as i32,
+                std::ptr::null(),
+            );
+            gl::EnableVertexAttribArray(0);
+            gl::BindVertexArray(0);
+        }
+        FlowLines {
+            kind,
+            style: LineStyle::default(),
+            color: Vector4::new(1.0, 1.0, 1.0, 1.0),
+            program,
+            sampler: FieldSampler::new(),
+            vao,
+            vbo,
+            ibo,
+            index_count: 0,
+            lines: vec![],
+        }
+    }
+
+    pub fn with_style(mut self, style: LineStyle) -> FlowLines {
+        self.style = style;
+        self
+    }
+
+    pub fn with_color(mut self, color: Vector4<f32>) -> FlowLines {
+        self.color = color;
+        self
+    }
+
+    /// Saves the last lines as OBJ, as tubes with the `Tubes` style.
+    pub fn save_obj(&self, path: &Path) -> io::Result<()> {
+        let tube = match self.style {
+            LineStyle::Lines => None,
+            LineStyle::Tubes { radius, sides } => Some((radius, sides)),
+        };
+        flow_lines::save_obj(&self.lines, tube, path)
+    }
+
+    fn upload(&mut self) {
+        let mut vertices = vec![];
+        let mut indices = vec![];
+        for line in &self.lines {
+            let first = vertices.len() as u32;
+            match self.style {
+                LineStyle::Lines => {
+                    vertices.extend(line.points.iter().map(|point| point.extend(1.0)));
+                    for i in 1..line.points.len() as u32 {
+                        indices.extend([first + i - 1, first + i]);
+                    }
+                }
+                LineStyle::Tubes { radius, sides } => {
+                    let (tube_vertices, tube_indices) = line.tube(radius, sides);
+                    vertices.extend(tube_vertices.iter().map(|vertex| vertex.extend(1.0)));
+                    indices.extend(tube_indices.iter().map(|index| first + index));
+                }
+            }
+        }
+        self.index_count = indices.len();
+        unsafe {
+            gl::BindVertexArray(self.vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                std::mem::size_of_val(vertices.as_slice()) as isize,
+                vertices.as_ptr().cast(),
+                gl::DYNAMIC_DRAW,
+            );
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.ibo);
+            gl::BufferData(
+                gl::ELEMENT_ARRAY_BUFFER,
+                std::mem::size_of_val(indices.as_slice()) as isize,
+                indices.as_ptr().cast(),
+                gl::DYNAMIC_DRAW,
+            );
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+impl Drawable for FlowLines {
+    fn draw(&self, camera: &PerspectiveCamera) {
+        if self.index_count == 0 {
+            return;
+        }
+        self.program.use_program();
+        unsafe {
+            self.program
+                .bind_uniform_matrix4fv("mvp", &camera.view_proj_matrix[0][0]);
+            self.program.bind_uniform_4fv("color", vec![self.color]);
+            gl::BindVertexArray(self.vao);
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.ibo);
+            let mode = match self.style {
+                LineStyle::Lines => gl::LINES,
+                LineStyle::Tubes { .. } => gl::TRIANGLES,
+            };
+            gl::DrawElements(
+                mode,
+                self.index_count as i32,
+                gl::UNSIGNED_INT,
+                std::ptr::null(),
+            );
+            gl::BindVertexArray(0);
+        }
+    }
+}
+
+impl Steppable for FlowLines {
+    fn step(&mut self, dt: f32, _camera: &PerspectiveCamera) {
+        let sampler = &self.sampler;
+        let velocity = |points: &[Vector3<f32>]| {
+            sampler
+                .sample(points)
+                .into_iter()
+                .map(|sample| sample.velocity)
+                .collect::<Vec<_>>()
+        };
+        self.lines = match &mut self.kind {
+            FlowLineKind::Streamlines { streamlines, seeds } => streamlines.trace(seeds, velocity),
+            FlowLineKind::Pathlines(pathlines) => {
+                pathlines.step(dt, velocity);
+                pathlines.lines()
+            }
+            FlowLineKind::Streaklines(streaklines) => {
+                streaklines.step(dt, velocity);
+                streaklines.lines.clone()
+            }
+        };
+        self.upload();
+    }
+}
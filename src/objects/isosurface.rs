@@ -0,0 +1,103 @@
+use std::{collections::HashMap, io, path::Path};
+
+use cgmath::Vector4;
+
+use crate::{
+    geometry::Geometry,
+    physics::{isosurface, vortex_identification::Identification},
+    shader_program::ShaderProgram,
+    structures::{grid::Grid, triangle_mesh::TriangleMesh},
+    support::camera::PerspectiveCamera,
+    traits::{drawable::Drawable, steppable::Steppable},
+    util::{self},
+};
+
+use super::field_sampler::FieldSampler;
+
+/// Isosurface of an `Identification` field of the scene, by default the vorticity
+/// magnitude, sampled on `grid` with a `FieldSampler` and extracted again every step. It
+/// has to step after the vorticies it shows.
+pub struct Isosurface {
+    pub grid: Grid,
+    pub identification: Identification,
+    pub iso: f32,
+    pub color: Vector4<f32>,
+    program: ShaderProgram,
+    geometry: Geometry,
+    sampler: FieldSampler,
+    /// The surface at the last step
+    pub mesh: TriangleMesh,
+}
+
+impl Isosurface {
+    pub fn new(grid: Grid, iso: f32) -> Isosurface {
+        let program = ShaderProgram::new(
+            "Isosurface",
+            "resources/shaders/isosurface.vert",
+            "resources/shaders/isosurface.frag",
+            HashMap::new(),
+        );
+        Isosurface {
+            grid,
+            identification: Identification::VorticityMagnitude,
+            iso,
+            color: Vector4::new(0.9, 0.5, 0.2, 1.0),
+            program,
+            geometry: Geometry::new(util::create_vao(), vec![], vec![]),
+            sampler: FieldSampler::new(),
+            mesh: TriangleMesh::default(),
+        }
+    }
+
+    pub fn with_identification(mut self, identification: Identification) -> Isosurface {
+        self.identification = identification;
+        self
+    }
+
+    pub fn with_color(mut self, color: Vector4<f32>) -> Isosurface {
+        self.color = color;
+        self
+    }
+
+    /// Saves the last surface as glTF for paths ending in `.glb`, as OBJ otherwise.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        self.mesh.save(path)
+    }
+}
+
+impl Drawable for Isosurface {
+    fn draw(&self, camera: &PerspectiveCamera) {
+        self.program.use_program();
+        unsafe {
+            self.program
+                .bind_uniform_matrix4fv("mvp", &camera.view_proj_matrix[0][0]);
+        }
+        self.program.bind_uniform_4fv("color", vec![self.color]);
+        self.geometry.draw();
+    }
+}
+
+impl Steppable for Isosurface {
+    fn step(&mut self, _dt: f32, _camera: &PerspectiveCamera) {
+        let volume = self
+            .sampler
+            .sample_grid(self.grid)
+            .volume(self.identification);
+        self.mesh = isosurface::marching_cubes(&volume, self.iso);
+        self.geometry.update(
+            self.mesh
+                .vertices
+                .iter()
+                .map(|vertex| vertex.extend(1.0))
+                .collect(),
+            self.mesh.indices.clone(),
+        );
+        self.geometry.update_normals(
+            self.mesh
+                .normals
+                .iter()
+                .map(|normal| normal.extend(0.0))
+                .collect(),
+        );
+    }
+}
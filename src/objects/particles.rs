@@ -1,13 +1,14 @@
 use std::{collections::HashMap, fs, path::Path};
 
-use cgmath::{Vector3, Vector4};
+use cgmath::{Array, Vector3, Vector4};
 
 use crate::{
     compute_shader_program::ComputeShaderProgram,
     geometry::Geometry,
     gl,
+    physics::{diffusion::ScalarDiffusion, inertia::Inertia, integrator::Integrator},
     shader_program::ShaderProgram,
-    structures::particle::Particle,
+    structures::{particle::Particle, particle_emitter::ParticleEmitter},
     support::camera::PerspectiveCamera,
     traits::{drawable::Drawable, steppable::Steppable},
     util::{self, random_inside_unit_sphere, random_on_unit_sphere},
@@ -15,6 +16,9 @@ use crate::{
 
 use super::texture::Texture;
 
+const DIFFUSE: u32 = 0;
+const ADVECT: u32 = 1;
+
 pub struct Particles {
     pub shader: ShaderProgram,
     pub compute_shader: ComputeShaderProgram,
@@ -25,6 +29,18 @@ pub struct Particles {
     pub resetting_enabled: bool,
     pub fading_enabled: bool,
     pub texture: Texture,
+    pub integrator: Integrator,
+    /// Reset particles respawn at a random one of these with its scalars, or inside the unit
+    /// sphere keeping theirs when there are none.
+    pub emitters: Vec<ParticleEmitter>,
+    pub ssbo_emitters: u32,
+    /// Diffusion of the scalars between the particles.
+    pub scalar_diffusion: ScalarDiffusion,
+    pub ssbo_scalars: u32,
+    pub scalar_coloring: bool,
+    /// Makes the particles droplets or dust lagging behind the flow, keeping their own
+    /// velocity in `velocity` instead of that of the last step.
+    pub inertia: Inertia,
 }
 
 impl Particles {
@@ -37,18 +53,42 @@ impl Particles {
         );
         let compute_shader = ComputeShaderProgram::new(
             "resources/shaders/particle.comp",
-            HashMap::from([(
-                "get_velocity",
-                fs::read_to_string("resources/gpu_methods/get_velocity.glsl")
-                    .unwrap()
-                    .as_str(),
-            )]),
+            HashMap::from([
+                (
+                    "get_velocity",
+                    fs::read_to_string("resources/gpu_methods/get_velocity.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+                (
+                    "panel",
+                    fs::read_to_string("resources/gpu_methods/panel.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+                (
+                    "integrate",
+                    fs::read_to_string("resources/gpu_methods/integrate.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+                (
+                    "get_tiled_velocity",
+                    fs::read_to_string("resources/gpu_methods/get_tiled_velocity.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+            ]),
         );
         let sorting_compute_shader =
             ComputeShaderProgram::new("resources/shaders/particle_sort.comp", HashMap::new());
 
         let ssbo = util::create_buffer();
         Particles::load_data_to_ssbo_particles(ssbo, &particles);
+        let ssbo_emitters = util::create_buffer();
+        Particles::load_data_to_ssbo_emitters(ssbo_emitters, &[]);
+        let ssbo_scalars = util::create_buffer();
+        Particles::allocate_ssbo_scalars(ssbo_scalars, particles.len());
 
         let vao = util::create_vao();
         unsafe {
@@ -66,6 +106,13 @@ impl Particles {
                 resetting_enabled: true,
                 fading_enabled: true,
                 texture,
+                integrator: Integrator::default(),
+                emitters: vec![],
+                ssbo_emitters,
+                scalar_diffusion: ScalarDiffusion::default(),
+                ssbo_scalars,
+                scalar_coloring: false,
+                inertia: Inertia::default(),
             }
         }
     }
@@ -91,7 +138,33 @@ impl Particles {
         }
     }
 
-    fn get_random_particles_inside_unit_sphere(n: usize) -> Vec<Particle> {
+    fn load_data_to_ssbo_emitters(ssbo: u32, emitters: &[ParticleEmitter]) {
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, ssbo);
+            gl::BufferData(
+                gl::SHADER_STORAGE_BUFFER,
+                std::mem::size_of_val(emitters) as isize,
+                emitters.as_ptr().cast(),
+                gl::DYNAMIC_DRAW,
+            );
+            gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, 16, ssbo);
+        }
+    }
+
+    fn allocate_ssbo_scalars(ssbo: u32, number_of_particles: usize) {
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, ssbo);
+            gl::BufferData(
+                gl::SHADER_STORAGE_BUFFER,
+                (number_of_particles * std::mem::size_of::<Vector4<f32>>()) as isize,
+                std::ptr::null(),
+                gl::DYNAMIC_DRAW,
+            );
+            gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, 17, ssbo);
+        }
+    }
+
+    pub fn get_random_particles_inside_unit_sphere(n: usize) -> Vec<Particle> {
         (0..n)
             .map(|_| {
                 let r = rand::random::<f32>() * 10.;
@@ -117,12 +190,13 @@ impl Particles {
                         z: 0f32,
                         w: 0f32,
                     },
+                    ..Default::default()
                 }
             })
             .collect()
     }
 
-    fn get_random_particles_on_unit_sphere(n: usize) -> Vec<Particle> {
+    pub fn get_random_particles_on_unit_sphere(n: usize) -> Vec<Particle> {
         (0..n)
             .map(|_| {
                 let r = rand::random::<f32>() * 10.;
@@ -146,6 +220,7 @@ impl Particles {
                         z: 0f32,
                         w: 0f32,
                     },
+                    ..Default::default()
                 }
             })
             .collect()
@@ -160,6 +235,32 @@ impl Particles {
         self.fading_enabled = fading_enabled;
         self
     }
+
+    pub fn with_integrator(mut self, integrator: Integrator) -> Particles {
+        self.integrator = integrator;
+        self
+    }
+
+    pub fn with_emitters(mut self, emitters: Vec<ParticleEmitter>) -> Particles {
+        Particles::load_data_to_ssbo_emitters(self.ssbo_emitters, &emitters);
+        self.emitters = emitters;
+        self
+    }
+
+    pub fn with_scalar_diffusion(mut self, scalar_diffusion: ScalarDiffusion) -> Particles {
+        self.scalar_diffusion = scalar_diffusion;
+        self
+    }
+
+    pub fn with_scalar_coloring(mut self, scalar_coloring: bool) -> Particles {
+        self.scalar_coloring = scalar_coloring;
+        self
+    }
+
+    pub fn with_inertia(mut self, inertia: Inertia) -> Particles {
+        self.inertia = inertia;
+        self
+    }
 }
 
 impl Drawable for Particles {
@@ -172,6 +273,8 @@ impl Drawable for Particles {
                 .bind_uniform_matrix4fv("viewProjectionMatrix", &camera.view_proj_matrix[0][0]);
             self.shader
                 .bind_uniform_1ui("fading_enabled", self.fading_enabled as u32);
+            self.shader
+                .bind_uniform_1ui("scalar_coloring", self.scalar_coloring as u32);
             // self.shader.bind_uniform_4fv(
             //     "color",
             //     vec![Vector4 {
@@ -200,14 +303,40 @@ impl Steppable for Particles {
             gl::Uniform1f(0, dt);
             gl::Uniform3uiv(1, 1, &random_vector[0]);
             gl::Uniform1ui(2, self.resetting_enabled as u32);
-            gl::DispatchCompute(self.number_of_particles as u32, 1, 1);
-            gl::MemoryBarrier(gl::SHADER_IMAGE_ACCESS_BARRIER_BIT);
+            gl::Uniform1ui(5, self.integrator as u32);
+            gl::Uniform1f(7, self.scalar_diffusion.diffusivity);
+            gl::Uniform1f(8, self.scalar_diffusion.core_radius);
+            gl::Uniform1f(9, self.scalar_diffusion.particle_volume);
+            let (response_time, effective_gravity) = if self.inertia.enabled() {
+                (
+                    self.inertia.response_time(),
+                    self.inertia.effective_gravity(),
+                )
+            } else {
+                (0.0, Vector3::new(0.0, 0.0, 0.0))
+            };
+            gl::Uniform1f(10, response_time);
+            gl::Uniform1ui(11, self.inertia.drag_law as u32);
+            gl::Uniform3fv(12, 1, effective_gravity.as_ptr());
+            gl::Uniform1f(13, self.inertia.diameter);
+            gl::Uniform1f(14, self.inertia.viscosity);
+
+            let stages: &[u32] = if self.scalar_diffusion.enabled() {
+                &[DIFFUSE, ADVECT]
+            } else {
+                &[ADVECT]
+            };
+            for &stage in stages {
+                gl::Uniform1ui(6, stage);
+                gl::DispatchCompute(util::number_of_workgroups(self.number_of_particles), 1, 1);
+                gl::MemoryBarrier(gl::SHADER_STORAGE_BARRIER_BIT);
+            }
 
             // for _i in 0..1024 {
             //     self.sorting_compute_shader.use_program();
             //     gl::UniformMatrix4fv(0, 1, false as u8, &camera.view_proj_matrix[0][0]);
             //     gl::DispatchCompute((self.number_of_particles as f32 / 2.) as u32, 1, 1);
-            //     gl::MemoryBarrier(gl::SHADER_IMAGE_ACCESS_BARRIER_BIT);
+            //     gl::MemoryBarrier(gl::SHADER_STORAGE_BARRIER_BIT);
             // }
         }
     }
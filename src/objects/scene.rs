@@ -1,16 +1,47 @@
 use crate::{
+    gl,
+    physics::kernel::Kernel,
+    structures::kernel_info::KernelInfo,
     support::camera::PerspectiveCamera,
     traits::{drawable::Drawable, object::Object, steppable::Steppable},
+    util,
 };
 
 pub struct Scene {
     objects: Vec<Box<dyn Object>>,
+    kernel: Kernel,
+    kernel_ssbo: u32,
 }
 
+/// Bindings of the current boundary vorticies and of the `BoundaryInfo`s, which
+/// `particle.comp`, `vortex.comp`, `field_sampler.comp` and the boundary shaders read even
+/// without a `BoundaryVorticies` in the scene.
+const BOUNDARY_BINDINGS: [u32; 2] = [11, 50];
+
 impl Scene {
+    /// Uploads `Kernel::default()` to binding 60, so the shaders always have a kernel, and
+    /// binds empty buffers at bindings 11 and 50 until a `BoundaryVorticies` replaces them.
     pub fn new() -> Scene {
+        let kernel = Kernel::default();
+        let kernel_ssbo = util::create_buffer();
+        Scene::load_kernel_to_ssbo(kernel_ssbo, kernel.info());
+        for binding in BOUNDARY_BINDINGS {
+            let ssbo = util::create_buffer();
+            unsafe {
+                gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, ssbo);
+                gl::BufferData(
+                    gl::SHADER_STORAGE_BUFFER,
+                    0,
+                    std::ptr::null(),
+                    gl::DYNAMIC_DRAW,
+                );
+                gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, binding, ssbo);
+            }
+        }
         Scene {
             objects: Vec::new(),
+            kernel,
+            kernel_ssbo,
         }
     }
 
@@ -18,6 +49,32 @@ impl Scene {
         self.objects.push(Box::new(object));
         self
     }
+
+    /// Selects the Biot–Savart kernel every `get_velocity` call in the shaders uses.
+    /// Objects evaluating the kernel on the CPU, like `BoundaryVorticies`, take it from the
+    /// scene they are created for.
+    pub fn with_kernel(mut self, kernel: Kernel) -> Scene {
+        self.kernel = kernel;
+        Scene::load_kernel_to_ssbo(self.kernel_ssbo, kernel.info());
+        self
+    }
+
+    pub fn kernel(&self) -> Kernel {
+        self.kernel
+    }
+
+    fn load_kernel_to_ssbo(ssbo: u32, info: KernelInfo) {
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, ssbo);
+            gl::BufferData(
+                gl::SHADER_STORAGE_BUFFER,
+                std::mem::size_of::<KernelInfo>() as isize,
+                &info as *const KernelInfo as *const _,
+                gl::DYNAMIC_DRAW,
+            );
+            gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, 60, ssbo);
+        }
+    }
 }
 
 impl Default for Scene {
@@ -0,0 +1,155 @@
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+use cgmath::{Vector3, Zero};
+
+use crate::{
+    gl::{self},
+    physics::pressure::Pressure,
+    structures::vortex::{BoundaryInfo, Vortex},
+    util::{self},
+};
+
+use super::{boundary_body::BoundaryBody, boundary_vorticies::BoundaryDiscretization};
+
+/// Body frame strengths of a set when it was last solved, and when.
+type SolvedStrengths = Option<(f32, Vec<Vector3<f32>>)>;
+
+/// Surface pressure of the current boundary vorticies of `BoundaryVorticies`, recovered
+/// every step from binding 11 and the active vorticies in binding 2. Binding 13 holds the
+/// pressure coefficients in the order of binding 11.
+pub struct SurfacePressure {
+    pub pressure: Pressure,
+    ssbo: u32,
+    /// Of every set of every body
+    last_strengths: Vec<Vec<SolvedStrengths>>,
+    /// Number of vorticies of each body in binding 11
+    counts: Vec<usize>,
+    /// The current boundary vorticies in the world frame at the last step
+    pub vorticies: Vec<Vortex>,
+    /// `Pressure::coefficient` at `vorticies`
+    pub coefficients: Vec<f32>,
+}
+
+impl SurfacePressure {
+    pub fn new(pressure: Pressure, bodies: &[BoundaryBody]) -> SurfacePressure {
+        let counts = bodies
+            .iter()
+            .map(|body| body.vorticies[0].len())
+            .collect::<Vec<_>>();
+        let coefficients = vec![0.0; counts.iter().sum()];
+        let ssbo = util::create_buffer();
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, ssbo);
+            gl::BufferData(
+                gl::SHADER_STORAGE_BUFFER,
+                std::mem::size_of_val(coefficients.as_slice()) as isize,
+                coefficients.as_ptr().cast(),
+                gl::DYNAMIC_DRAW,
+            );
+            gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, 13, ssbo);
+        }
+        SurfacePressure {
+            pressure,
+            ssbo,
+            last_strengths: bodies
+                .iter()
+                .map(|body| vec![None; body.vorticies.len()])
+                .collect(),
+            counts,
+            vorticies: vec![],
+            coefficients,
+        }
+    }
+
+    /// Recovers the pressure at `time` on `current`, the corrected current sets of `bodies`
+    /// in the world frame, `infos` being the ones in binding 50. A set solved for the first
+    /// time has no strength rate yet.
+    pub fn step(
+        &mut self,
+        time: f32,
+        bodies: &[BoundaryBody],
+        infos: &[BoundaryInfo],
+        current: &[Vortex],
+    ) {
+        let mut velocities = vec![];
+        let mut rates = vec![];
+        for (index, (body, info)) in bodies.iter().zip(infos).enumerate() {
+            let start = info.current_offset as usize;
+            let vorticies = &current[start..start + info.active_count as usize];
+            let strengths = vorticies
+                .iter()
+                .map(|vortex| {
+                    body.transform
+                        .direction_to_body(vortex.vorticity.truncate())
+                })
+                .collect::<Vec<_>>();
+            let last = self.last_strengths[index][info.active_index as usize]
+                .replace((time, strengths.clone()));
+            for (i, vortex) in vorticies.iter().enumerate() {
+                let rate = match &last {
+                    Some((last_time, last)) if time > *last_time => {
+                        body.transform.rotation * (strengths[i] - last[i]) / (time - last_time)
+                    }
+                    _ => Vector3::zero(),
+                };
+                velocities.push(body.velocity_at(vortex.position.truncate()));
+                rates.push(Vortex {
+                    vorticity: rate.extend(0.0),
+                    ..*vortex
+                });
+            }
+        }
+
+        let mut sources = util::get_bound_buffer::<Vortex>(2);
+        // Vertex vorticies weigh one in `normal.w`, which is no area to spread them over.
+        for (body, info) in bodies.iter().zip(infos) {
+            let start = info.current_offset as usize;
+            let vorticies = current[start..start + info.active_count as usize].iter();
+            sources.extend(vorticies.map(|vortex| match body.discretization {
+                BoundaryDiscretization::Panels => *vortex,
+                BoundaryDiscretization::Vertices => Vortex {
+                    normal: vortex.normal.truncate().extend(0.0),
+                    ..*vortex
+                },
+            }));
+        }
+        let field = self.pressure.field(&sources, &velocities, &rates);
+        self.coefficients = field.surface_coefficients(current.len());
+        self.vorticies = current.to_vec();
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.ssbo);
+            gl::BufferSubData(
+                gl::SHADER_STORAGE_BUFFER,
+                0,
+                std::mem::size_of_val(self.coefficients.as_slice()) as isize,
+                self.coefficients.as_ptr().cast(),
+            );
+        }
+    }
+
+    /// Writes the last coefficients as CSV, one row per boundary vortex.
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "body,x,y,z,normal_x,normal_y,normal_z,cp")?;
+        let bodies = self
+            .counts
+            .iter()
+            .enumerate()
+            .flat_map(|(body, count)| std::iter::repeat_n(body, *count));
+        for ((body, vortex), cp) in bodies.zip(&self.vorticies).zip(&self.coefficients) {
+            let (p, n) = (vortex.position, vortex.normal);
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{}",
+                body, p.x, p.y, p.z, n.x, n.y, n.z, cp
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn save_csv(&self, path: &Path) -> io::Result<()> {
+        util::save_with(path, |writer| self.write_csv(writer))
+    }
+}
@@ -1,12 +1,13 @@
-use std::{collections::HashMap, fs};
+use std::{cell::RefCell, collections::HashMap, fs, rc::Rc};
 
-use cgmath::{Matrix, Matrix4, Vector3};
+use cgmath::Matrix;
 
 use crate::{
     compute_shader_program::ComputeShaderProgram,
     geometry::Geometry,
     gl,
     shader_program::ShaderProgram,
+    structures::transform::{SharedTransform, Transform},
     support::camera::PerspectiveCamera,
     traits::{drawable::Drawable, steppable::Steppable},
     util::{self},
@@ -18,6 +19,8 @@ pub struct TestSphere {
     pub compute_program: ComputeShaderProgram,
     pub vao: u32,
     pub hover: bool,
+    /// Where the mesh is drawn, shared with the body it shows
+    pub transform: SharedTransform,
 }
 
 impl TestSphere {
@@ -26,12 +29,20 @@ impl TestSphere {
             "Test Sphere",
             "resources/shaders/test_sphere.vert",
             "resources/shaders/test_sphere.frag",
-            HashMap::from([(
-                "get_velocity",
-                fs::read_to_string("resources/gpu_methods/get_velocity.glsl")
-                    .unwrap()
-                    .as_str(),
-            )]),
+            HashMap::from([
+                (
+                    "get_velocity",
+                    fs::read_to_string("resources/gpu_methods/get_velocity.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+                (
+                    "panel",
+                    fs::read_to_string("resources/gpu_methods/panel.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+            ]),
         );
 
         let vao = util::create_vao();
@@ -47,14 +58,27 @@ impl TestSphere {
             compute_program,
             vao,
             hover: false,
+            transform: Rc::new(RefCell::new(Transform::default())),
         }
     }
+
+    pub fn with_transform(mut self, transform: Transform) -> TestSphere {
+        self.transform = Rc::new(RefCell::new(transform));
+        self
+    }
+
+    /// Draws the mesh wherever the body behind `transform` is, see
+    /// `BoundaryVorticies::shared_transform`.
+    pub fn with_shared_transform(mut self, transform: SharedTransform) -> TestSphere {
+        self.transform = transform;
+        self
+    }
 }
 
 impl Drawable for TestSphere {
     fn draw(&self, camera: &PerspectiveCamera) {
         self.program.use_program();
-        let model_matrix = Matrix4::from_translation(Vector3::new(0.0, 0.0, 0.0)).transpose();
+        let model_matrix = self.transform.borrow().matrix().transpose();
         let mvp = model_matrix * camera.view_proj_matrix;
         unsafe {
             self.program.bind_uniform_matrix4fv("mvp", &mvp[0][0]);
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use crate::{
+    compute_shader_program::ComputeShaderProgram,
+    gl::{self},
+    physics::shedding::{Shedder, Shedding},
+    structures::vortex::BoundaryInfo,
+    util::{self},
+};
+
+use super::boundary_body::BoundaryBody;
+
+/// Runs `boundary_shedding.comp` for `BoundaryVorticies`, turning the corrected boundary
+/// vorticies into active vorticies.
+pub struct VortexShedder {
+    compute_program: ComputeShaderProgram,
+    shedder: Shedder,
+    /// First candidate of every set of every body in binding 12
+    candidate_offsets: Vec<Vec<usize>>,
+}
+
+impl VortexShedder {
+    /// Uploads the candidates of every set of `bodies` to binding 12.
+    pub fn new(shedding: Shedding, bodies: &[BoundaryBody]) -> VortexShedder {
+        let compute_program =
+            ComputeShaderProgram::new("resources/shaders/boundary_shedding.comp", HashMap::new());
+        let sets = bodies
+            .iter()
+            .map(|body| body.vorticies.as_slice())
+            .collect::<Vec<_>>();
+        let shedder = Shedder::new(shedding, &sets);
+
+        let mut candidate_offsets = vec![];
+        let mut candidates = vec![];
+        for body in &shedder.candidates {
+            let mut offsets = vec![];
+            for set in body {
+                offsets.push(candidates.len());
+                candidates.extend(set);
+            }
+            candidate_offsets.push(offsets);
+        }
+        let ssbo = util::create_buffer();
+        VortexShedder::load_candidates_to_ssbo(ssbo, &candidates);
+
+        VortexShedder {
+            compute_program,
+            shedder,
+            candidate_offsets,
+        }
+    }
+
+    fn load_candidates_to_ssbo(ssbo: u32, candidates: &[u32]) {
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, ssbo);
+            gl::BufferData(
+                gl::SHADER_STORAGE_BUFFER,
+                std::mem::size_of_val(candidates) as isize,
+                candidates.as_ptr().cast(),
+                gl::STATIC_DRAW,
+            );
+            gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, 12, ssbo);
+        }
+    }
+
+    /// Sheds from the current set of every body, `infos` being the ones in binding 50.
+    pub fn shed(&mut self, infos: &[BoundaryInfo], dt: f32) {
+        self.compute_program.use_program();
+        for (body, info) in infos.iter().enumerate() {
+            let set = info.active_index as usize;
+            let Some(release) = self.shedder.next(body, set, dt) else {
+                continue;
+            };
+            let shedding = &self.shedder.shedding;
+            unsafe {
+                gl::Uniform1ui(0, body as u32);
+                gl::Uniform1ui(1, self.candidate_offsets[body][set] as u32);
+                gl::Uniform1ui(2, self.shedder.candidates[body][set].len() as u32);
+                gl::Uniform1ui(3, release.first as u32);
+                gl::Uniform1ui(4, release.count as u32);
+                gl::Uniform1ui(5, shedding.slots.start as u32);
+                gl::Uniform1ui(6, shedding.slots.len() as u32);
+                gl::Uniform1ui(7, (release.slot - shedding.slots.start) as u32);
+                gl::Uniform1f(8, release.fraction);
+                gl::Uniform1f(9, shedding.offset);
+                gl::Uniform1f(10, shedding.lifetime);
+                gl::DispatchCompute(util::number_of_workgroups(release.count), 1, 1);
+                gl::MemoryBarrier(gl::SHADER_STORAGE_BARRIER_BIT);
+            }
+        }
+    }
+}
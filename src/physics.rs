@@ -0,0 +1,18 @@
+pub mod boundary_condition;
+pub mod boundary_solver;
+pub mod buoyancy;
+pub mod diffusion;
+pub mod flow_lines;
+pub mod forces;
+pub mod inertia;
+pub mod integrator;
+pub mod isosurface;
+pub mod kernel;
+pub mod kinematics;
+pub mod panel;
+pub mod pressure;
+pub mod rigid_body;
+pub mod shedding;
+pub mod stretching;
+pub mod treecode;
+pub mod vortex_identification;
@@ -0,0 +1,38 @@
+use cgmath::{InnerSpace, Vector3};
+
+use crate::structures::vortex::Vortex;
+
+/// What `BoundaryVorticies` cancels at its control points, the boundary vortex positions.
+/// The discriminants are the values `boundary_vortex_error.comp` switches on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoundaryCondition {
+    /// Zero total velocity
+    #[default]
+    NoSlip = 0,
+    /// Zero velocity along the surface normal, the fluid may slide along the surface
+    Slip = 1,
+}
+
+impl BoundaryCondition {
+    /// The part of `velocity` at a control point with unit surface normal `normal` this
+    /// condition cancels.
+    pub fn constrained(&self, velocity: Vector3<f32>, normal: Vector3<f32>) -> Vector3<f32> {
+        match self {
+            BoundaryCondition::NoSlip => velocity,
+            BoundaryCondition::Slip => normal * normal.dot(velocity),
+        }
+    }
+
+    /// `constrained` for the velocities `errors` measured at the control points of `vorticies`.
+    pub fn constrain_errors(
+        &self,
+        vorticies: &[Vortex],
+        errors: &[Vector3<f32>],
+    ) -> Vec<Vector3<f32>> {
+        vorticies
+            .iter()
+            .zip(errors)
+            .map(|(vortex, error)| self.constrained(*error, vortex.normal.truncate()))
+            .collect()
+    }
+}
@@ -0,0 +1,231 @@
+use cgmath::{InnerSpace, Vector3, Zero};
+
+use crate::structures::vortex::Vortex;
+
+use super::{boundary_condition::BoundaryCondition, kernel::Kernel, treecode::Evaluator};
+
+/// How `BoundaryVorticies` turns the measured boundary errors into vortex strengths.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BoundarySolver {
+    /// Dense pseudo-inverse of the `3N × 3N` influence matrix, precomputed once per boundary
+    /// set and applied by `boundary_vortex_correction.comp`.
+    #[default]
+    PseudoInverse,
+    /// Matrix-free least squares solve every step, see `IterativeSolver`.
+    Iterative(IterativeSolver),
+}
+
+/// MINRES for the strengths `c` of the boundary vorticies minimizing `|P (M c + e)|`, where
+/// `M c` is the velocity the boundary induces at its own positions, `e` the error and `P` the
+/// `BoundaryCondition`. `M` is only applied through Biot–Savart sums, so memory stays `O(N)`
+/// and the sums can go through the treecode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IterativeSolver {
+    /// Stop once the residual, or `|A r|` relative to `|A| |r|` for inconsistent systems,
+    /// dropped below this.
+    pub tolerance: f32,
+    pub max_iterations: usize,
+    pub evaluator: Evaluator,
+}
+
+#[derive(Debug, Clone)]
+pub struct IterativeSolution {
+    pub corrections: Vec<Vector3<f32>>,
+    pub iterations: usize,
+    /// `|P (M c + e)| / |P e|`
+    pub residual: f32,
+}
+
+impl Default for IterativeSolver {
+    fn default() -> Self {
+        Self {
+            tolerance: 0.0001,
+            max_iterations: 50,
+            evaluator: Evaluator::Direct,
+        }
+    }
+}
+
+impl IterativeSolver {
+    pub fn new() -> IterativeSolver {
+        IterativeSolver::default()
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> IterativeSolver {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> IterativeSolver {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    pub fn with_evaluator(mut self, evaluator: Evaluator) -> IterativeSolver {
+        self.evaluator = evaluator;
+        self
+    }
+
+    /// Solves for the corrections of `vorticies` cancelling the part of `errors`
+    /// `boundary_condition` constrains, warm started from `initial`, typically the
+    /// corrections of the previous step.
+    pub fn solve(
+        &self,
+        vorticies: &[Vortex],
+        errors: &[Vector3<f32>],
+        initial: &[Vector3<f32>],
+        kernel: &Kernel,
+        boundary_condition: BoundaryCondition,
+    ) -> IterativeSolution {
+        let mut x = initial.to_vec();
+        x.resize(errors.len(), Vector3::zero());
+        let errors = boundary_condition.constrain_errors(vorticies, errors);
+        let e_norm = norm(&errors);
+        if e_norm == 0.0 {
+            return IterativeSolution {
+                corrections: vec![Vector3::zero(); errors.len()],
+                iterations: 0,
+                residual: 0.0,
+            };
+        }
+
+        let (iterations, residual) = match boundary_condition {
+            // The velocity of a radial kernel `f(|d|) ω × d` is antisymmetric in `d`, which
+            // makes `M` symmetric, indefinite and, for smooth surfaces, singular.
+            BoundaryCondition::NoSlip => {
+                let b = scale(&errors, -1.0);
+                let (iterations, phibar) =
+                    self.minres(&mut x, &b, |v| self.apply(vorticies, v, kernel));
+                (iterations, phibar / e_norm)
+            }
+            // `P M`, with `P` projecting onto the normals, is not symmetric, so its least
+            // squares problem is solved through the normal equations `M P M c = -M P e`.
+            BoundaryCondition::Slip => {
+                let constrained_apply = |v: &[Vector3<f32>]| {
+                    boundary_condition
+                        .constrain_errors(vorticies, &self.apply(vorticies, v, kernel))
+                };
+                let b = scale(&self.apply(vorticies, &errors, kernel), -1.0);
+                let (iterations, _) = self.minres(&mut x, &b, |v| {
+                    self.apply(vorticies, &constrained_apply(v), kernel)
+                });
+                let residual = norm(&add(&constrained_apply(&x), &errors)) / e_norm;
+                (iterations, residual)
+            }
+        };
+
+        IterativeSolution {
+            corrections: x,
+            iterations,
+            residual,
+        }
+    }
+
+    /// MINRES for the symmetric `operator x = b` from the initial guess in `x`, returning the
+    /// number of iterations and the estimate of the final `|b - operator x|`.
+    fn minres<F>(&self, x: &mut [Vector3<f32>], b: &[Vector3<f32>], operator: F) -> (usize, f32)
+    where
+        F: Fn(&[Vector3<f32>]) -> Vec<Vector3<f32>>,
+    {
+        let b_norm = norm(b);
+        let mut r1 = subtract(b, &operator(x));
+        let mut r2 = r1.clone();
+        let mut y = r1.clone();
+        let mut beta = norm(&r1);
+        let mut old_beta = 0.0;
+        let mut phibar = beta;
+        let (mut dbar, mut epsilon, mut cs, mut sn) = (0.0, 0.0, -1.0f32, 0.0);
+        let mut a_norm2 = 0.0;
+        let mut w = vec![Vector3::zero(); b.len()];
+        let mut w2 = w.clone();
+
+        let mut iterations = 0;
+        while iterations < self.max_iterations && phibar > self.tolerance * b_norm && beta > 0.0 {
+            let v = scale(&y, 1.0 / beta);
+            y = operator(&v);
+            if iterations > 0 {
+                y = subtract(&y, &scale(&r1, beta / old_beta));
+            }
+            let alpha = dot(&v, &y);
+            y = subtract(&y, &scale(&r2, alpha / beta));
+            r1 = std::mem::replace(&mut r2, y.clone());
+            old_beta = beta;
+            beta = norm(&y);
+            a_norm2 += alpha * alpha + old_beta * old_beta + beta * beta;
+
+            let old_epsilon = epsilon;
+            let delta = cs * dbar + sn * alpha;
+            let gbar = sn * dbar - cs * alpha;
+            epsilon = sn * beta;
+            dbar = -cs * beta;
+            let gamma = gbar.hypot(beta).max(f32::EPSILON);
+            cs = gbar / gamma;
+            sn = beta / gamma;
+            let phi = cs * phibar;
+            phibar *= sn;
+
+            let w1 = std::mem::replace(&mut w2, w);
+            w = v
+                .iter()
+                .zip(&w1)
+                .zip(&w2)
+                .map(|((v, w1), w2)| (v - w1 * old_epsilon - w2 * delta) / gamma)
+                .collect();
+            for (x, w) in x.iter_mut().zip(&w) {
+                *x += w * phi;
+            }
+            iterations += 1;
+
+            // `|A r|` estimate, small relative to `|A| |r|` once the least squares problem of an
+            // inconsistent system is solved even though `|r|` stays large.
+            let ar_norm = phibar * gbar.hypot(dbar);
+            if ar_norm <= self.tolerance * a_norm2.sqrt() * phibar {
+                break;
+            }
+        }
+        (iterations, phibar)
+    }
+
+    /// `M strengths`, the velocity induced at the boundary positions.
+    fn apply(
+        &self,
+        vorticies: &[Vortex],
+        strengths: &[Vector3<f32>],
+        kernel: &Kernel,
+    ) -> Vec<Vector3<f32>> {
+        let sources = vorticies
+            .iter()
+            .zip(strengths)
+            .map(|(vortex, strength)| Vortex {
+                vorticity: strength.extend(1.0),
+                ..*vortex
+            })
+            .collect::<Vec<_>>();
+        let points = vorticies
+            .iter()
+            .map(|v| v.position.truncate())
+            .collect::<Vec<_>>();
+        self.evaluator
+            .get_linear_velocities(&points, &sources, kernel)
+    }
+}
+
+fn dot(a: &[Vector3<f32>], b: &[Vector3<f32>]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| a.dot(*b)).sum()
+}
+
+fn norm(a: &[Vector3<f32>]) -> f32 {
+    dot(a, a).sqrt()
+}
+
+fn scale(a: &[Vector3<f32>], factor: f32) -> Vec<Vector3<f32>> {
+    a.iter().map(|a| a * factor).collect()
+}
+
+fn add(a: &[Vector3<f32>], b: &[Vector3<f32>]) -> Vec<Vector3<f32>> {
+    a.iter().zip(b).map(|(a, b)| a + b).collect()
+}
+
+fn subtract(a: &[Vector3<f32>], b: &[Vector3<f32>]) -> Vec<Vector3<f32>> {
+    a.iter().zip(b).map(|(a, b)| a - b).collect()
+}
@@ -0,0 +1,158 @@
+use std::f32::consts::PI;
+
+use cgmath::{InnerSpace, Vector3, Zero};
+
+use crate::structures::vortex::Vortex;
+
+use super::kernel::STRENGTH_TO_CIRCULATION;
+
+/// Volumetric heating around a point, `rate · exp(-r² / 2R²)` per unit time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeatSource {
+    pub position: Vector3<f32>,
+    pub radius: f32,
+    /// Heating at the center, zero disables the source
+    pub rate: f32,
+}
+
+impl HeatSource {
+    pub fn new(position: Vector3<f32>, radius: f32, rate: f32) -> HeatSource {
+        HeatSource {
+            position,
+            radius,
+            rate,
+        }
+    }
+
+    /// Rate of change of the temperature at `point`.
+    pub fn heating(&self, point: Vector3<f32>) -> f32 {
+        if self.rate == 0.0 {
+            return 0.0;
+        }
+        let distance2 = (point - self.position).magnitude2();
+        self.rate * (-distance2 / (2.0 * self.radius * self.radius)).exp()
+    }
+}
+
+impl Default for HeatSource {
+    fn default() -> Self {
+        HeatSource::new(Vector3::zero(), 1.0, 0.0)
+    }
+}
+
+/// Boussinesq buoyancy. Every active vortex carries a temperature above the ambient fluid in
+/// `lifetime.w`, and the density deviation `ρ' / ρ₀ = -β T` generates vorticity through the
+/// baroclinic term `Dω/Dt = ∇(ρ' / ρ₀) × g = β g × ∇T`. The temperature gradient is estimated
+/// from the neighbouring vorticies with a Gaussian smoothing kernel, and the temperature is
+/// conducted between them by particle strength exchange like `Diffusion`. No expansion,
+/// diffusivity or heating disables it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Buoyancy {
+    /// Thermal expansion coefficient β
+    pub expansion: f32,
+    pub gravity: Vector3<f32>,
+    /// Thermal diffusivity κ
+    pub diffusivity: f32,
+    /// Smoothing radius ε of the gradient and conduction kernels.
+    pub core_radius: f32,
+    /// Volume V carried by every vortex.
+    pub particle_volume: f32,
+    pub heat_source: HeatSource,
+}
+
+impl Buoyancy {
+    /// Gravity along `-y`. Assumes vortices spaced about one core radius apart, `V = ε³`.
+    pub fn new(expansion: f32, core_radius: f32) -> Buoyancy {
+        assert!(core_radius > 0.0, "Buoyancy needs a positive core radius");
+        Buoyancy {
+            expansion,
+            gravity: Vector3::new(0.0, -1.0, 0.0),
+            diffusivity: 0.0,
+            core_radius,
+            particle_volume: core_radius * core_radius * core_radius,
+            heat_source: HeatSource::default(),
+        }
+    }
+
+    pub fn with_gravity(mut self, gravity: Vector3<f32>) -> Buoyancy {
+        self.gravity = gravity;
+        self
+    }
+
+    pub fn with_diffusivity(mut self, diffusivity: f32) -> Buoyancy {
+        self.diffusivity = diffusivity;
+        self
+    }
+
+    pub fn with_particle_volume(mut self, particle_volume: f32) -> Buoyancy {
+        self.particle_volume = particle_volume;
+        self
+    }
+
+    pub fn with_heat_source(mut self, heat_source: HeatSource) -> Buoyancy {
+        self.heat_source = heat_source;
+        self
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.expansion != 0.0 || self.diffusivity > 0.0 || self.heat_source.rate != 0.0
+    }
+
+    pub fn temperature(vortex: &Vortex) -> f32 {
+        vortex.lifetime.w
+    }
+
+    pub fn with_temperature(vortex: Vortex, temperature: f32) -> Vortex {
+        Vortex {
+            lifetime: vortex.lifetime.truncate().extend(temperature),
+            ..vortex
+        }
+    }
+
+    /// Normalized Gaussian `W_ε(r)`.
+    pub fn smoothing(&self, distance: f32) -> f32 {
+        let epsilon2 = self.core_radius * self.core_radius;
+        (-distance * distance / (2.0 * epsilon2)).exp()
+            / ((2.0 * PI).powf(1.5) * epsilon2 * self.core_radius)
+    }
+
+    /// Contribution of `b` to `∇T` at `a`, `V (T_b - T_a) ∇W_ε`, which vanishes for a
+    /// uniform temperature.
+    pub fn get_gradient(&self, a: &Vortex, b: &Vortex) -> Vector3<f32> {
+        let diff = (b.position - a.position).truncate();
+        let epsilon2 = self.core_radius * self.core_radius;
+        diff * (self.particle_volume
+            * (Buoyancy::temperature(b) - Buoyancy::temperature(a))
+            * self.smoothing(diff.magnitude())
+            / epsilon2)
+    }
+
+    /// Contribution of `b` to `dT/dt` of `a` by conduction.
+    pub fn get_conduction(&self, a: &Vortex, b: &Vortex) -> f32 {
+        if self.diffusivity == 0.0 {
+            return 0.0;
+        }
+        let distance = (b.position - a.position).truncate().magnitude();
+        let epsilon2 = self.core_radius * self.core_radius;
+        (Buoyancy::temperature(b) - Buoyancy::temperature(a))
+            * 2.0
+            * self.diffusivity
+            * self.particle_volume
+            / epsilon2
+            * self.smoothing(distance)
+    }
+
+    /// Rate of change of the strength of a vortex with temperature gradient `gradient`, the
+    /// circulation `ω V` it stands for changing by `V β g × ∇T`.
+    pub fn baroclinic(&self, gradient: Vector3<f32>) -> Vector3<f32> {
+        self.gravity.cross(gradient)
+            * (self.expansion * self.particle_volume / STRENGTH_TO_CIRCULATION)
+    }
+}
+
+impl Default for Buoyancy {
+    /// Disabled, with no expansion.
+    fn default() -> Self {
+        Buoyancy::new(0.0, 0.1)
+    }
+}
@@ -0,0 +1,125 @@
+use std::f32::consts::PI;
+
+use cgmath::{InnerSpace, Vector3};
+
+use crate::structures::vortex::Vortex;
+
+/// Viscous diffusion by particle strength exchange with a Gaussian exchange kernel.
+/// A zero viscosity disables it.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Diffusion {
+    pub viscosity: f32,
+    /// Smoothing radius ε of the exchange kernel.
+    pub core_radius: f32,
+    /// Volume V carried by every vortex, the strength is `α = ω V`.
+    pub particle_volume: f32,
+}
+
+impl Diffusion {
+    /// Assumes vortices spaced about one core radius apart, `V = ε³`.
+    pub fn new(viscosity: f32, core_radius: f32) -> Diffusion {
+        assert!(core_radius > 0.0, "Diffusion needs a positive core radius");
+        Diffusion {
+            viscosity,
+            core_radius,
+            particle_volume: core_radius * core_radius * core_radius,
+        }
+    }
+
+    /// `Re = Γ / ν` for a flow with characteristic circulation `circulation`.
+    pub fn from_reynolds_number(
+        reynolds_number: f32,
+        circulation: f32,
+        core_radius: f32,
+    ) -> Diffusion {
+        Diffusion::new(circulation / reynolds_number, core_radius)
+    }
+
+    pub fn with_particle_volume(mut self, particle_volume: f32) -> Diffusion {
+        self.particle_volume = particle_volume;
+        self
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.viscosity > 0.0
+    }
+
+    /// `2 ν V / ε² · η_ε(r)`
+    pub fn exchange_factor(&self, distance: f32) -> f32 {
+        exchange_factor(
+            self.viscosity,
+            self.core_radius,
+            self.particle_volume,
+            distance,
+        )
+    }
+
+    /// Contribution of `b` to `dα/dt` of `a`.
+    pub fn get_exchange(&self, a: &Vortex, b: &Vortex) -> Vector3<f32> {
+        let distance = (b.position - a.position).truncate().magnitude();
+        (b.vorticity - a.vorticity).truncate() * self.exchange_factor(distance)
+    }
+}
+
+/// Diffusion of the `scalars` of the particles by particle strength exchange with the same
+/// kernel as `Diffusion`, with the diffusivity of the scalar in place of the viscosity. All
+/// four channels share it. A zero diffusivity disables it.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ScalarDiffusion {
+    /// Diffusivity κ of the scalars, like the mass diffusivity of a dye
+    pub diffusivity: f32,
+    /// Smoothing radius ε of the exchange kernel.
+    pub core_radius: f32,
+    /// Volume V carried by every particle.
+    pub particle_volume: f32,
+}
+
+impl ScalarDiffusion {
+    /// Assumes particles spaced about one core radius apart, `V = ε³`.
+    pub fn new(diffusivity: f32, core_radius: f32) -> ScalarDiffusion {
+        assert!(
+            core_radius > 0.0,
+            "ScalarDiffusion needs a positive core radius"
+        );
+        ScalarDiffusion {
+            diffusivity,
+            core_radius,
+            particle_volume: core_radius * core_radius * core_radius,
+        }
+    }
+
+    /// `Sc = ν / κ` for a fluid of kinematic viscosity `viscosity`.
+    pub fn from_schmidt_number(
+        schmidt_number: f32,
+        viscosity: f32,
+        core_radius: f32,
+    ) -> ScalarDiffusion {
+        ScalarDiffusion::new(viscosity / schmidt_number, core_radius)
+    }
+
+    pub fn with_particle_volume(mut self, particle_volume: f32) -> ScalarDiffusion {
+        self.particle_volume = particle_volume;
+        self
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.diffusivity > 0.0
+    }
+
+    /// `2 κ V / ε² · η_ε(r)`
+    pub fn exchange_factor(&self, distance: f32) -> f32 {
+        exchange_factor(
+            self.diffusivity,
+            self.core_radius,
+            self.particle_volume,
+            distance,
+        )
+    }
+}
+
+fn exchange_factor(diffusivity: f32, core_radius: f32, particle_volume: f32, distance: f32) -> f32 {
+    let epsilon2 = core_radius * core_radius;
+    let rho2 = distance * distance / epsilon2;
+    let eta = (-rho2 / 2.0).exp() / ((2.0 * PI).powf(1.5) * epsilon2 * core_radius);
+    2.0 * diffusivity * particle_volume / epsilon2 * eta
+}
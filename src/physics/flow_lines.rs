@@ -0,0 +1,398 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    path::Path,
+};
+
+use cgmath::{InnerSpace, Vector3, Zero};
+
+use crate::util;
+
+use super::integrator::Integrator;
+
+/// Points along a traced line, in order.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Polyline {
+    pub points: Vec<Vector3<f32>>,
+}
+
+impl Polyline {
+    pub fn length(&self) -> f32 {
+        self.points
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).magnitude())
+            .sum()
+    }
+
+    /// Tube of `radius` around the line, a ring of `sides` vertices at every point, and the
+    /// triangles joining consecutive rings. Points coinciding with the one before, like those
+    /// of a tracer at a stagnation point, are skipped, as they have no direction.
+    pub fn tube(&self, radius: f32, sides: usize) -> (Vec<Vector3<f32>>, Vec<u32>) {
+        let mut points: Vec<Vector3<f32>> = vec![];
+        for point in &self.points {
+            if points
+                .last()
+                .is_none_or(|last| (point - last).magnitude2() > 0.0)
+            {
+                points.push(*point);
+            }
+        }
+        if points.len() < 2 || sides < 3 {
+            return (vec![], vec![]);
+        }
+        let mut vertices = vec![];
+        // The ring frame is carried along the line, so the tube does not twist.
+        let mut normal = Vector3::zero();
+        let mut tangent = Vector3::unit_x();
+        for (i, point) in points.iter().enumerate() {
+            let previous = points[i.saturating_sub(1)];
+            let next = points[(i + 1).min(points.len() - 1)];
+            // A line doubling back on itself keeps the tangent it had.
+            if (next - previous).magnitude2() > 0.0 {
+                tangent = (next - previous).normalize();
+            }
+            normal = (normal - tangent * normal.dot(tangent)).normalize();
+            if !normal.x.is_finite() || normal.magnitude2() < 0.5 {
+                let axis = if tangent.x.abs() < 0.9 {
+                    Vector3::unit_x()
+                } else {
+                    Vector3::unit_y()
+                };
+                normal = tangent.cross(axis).normalize();
+            }
+            let binormal = tangent.cross(normal);
+            for side in 0..sides {
+                let angle = 2.0 * std::f32::consts::PI * side as f32 / sides as f32;
+                vertices.push(point + (normal * angle.cos() + binormal * angle.sin()) * radius);
+            }
+        }
+        let mut indices = vec![];
+        for ring in 0..points.len() as u32 - 1 {
+            for side in 0..sides as u32 {
+                let next_side = (side + 1) % sides as u32;
+                let [a, b] = [side, next_side].map(|s| ring * sides as u32 + s);
+                let [c, d] = [a, b].map(|v| v + sides as u32);
+                indices.extend([a, b, c, b, d, c]);
+            }
+        }
+        (vertices, indices)
+    }
+}
+
+/// `count` seeds evenly spaced from `start` to `end`.
+pub fn rake(start: Vector3<f32>, end: Vector3<f32>, count: usize) -> Vec<Vector3<f32>> {
+    match count {
+        0 => vec![],
+        1 => vec![(start + end) * 0.5],
+        _ => (0..count)
+            .map(|i| start + (end - start) * (i as f32 / (count - 1) as f32))
+            .collect(),
+    }
+}
+
+/// Streamlines of the instantaneous velocity field, traced by arc length.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Streamlines {
+    pub integrator: Integrator,
+    /// Distance between consecutive points
+    pub step: f32,
+    pub max_steps: usize,
+    /// Lines end where the flow is slower than this
+    pub min_speed: f32,
+    /// Traces upstream of the seeds as well
+    pub both_directions: bool,
+}
+
+impl Streamlines {
+    pub fn new(step: f32, max_steps: usize) -> Streamlines {
+        Streamlines {
+            integrator: Integrator::RungeKutta4,
+            step,
+            max_steps,
+            min_speed: 1e-6,
+            both_directions: false,
+        }
+    }
+
+    pub fn with_integrator(mut self, integrator: Integrator) -> Streamlines {
+        self.integrator = integrator;
+        self
+    }
+
+    pub fn with_min_speed(mut self, min_speed: f32) -> Streamlines {
+        self.min_speed = min_speed;
+        self
+    }
+
+    pub fn with_both_directions(mut self, both_directions: bool) -> Streamlines {
+        self.both_directions = both_directions;
+        self
+    }
+
+    /// One line per seed through `velocity`, which gives the velocities at a batch of points.
+    pub fn trace<F>(&self, seeds: &[Vector3<f32>], mut velocity: F) -> Vec<Polyline>
+    where
+        F: FnMut(&[Vector3<f32>]) -> Vec<Vector3<f32>>,
+    {
+        let downstream = self.trace_direction(seeds, 1.0, &mut velocity);
+        if !self.both_directions {
+            return downstream;
+        }
+        let upstream = self.trace_direction(seeds, -1.0, &mut velocity);
+        upstream
+            .into_iter()
+            .zip(downstream)
+            .map(|(upstream, downstream)| Polyline {
+                points: upstream
+                    .points
+                    .into_iter()
+                    .rev()
+                    .chain(downstream.points.into_iter().skip(1))
+                    .collect(),
+            })
+            .collect()
+    }
+
+    fn trace_direction<F>(
+        &self,
+        seeds: &[Vector3<f32>],
+        sign: f32,
+        velocity: &mut F,
+    ) -> Vec<Polyline>
+    where
+        F: FnMut(&[Vector3<f32>]) -> Vec<Vector3<f32>>,
+    {
+        let mut lines = seeds
+            .iter()
+            .map(|seed| Polyline {
+                points: vec![*seed],
+            })
+            .collect::<Vec<_>>();
+        let mut tracing = (0..seeds.len()).collect::<Vec<_>>();
+        let min_speed = self.min_speed;
+        let mut direction = |points: &[Vector3<f32>]| {
+            velocity(points)
+                .into_iter()
+                .map(|u| {
+                    let speed = u.magnitude();
+                    if speed > min_speed {
+                        u * (sign / speed)
+                    } else {
+                        Vector3::zero()
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        for _ in 0..self.max_steps {
+            if tracing.is_empty() {
+                break;
+            }
+            let positions = tracing
+                .iter()
+                .map(|line| *lines[*line].points.last().unwrap())
+                .collect::<Vec<_>>();
+            // Every integrator starts at the current positions, where the direction also tells
+            // which lines have run into still fluid.
+            let mut speeds = None;
+            let next = self
+                .integrator
+                .integrate_batch(&positions, self.step, |points| {
+                    let directions = direction(points);
+                    if speeds.is_none() {
+                        speeds = Some(directions.clone());
+                    }
+                    directions
+                });
+            let speeds = speeds.unwrap_or_default();
+            let mut still_tracing = vec![];
+            for ((line, speed), next) in tracing.into_iter().zip(speeds).zip(next) {
+                if speed.magnitude2() > 0.0 && next.x.is_finite() {
+                    lines[line].points.push(next);
+                    still_tracing.push(line);
+                }
+            }
+            tracing = still_tracing;
+        }
+        lines
+    }
+}
+
+/// Pathlines of tracers released at the seeds, grown every time the flow advances.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pathlines {
+    pub integrator: Integrator,
+    /// The points of every line, oldest first
+    pub tracks: Vec<VecDeque<Vector3<f32>>>,
+    /// Older points are dropped from the lines beyond this
+    pub max_points: usize,
+}
+
+impl Pathlines {
+    pub fn new(seeds: &[Vector3<f32>]) -> Pathlines {
+        Pathlines {
+            integrator: Integrator::default(),
+            tracks: seeds.iter().map(|seed| VecDeque::from([*seed])).collect(),
+            max_points: usize::MAX,
+        }
+    }
+
+    pub fn lines(&self) -> Vec<Polyline> {
+        self.tracks
+            .iter()
+            .map(|track| Polyline {
+                points: track.iter().cloned().collect(),
+            })
+            .collect()
+    }
+
+    pub fn with_integrator(mut self, integrator: Integrator) -> Pathlines {
+        self.integrator = integrator;
+        self
+    }
+
+    pub fn with_max_points(mut self, max_points: usize) -> Pathlines {
+        self.max_points = max_points;
+        self
+    }
+
+    /// Moves the tracers by `dt` through the current `velocity`.
+    pub fn step<F>(&mut self, dt: f32, velocity: F)
+    where
+        F: FnMut(&[Vector3<f32>]) -> Vec<Vector3<f32>>,
+    {
+        let positions = self
+            .tracks
+            .iter()
+            .map(|track| *track.back().unwrap())
+            .collect::<Vec<_>>();
+        let next = self.integrator.integrate_batch(&positions, dt, velocity);
+        for (track, next) in self.tracks.iter_mut().zip(next) {
+            track.push_back(next);
+            if track.len() > self.max_points {
+                track.pop_front();
+            }
+        }
+    }
+}
+
+/// Streaklines of dye injected at fixed points: every `release_interval` each injector
+/// releases a tracer, and line `i` joins the tracers of injector `i`, newest first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Streaklines {
+    pub injectors: Vec<Vector3<f32>>,
+    pub integrator: Integrator,
+    pub release_interval: f32,
+    /// The oldest tracers are dropped from the lines beyond this
+    pub max_points: usize,
+    pub lines: Vec<Polyline>,
+    /// Time since the last release
+    pub elapsed: f32,
+}
+
+impl Streaklines {
+    pub fn new(injectors: &[Vector3<f32>], release_interval: f32) -> Streaklines {
+        Streaklines {
+            injectors: injectors.to_vec(),
+            integrator: Integrator::default(),
+            release_interval,
+            max_points: 1000,
+            lines: injectors
+                .iter()
+                .map(|injector| Polyline {
+                    points: vec![*injector],
+                })
+                .collect(),
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn with_integrator(mut self, integrator: Integrator) -> Streaklines {
+        self.integrator = integrator;
+        self
+    }
+
+    pub fn with_max_points(mut self, max_points: usize) -> Streaklines {
+        self.max_points = max_points;
+        self
+    }
+
+    /// Moves the released tracers by `dt` through the current `velocity` and releases new
+    /// ones when it is time.
+    pub fn step<F>(&mut self, dt: f32, velocity: F)
+    where
+        F: FnMut(&[Vector3<f32>]) -> Vec<Vector3<f32>>,
+    {
+        let positions = self
+            .lines
+            .iter()
+            .flat_map(|line| line.points.iter().cloned())
+            .collect::<Vec<_>>();
+        let mut next = self
+            .integrator
+            .integrate_batch(&positions, dt, velocity)
+            .into_iter();
+        for line in &mut self.lines {
+            for point in &mut line.points {
+                *point = next.next().unwrap();
+            }
+        }
+
+        self.elapsed += dt;
+        if self.elapsed >= self.release_interval {
+            self.elapsed -= self.release_interval;
+            for (line, injector) in self.lines.iter_mut().zip(&self.injectors) {
+                line.points.insert(0, *injector);
+                line.points.truncate(self.max_points);
+            }
+        }
+    }
+}
+
+/// Writes `lines` as Wavefront OBJ line elements.
+pub fn write_obj<W: Write>(lines: &[Polyline], writer: &mut W) -> io::Result<()> {
+    let mut first = 1;
+    for line in lines {
+        for point in &line.points {
+            writeln!(writer, "v {} {} {}", point.x, point.y, point.z)?;
+        }
+        if line.points.len() > 1 {
+            write!(writer, "l")?;
+            for index in first..first + line.points.len() {
+                write!(writer, " {}", index)?;
+            }
+            writeln!(writer)?;
+        }
+        first += line.points.len();
+    }
+    Ok(())
+}
+
+/// Writes the `Polyline::tube` of every line of `lines` as a Wavefront OBJ mesh.
+pub fn write_tube_obj<W: Write>(
+    lines: &[Polyline],
+    radius: f32,
+    sides: usize,
+    writer: &mut W,
+) -> io::Result<()> {
+    let mut first = 1;
+    for line in lines {
+        let (vertices, indices) = line.tube(radius, sides);
+        for vertex in &vertices {
+            writeln!(writer, "v {} {} {}", vertex.x, vertex.y, vertex.z)?;
+        }
+        for triangle in indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| triangle[i] as usize + first);
+            writeln!(writer, "f {} {} {}", a, b, c)?;
+        }
+        first += vertices.len();
+    }
+    Ok(())
+}
+
+/// `write_obj`, or `write_tube_obj` with `Some((radius, sides))`, to the file at `path`.
+pub fn save_obj(lines: &[Polyline], tube: Option<(f32, usize)>, path: &Path) -> io::Result<()> {
+    util::save_with(path, |writer| match tube {
+        Some((radius, sides)) => write_tube_obj(lines, radius, sides, writer),
+        None => write_obj(lines, writer),
+    })
+}
@@ -0,0 +1,187 @@
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+use cgmath::{InnerSpace, Matrix, Matrix3, Vector3, Zero};
+
+use crate::{
+    structures::{transform::Transform, vortex::Vortex},
+    util,
+};
+
+use super::{kernel::STRENGTH_TO_CIRCULATION, kinematics::BodyVelocity};
+
+/// The fluid a body displaces, which moves with it. Its momentum is part of what the body
+/// takes from the rest of the fluid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplacedFluid {
+    pub density: f32,
+    pub volume: f32,
+    /// Inertia tensor of the displaced volume at unit density about the body origin in the
+    /// body frame
+    pub inertia: Matrix3<f32>,
+}
+
+impl DisplacedFluid {
+    /// Without a volume, which is exact for bodies that do not move.
+    pub fn new(density: f32) -> DisplacedFluid {
+        DisplacedFluid {
+            density,
+            volume: 0.0,
+            inertia: Matrix3::zero(),
+        }
+    }
+
+    pub fn with_volume(mut self, volume: f32, inertia: Matrix3<f32>) -> DisplacedFluid {
+        self.volume = volume;
+        self.inertia = inertia;
+        self
+    }
+
+    /// Linear and, about the world origin, angular momentum whose rate of change is the force
+    /// and moment of the fluid on the body: the momentum of the displaced fluid, moving with
+    /// the body, less the `impulse` of all the vorticity in the fluid, `vorticies`, the
+    /// boundary vorticies of the body, and `free`, the active ones, all in the world frame.
+    /// The wake carries the impulse behind steady drag and lift. With several bodies the
+    /// impulse of the free vorticity cannot be told apart, each body gets all of it.
+    pub fn momentum(
+        &self,
+        vorticies: &[Vortex],
+        free: &[Vortex],
+        transform: &Transform,
+        velocity: &BodyVelocity,
+    ) -> (Vector3<f32>, Vector3<f32>) {
+        let (linear, angular) = impulse(vorticies.iter().chain(free));
+        let rotation = transform.rotation_matrix();
+        let inertia = rotation * self.inertia * rotation.transpose();
+        let mass = self.density * self.volume;
+        (
+            velocity.linear * mass - linear * self.density,
+            transform.translation.cross(velocity.linear) * mass
+                + inertia * velocity.angular * self.density
+                - angular * self.density,
+        )
+    }
+}
+
+/// Linear impulse `½ ∫ x × ω dV` and angular impulse about the world origin
+/// `-½ ∫ |x|² ω dV` of vortex elements in the world frame, per unit density.
+pub fn impulse<'a>(vorticies: impl Iterator<Item = &'a Vortex>) -> (Vector3<f32>, Vector3<f32>) {
+    let (mut linear, mut angular) = (Vector3::zero(), Vector3::zero());
+    for vortex in vorticies {
+        let x = vortex.position.truncate();
+        let circulation = vortex.vorticity.truncate() * STRENGTH_TO_CIRCULATION;
+        linear += x.cross(circulation) * 0.5;
+        angular -= circulation * 0.5 * x.magnitude2();
+    }
+    (linear, angular)
+}
+
+/// Force and moment about the body origin at `translation` of a momentum changing from
+/// `last` to `momentum` over `dt`, see `DisplacedFluid::momentum`.
+pub fn momentum_rate(
+    last: (Vector3<f32>, Vector3<f32>),
+    momentum: (Vector3<f32>, Vector3<f32>),
+    translation: Vector3<f32>,
+    dt: f32,
+) -> (Vector3<f32>, Vector3<f32>) {
+    let force = (momentum.0 - last.0) / dt;
+    let moment = (momentum.1 - last.1) / dt - translation.cross(force);
+    (force, moment)
+}
+
+/// Force and moment about the body origin of the fluid on a body, in the world frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ForceSample {
+    pub time: f32,
+    pub force: Vector3<f32>,
+    pub moment: Vector3<f32>,
+}
+
+impl ForceSample {
+    /// Drag and lift coefficients of a body moving through the fluid with `velocity`,
+    /// relative to the dynamic pressure on `reference_area`. The drag is the force against
+    /// the motion, the lift the magnitude of the rest. `None` without a velocity or area to
+    /// refer to.
+    pub fn coefficients(
+        &self,
+        density: f32,
+        velocity: Vector3<f32>,
+        reference_area: f32,
+    ) -> Option<(f32, f32)> {
+        let dynamic_pressure = 0.5 * density * velocity.magnitude2() * reference_area;
+        if dynamic_pressure <= 0.0 {
+            return None;
+        }
+        let direction = velocity.normalize();
+        let drag = -self.force.dot(direction);
+        let lift = (self.force + direction * drag).magnitude();
+        Some((drag / dynamic_pressure, lift / dynamic_pressure))
+    }
+}
+
+/// Force and moment time series of a boundary body from the rate of change of the fluid
+/// impulse, see `DisplacedFluid::momentum`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForceHistory {
+    pub fluid: DisplacedFluid,
+    pub samples: Vec<ForceSample>,
+    /// `DisplacedFluid::momentum` at the last step
+    pub last_momentum: Option<(Vector3<f32>, Vector3<f32>)>,
+}
+
+impl ForceHistory {
+    pub fn new(fluid: DisplacedFluid) -> ForceHistory {
+        ForceHistory {
+            fluid,
+            samples: vec![],
+            last_momentum: None,
+        }
+    }
+
+    /// Adds the sample at `time`, `dt` after the last call, from `vorticies`, the boundary
+    /// vorticies, and `free`, the active vorticies, in the world frame. The first call only
+    /// takes the initial momentum.
+    pub fn record(
+        &mut self,
+        time: f32,
+        vorticies: &[Vortex],
+        free: &[Vortex],
+        transform: &Transform,
+        velocity: &BodyVelocity,
+        dt: f32,
+    ) -> Option<ForceSample> {
+        let momentum = self.fluid.momentum(vorticies, free, transform, velocity);
+        let last = self.last_momentum.replace(momentum)?;
+        let (force, moment) = momentum_rate(last, momentum, transform.translation, dt);
+        let sample = ForceSample {
+            time,
+            force,
+            moment,
+        };
+        self.samples.push(sample);
+        Some(sample)
+    }
+
+    /// Writes the samples as CSV, one row per sample.
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(
+            writer,
+            "time,force_x,force_y,force_z,moment_x,moment_y,moment_z"
+        )?;
+        for sample in &self.samples {
+            let (f, m) = (sample.force, sample.moment);
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{}",
+                sample.time, f.x, f.y, f.z, m.x, m.y, m.z
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn save_csv(&self, path: &Path) -> io::Result<()> {
+        util::save_with(path, |writer| self.write_csv(writer))
+    }
+}
@@ -0,0 +1,101 @@
+use cgmath::{Vector3, Zero};
+
+/// Time integration scheme for advecting vorticies and tracers. The discriminants are
+/// the values `integrate.glsl` switches on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Integrator {
+    #[default]
+    Euler = 0,
+    /// Explicit midpoint, second order
+    Midpoint = 1,
+    /// Classical fourth order Runge–Kutta
+    RungeKutta4 = 2,
+    /// Williamson's 2N-storage third order Runge–Kutta
+    LowStorageRungeKutta3 = 3,
+}
+
+const LOW_STORAGE_A: [f32; 3] = [0.0, -5.0 / 9.0, -153.0 / 128.0];
+const LOW_STORAGE_B: [f32; 3] = [1.0 / 3.0, 15.0 / 16.0, 8.0 / 15.0];
+
+impl Integrator {
+    /// Advances `position` by `dt` through the frozen velocity field `velocity`.
+    pub fn integrate<F>(&self, position: Vector3<f32>, dt: f32, velocity: F) -> Vector3<f32>
+    where
+        F: Fn(Vector3<f32>) -> Vector3<f32>,
+    {
+        match self {
+            Integrator::Euler => position + velocity(position) * dt,
+            Integrator::Midpoint => {
+                let k1 = velocity(position);
+                position + velocity(position + k1 * dt * 0.5) * dt
+            }
+            Integrator::RungeKutta4 => {
+                let k1 = velocity(position);
+                let k2 = velocity(position + k1 * dt * 0.5);
+                let k3 = velocity(position + k2 * dt * 0.5);
+                let k4 = velocity(position + k3 * dt);
+                position + (k1 + k2 * 2.0 + k3 * 2.0 + k4) * dt / 6.0
+            }
+            Integrator::LowStorageRungeKutta3 => {
+                let mut position = position;
+                let mut q = Vector3::zero();
+                for (a, b) in LOW_STORAGE_A.iter().zip(LOW_STORAGE_B.iter()) {
+                    q = q * *a + velocity(position) * dt;
+                    position += q * *b;
+                }
+                position
+            }
+        }
+    }
+
+    /// `integrate` of every position of `positions` at once, for velocity fields sampled in
+    /// batches like `FieldSampler`. The first batch `velocity` is asked for is `positions`
+    /// itself.
+    pub fn integrate_batch<F>(
+        &self,
+        positions: &[Vector3<f32>],
+        dt: f32,
+        mut velocity: F,
+    ) -> Vec<Vector3<f32>>
+    where
+        F: FnMut(&[Vector3<f32>]) -> Vec<Vector3<f32>>,
+    {
+        let shifted = |k: &[Vector3<f32>], scale: f32| {
+            positions
+                .iter()
+                .zip(k)
+                .map(|(position, k)| position + k * scale)
+                .collect::<Vec<_>>()
+        };
+        match self {
+            Integrator::Euler => shifted(&velocity(positions), dt),
+            Integrator::Midpoint => {
+                let k1 = velocity(positions);
+                let k2 = velocity(&shifted(&k1, dt * 0.5));
+                shifted(&k2, dt)
+            }
+            Integrator::RungeKutta4 => {
+                let k1 = velocity(positions);
+                let k2 = velocity(&shifted(&k1, dt * 0.5));
+                let k3 = velocity(&shifted(&k2, dt * 0.5));
+                let k4 = velocity(&shifted(&k3, dt));
+                let k = (0..positions.len())
+                    .map(|i| (k1[i] + k2[i] * 2.0 + k3[i] * 2.0 + k4[i]) / 6.0)
+                    .collect::<Vec<_>>();
+                shifted(&k, dt)
+            }
+            Integrator::LowStorageRungeKutta3 => {
+                let mut positions = positions.to_vec();
+                let mut q = vec![Vector3::zero(); positions.len()];
+                for (a, b) in LOW_STORAGE_A.iter().zip(LOW_STORAGE_B.iter()) {
+                    let k = velocity(&positions);
+                    for ((position, q), k) in positions.iter_mut().zip(&mut q).zip(k) {
+                        *q = *q * *a + k * dt;
+                        *position += *q * *b;
+                    }
+                }
+                positions
+            }
+        }
+    }
+}
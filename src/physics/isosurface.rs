@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use cgmath::{InnerSpace, Vector3, Zero};
+
+use crate::structures::{grid::ScalarVolume, triangle_mesh::TriangleMesh};
+
+/// Corners of a grid cell, bit 0 stepping along `x`, bit 1 along `y` and bit 2 along `z`.
+const CORNERS: [[usize; 3]; 8] = [
+    [0, 0, 0],
+    [1, 0, 0],
+    [0, 1, 0],
+    [1, 1, 0],
+    [0, 0, 1],
+    [1, 0, 1],
+    [0, 1, 1],
+    [1, 1, 1],
+];
+
+/// The six tetrahedra around the diagonal from corner 0 to corner 7. Neighbouring cells
+/// split their shared faces along the same diagonal, so the surface has no cracks.
+const TETRAHEDRA: [[usize; 4]; 6] = [
+    [0, 1, 3, 7],
+    [0, 1, 5, 7],
+    [0, 2, 3, 7],
+    [0, 2, 6, 7],
+    [0, 4, 5, 7],
+    [0, 4, 6, 7],
+];
+
+/// Surface where `volume` crosses `iso`, by marching cubes with every cell split into
+/// tetrahedra, which needs no case table and has no ambiguous faces. Vertices on the same
+/// grid edge are shared, and the normals point towards the values below `iso`, out of a
+/// vorticity blob.
+pub fn marching_cubes(volume: &ScalarVolume, iso: f32) -> TriangleMesh {
+    let grid = &volume.grid;
+    let [nx, ny, nz] = grid.dimensions;
+    let mut mesh = TriangleMesh::default();
+    let mut edge_vertices = HashMap::<(usize, usize), u32>::new();
+    let mut vertex = |mesh: &mut TriangleMesh, a: usize, b: usize| {
+        let key = (a.min(b), a.max(b));
+        *edge_vertices.entry(key).or_insert_with(|| {
+            let (va, vb) = (volume.values[a], volume.values[b]);
+            let t = (iso - va) / (vb - va);
+            let position = |index| {
+                let [i, j, k] = unravel(grid.dimensions, index);
+                grid.point(i, j, k)
+            };
+            mesh.vertices
+                .push(position(a) + (position(b) - position(a)) * t);
+            let normal = -(gradient(volume, a) * (1.0 - t) + gradient(volume, b) * t);
+            mesh.normals.push(if normal.magnitude2() > 0.0 {
+                normal.normalize()
+            } else {
+                Vector3::zero()
+            });
+            mesh.vertices.len() as u32 - 1
+        })
+    };
+    for k in 0..nz.saturating_sub(1) {
+        for j in 0..ny.saturating_sub(1) {
+            for i in 0..nx.saturating_sub(1) {
+                let corners = CORNERS.map(|[di, dj, dk]| grid.index(i + di, j + dj, k + dk));
+                for tetrahedron in TETRAHEDRA {
+                    let points = tetrahedron.map(|corner| corners[corner]);
+                    let (inside, outside): (Vec<usize>, Vec<usize>) = points
+                        .iter()
+                        .partition(|point| volume.values[**point] > iso);
+                    let polygon = match (inside.as_slice(), outside.as_slice()) {
+                        ([a], [b, c, d]) | ([b, c, d], [a]) => vec![(*a, *b), (*a, *c), (*a, *d)],
+                        ([a, b], [c, d]) => vec![(*a, *c), (*a, *d), (*b, *d), (*b, *c)],
+                        _ => continue,
+                    };
+                    let polygon = polygon
+                        .into_iter()
+                        .map(|(a, b)| vertex(&mut mesh, a, b))
+                        .collect::<Vec<_>>();
+                    // Wind the triangles to face from the inside corners to the outside ones.
+                    let center = |indices: &[usize]| {
+                        indices.iter().fold(Vector3::zero(), |sum, index| {
+                            let [i, j, k] = unravel(grid.dimensions, *index);
+                            sum + grid.point(i, j, k)
+                        }) / indices.len() as f32
+                    };
+                    let outward = center(&outside) - center(&inside);
+                    for fan in 1..polygon.len() - 1 {
+                        let mut triangle = [polygon[0], polygon[fan], polygon[fan + 1]];
+                        let [a, b, c] = triangle.map(|index| mesh.vertices[index as usize]);
+                        if (b - a).cross(c - a).dot(outward) < 0.0 {
+                            triangle.swap(1, 2);
+                        }
+                        mesh.indices.extend(triangle);
+                    }
+                }
+            }
+        }
+    }
+    mesh
+}
+
+fn unravel([nx, ny, _]: [usize; 3], index: usize) -> [usize; 3] {
+    [index % nx, (index / nx) % ny, index / (nx * ny)]
+}
+
+/// Central differences, one sided at the sides of the grid.
+fn gradient(volume: &ScalarVolume, index: usize) -> Vector3<f32> {
+    let grid = &volume.grid;
+    let point = unravel(grid.dimensions, index);
+    let mut gradient = [0.0; 3];
+    for (axis, derivative) in gradient.iter_mut().enumerate() {
+        let mut low = point;
+        let mut high = point;
+        low[axis] = low[axis].saturating_sub(1);
+        high[axis] = (high[axis] + 1).min(grid.dimensions[axis] - 1);
+        if low[axis] == high[axis] {
+            continue;
+        }
+        let value = |[i, j, k]: [usize; 3]| volume.get(i, j, k);
+        *derivative = (value(high) - value(low)) / ((high[axis] - low[axis]) as f32 * grid.spacing);
+    }
+    Vector3::from(gradient)
+}
@@ -0,0 +1,173 @@
+use std::f32::consts::PI;
+
+use cgmath::{InnerSpace, Matrix3, Vector3, Zero};
+
+use crate::structures::{kernel_info::KernelInfo, vortex::Vortex};
+
+use super::panel;
+
+/// `Kernel` leaves the `-1 / 4π` of the Biot–Savart law out of the strengths, this turns
+/// them back into vector circulations.
+pub const STRENGTH_TO_CIRCULATION: f32 = -4.0 * PI;
+
+/// Vorticies weaker than this induce no velocity, like in `get_velocity.glsl`.
+pub const MIN_STRENGTH: f32 = 0.0001;
+
+/// Smoothing function of the Biot–Savart kernel. The discriminants are the values
+/// `get_velocity.glsl` switches on, so keep the two in sync.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Smoothing {
+    #[default]
+    Singular = 0,
+    RosenheadMoore = 1,
+    Gaussian = 2,
+    WinckelmansLeonard = 3,
+}
+
+/// Regularized Biot–Savart kernel, `u(a) = q(r / σ) / r³ · (ω × (b - a))`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Kernel {
+    pub smoothing: Smoothing,
+    pub core_radius: f32,
+}
+
+impl Kernel {
+    pub fn new(smoothing: Smoothing, core_radius: f32) -> Kernel {
+        assert!(
+            smoothing == Smoothing::Singular || core_radius > 0.0,
+            "Regularized kernels need a positive core radius"
+        );
+        Kernel {
+            smoothing,
+            core_radius,
+        }
+    }
+
+    pub fn singular() -> Kernel {
+        Kernel::default()
+    }
+
+    pub fn rosenhead_moore(core_radius: f32) -> Kernel {
+        Kernel::new(Smoothing::RosenheadMoore, core_radius)
+    }
+
+    pub fn gaussian(core_radius: f32) -> Kernel {
+        Kernel::new(Smoothing::Gaussian, core_radius)
+    }
+
+    pub fn winckelmans_leonard(core_radius: f32) -> Kernel {
+        Kernel::new(Smoothing::WinckelmansLeonard, core_radius)
+    }
+
+    pub fn info(&self) -> KernelInfo {
+        KernelInfo {
+            smoothing: self.smoothing as u32,
+            core_radius: self.core_radius,
+        }
+    }
+
+    /// `q(r / σ) / r³`, written so that the regularized variants stay finite at `r = 0`.
+    pub fn factor(&self, distance: f32) -> f32 {
+        let r2 = distance * distance;
+        let s2 = self.core_radius * self.core_radius;
+        match self.smoothing {
+            Smoothing::Singular => 1.0 / (r2 * distance),
+            Smoothing::RosenheadMoore => 1.0 / (r2 + s2).powf(1.5),
+            Smoothing::WinckelmansLeonard => (r2 + 2.5 * s2) / (r2 + s2).powf(2.5),
+            Smoothing::Gaussian => {
+                let rho = distance / self.core_radius;
+                let sigma3 = s2 * self.core_radius;
+                if rho < 0.5 {
+                    let rho2 = rho * rho;
+                    (2.0 / PI).sqrt()
+                        * (1.0 / 3.0 - rho2 / 10.0 + rho2 * rho2 / 56.0
+                            - rho2 * rho2 * rho2 / 432.0)
+                        / sigma3
+                } else {
+                    let q =
+                        erf(rho / 2f32.sqrt()) - (2.0 / PI).sqrt() * rho * (-rho * rho / 2.0).exp();
+                    q / (r2 * distance)
+                }
+            }
+        }
+    }
+
+    /// `f'(r) / r` of `f = factor`, needed for the velocity gradient.
+    pub fn factor_derivative(&self, distance: f32) -> f32 {
+        let r2 = distance * distance;
+        let s2 = self.core_radius * self.core_radius;
+        match self.smoothing {
+            Smoothing::Singular => -3.0 / (r2 * r2 * distance),
+            Smoothing::RosenheadMoore => -3.0 / (r2 + s2).powf(2.5),
+            Smoothing::WinckelmansLeonard => {
+                2.0 * (1.0 / (r2 + s2).powf(2.5) - 2.5 * (r2 + 2.5 * s2) / (r2 + s2).powf(3.5))
+            }
+            Smoothing::Gaussian => {
+                let rho = distance / self.core_radius;
+                let sigma5 = s2 * s2 * self.core_radius;
+                if rho < 0.5 {
+                    let rho2 = rho * rho;
+                    (2.0 / PI).sqrt() * (-1.0 / 5.0 + rho2 / 14.0 - rho2 * rho2 / 72.0) / sigma5
+                } else {
+                    let gauss = (2.0 / PI).sqrt() * (-rho * rho / 2.0).exp();
+                    let q = erf(rho / 2f32.sqrt()) - gauss * rho;
+                    (gauss * rho * rho * rho - 3.0 * q) / (r2 * r2 * distance)
+                }
+            }
+        }
+    }
+
+    pub fn get_velocity(&self, a: Vector3<f32>, b: &Vortex) -> Vector3<f32> {
+        if b.vorticity.truncate().magnitude() < MIN_STRENGTH {
+            return Vector3::zero();
+        }
+        self.get_linear_velocity(a, b)
+    }
+
+    /// `get_velocity` without the `MIN_STRENGTH` cutoff, so linear in the strength of `b`.
+    /// Near a panel, see `physics::panel`, this is its exact velocity instead.
+    pub fn get_linear_velocity(&self, a: Vector3<f32>, b: &Vortex) -> Vector3<f32> {
+        if let Some(corners) = panel::corners(b) {
+            if panel::is_near(a, b.position.truncate(), corners) {
+                return panel::get_velocity(a, b, corners);
+            }
+        }
+        let diff = b.position.truncate() - a;
+        let distance = diff.magnitude();
+        if distance < 0.0001 {
+            return Vector3::zero();
+        }
+        b.vorticity.truncate().cross(diff) * self.factor(distance)
+    }
+
+    /// Jacobian `∂u_i / ∂a_j` of `get_velocity` with respect to the evaluation point.
+    pub fn get_velocity_gradient(&self, a: Vector3<f32>, b: &Vortex) -> Matrix3<f32> {
+        let vorticity = b.vorticity.truncate();
+        if vorticity.magnitude() < MIN_STRENGTH {
+            return Matrix3::zero();
+        }
+        let diff = b.position.truncate() - a;
+        let distance = diff.magnitude();
+        if distance < 0.0001 {
+            return Matrix3::zero();
+        }
+        let cross_matrix = Matrix3::from_cols(
+            Vector3::new(0.0, vorticity.z, -vorticity.y),
+            Vector3::new(-vorticity.z, 0.0, vorticity.x),
+            Vector3::new(vorticity.y, -vorticity.x, 0.0),
+        );
+        let direction = vorticity.cross(diff);
+        let outer = Matrix3::from_cols(direction * diff.x, direction * diff.y, direction * diff.z);
+        -(cross_matrix * self.factor(distance) + outer * self.factor_derivative(distance))
+    }
+}
+
+/// Abramowitz–Stegun 7.1.26, the same approximation `get_velocity.glsl` uses.
+pub fn erf(x: f32) -> f32 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let polynomial = t
+        * (0.254_829_6
+            + t * (-0.284_496_72 + t * (1.421_413_8 + t * (-1.453_152 + t * 1.061_405_4))));
+    let value = 1.0 - polynomial * (-x * x).exp();
+    value.copysign(x)
+}
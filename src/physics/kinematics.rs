@@ -0,0 +1,180 @@
+use std::f32::consts::PI;
+
+use cgmath::{InnerSpace, Quaternion, Rad, Rotation3, Vector3, Zero};
+
+use crate::structures::transform::Transform;
+
+/// Prescribed motion of a rigid body, applied on top of its initial `Transform`. Rotations
+/// are about the body origin.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Kinematics {
+    #[default]
+    Static,
+    /// Constant world frame velocity
+    Translation { velocity: Vector3<f32> },
+    /// Constant world frame angular velocity, in radians per second
+    Rotation { angular_velocity: Vector3<f32> },
+    /// `amplitude sin(2π f t)` displacement and a rotation by `angular_amplitude sin(2π f t)`
+    /// radians about the direction of `angular_amplitude`, like a heaving or flapping plate.
+    Oscillation {
+        amplitude: Vector3<f32>,
+        angular_amplitude: Vector3<f32>,
+        frequency: f32,
+    },
+    /// Linear path through the keyframes, sorted by time, rotating at a constant rate between
+    /// them. The keyframes replace the initial transform and hold still outside their range.
+    Keyframes(Vec<Keyframe>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Keyframe {
+    pub time: f32,
+    pub transform: Transform,
+}
+
+/// Rigid body velocity in the world frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BodyVelocity {
+    pub linear: Vector3<f32>,
+    /// In radians per second about the body origin
+    pub angular: Vector3<f32>,
+}
+
+impl Default for BodyVelocity {
+    fn default() -> Self {
+        Self {
+            linear: Vector3::zero(),
+            angular: Vector3::zero(),
+        }
+    }
+}
+
+impl BodyVelocity {
+    /// Velocity of the body point at `point`, `origin` being where the body origin is.
+    pub fn at(&self, point: Vector3<f32>, origin: Vector3<f32>) -> Vector3<f32> {
+        self.linear + self.angular.cross(point - origin)
+    }
+}
+
+impl Kinematics {
+    pub fn is_static(&self) -> bool {
+        *self == Kinematics::Static
+    }
+
+    /// Where the body starting at `initial` is at `time`.
+    pub fn transform_at(&self, initial: &Transform, time: f32) -> Transform {
+        match self {
+            Kinematics::Static => *initial,
+            Kinematics::Translation { velocity } => {
+                initial.with_translation(initial.translation + velocity * time)
+            }
+            Kinematics::Rotation { angular_velocity } => {
+                initial.with_rotation(rotation(*angular_velocity * time) * initial.rotation)
+            }
+            Kinematics::Oscillation {
+                amplitude,
+                angular_amplitude,
+                frequency,
+            } => {
+                let phase = (2.0 * PI * frequency * time).sin();
+                initial
+                    .with_translation(initial.translation + amplitude * phase)
+                    .with_rotation(rotation(angular_amplitude * phase) * initial.rotation)
+            }
+            Kinematics::Keyframes(keyframes) => match Kinematics::segment(keyframes, time) {
+                Segment::Before(keyframe) | Segment::After(keyframe) => keyframe.transform,
+                Segment::Between(a, b) => {
+                    let s = (time - a.time) / (b.time - a.time);
+                    let (translation, turn) = Kinematics::difference(a, b);
+                    a.transform
+                        .with_translation(a.transform.translation + translation * s)
+                        .with_rotation(rotation(turn * s) * a.transform.rotation)
+                }
+                Segment::Empty => *initial,
+            },
+        }
+    }
+
+    /// Velocity of the body at `time`.
+    pub fn velocity_at(&self, time: f32) -> BodyVelocity {
+        match self {
+            Kinematics::Static => BodyVelocity::default(),
+            Kinematics::Translation { velocity } => BodyVelocity {
+                linear: *velocity,
+                ..Default::default()
+            },
+            Kinematics::Rotation { angular_velocity } => BodyVelocity {
+                angular: *angular_velocity,
+                ..Default::default()
+            },
+            Kinematics::Oscillation {
+                amplitude,
+                angular_amplitude,
+                frequency,
+            } => {
+                let rate = 2.0 * PI * frequency * (2.0 * PI * frequency * time).cos();
+                BodyVelocity {
+                    linear: amplitude * rate,
+                    angular: angular_amplitude * rate,
+                }
+            }
+            Kinematics::Keyframes(keyframes) => match Kinematics::segment(keyframes, time) {
+                Segment::Between(a, b) => {
+                    let (translation, turn) = Kinematics::difference(a, b);
+                    let duration = b.time - a.time;
+                    BodyVelocity {
+                        linear: translation / duration,
+                        angular: turn / duration,
+                    }
+                }
+                _ => BodyVelocity::default(),
+            },
+        }
+    }
+
+    fn segment(keyframes: &[Keyframe], time: f32) -> Segment<'_> {
+        let (Some(first), Some(last)) = (keyframes.first(), keyframes.last()) else {
+            return Segment::Empty;
+        };
+        if time <= first.time {
+            return Segment::Before(first);
+        }
+        keyframes
+            .windows(2)
+            .find(|pair| time < pair[1].time && pair[1].time > pair[0].time)
+            .map_or(Segment::After(last), |pair| {
+                Segment::Between(&pair[0], &pair[1])
+            })
+    }
+
+    /// Translation and rotation vector, axis times angle, taking `a` to `b` the short way.
+    fn difference(a: &Keyframe, b: &Keyframe) -> (Vector3<f32>, Vector3<f32>) {
+        let translation = b.transform.translation - a.transform.translation;
+        let mut turn = b.transform.rotation * a.transform.rotation.conjugate();
+        if turn.s < 0.0 {
+            turn = -turn;
+        }
+        let sin = turn.v.magnitude();
+        if sin < 1e-7 {
+            return (translation, Vector3::zero());
+        }
+        let angle = 2.0 * sin.atan2(turn.s);
+        (translation, turn.v / sin * angle)
+    }
+}
+
+enum Segment<'a> {
+    Empty,
+    Before(&'a Keyframe),
+    Between(&'a Keyframe, &'a Keyframe),
+    After(&'a Keyframe),
+}
+
+/// Rotation by `|turn|` radians about `turn`.
+pub fn rotation(turn: Vector3<f32>) -> Quaternion<f32> {
+    let angle = turn.magnitude();
+    if angle < 1e-7 {
+        return Quaternion::new(1.0, 0.0, 0.0, 0.0);
+    }
+    Quaternion::from_axis_angle(turn / angle, Rad(angle))
+}
@@ -0,0 +1,124 @@
+use cgmath::{InnerSpace, Matrix3, Vector3, Vector4, Zero};
+
+use crate::structures::vortex::Vortex;
+
+/// Panels are evaluated analytically within this many panel radii of their centroid, and
+/// as the point vortex of their total strength beyond, where the two differ by less than
+/// about a percent.
+pub const NEAR_FIELD_RADII: f32 = 10.0;
+
+/// Orthonormal tangents of a unit `normal`, Duff et al. 2017. `panel.glsl` builds the same
+/// ones, so the corners packed by either side unpack on the other.
+pub fn tangents(normal: Vector3<f32>) -> (Vector3<f32>, Vector3<f32>) {
+    let sign = if normal.z >= 0.0 { 1.0 } else { -1.0 };
+    let a = -1.0 / (sign + normal.z);
+    let b = normal.x * normal.y * a;
+    (
+        Vector3::new(
+            1.0 + sign * normal.x * normal.x * a,
+            sign * b,
+            -sign * normal.x,
+        ),
+        Vector3::new(b, sign + normal.y * normal.y * a, -normal.y),
+    )
+}
+
+/// Packs the corners of a triangle, relative to its centroid, into the `lifetime` of its
+/// boundary vortex: the first two in the tangents of `normal`, the third is minus their sum.
+pub fn pack_corners(normal: Vector3<f32>, corners: [Vector3<f32>; 3]) -> Vector4<f32> {
+    let (t1, t2) = tangents(normal.normalize());
+    Vector4::new(
+        corners[0].dot(t1),
+        corners[0].dot(t2),
+        corners[1].dot(t1),
+        corners[1].dot(t2),
+    )
+}
+
+/// The corners of `vortex` relative to its position if it is a panel, a boundary vortex
+/// with a normal and its corners in `lifetime`. Active vorticies have no normal, and
+/// boundary vorticies on vertices no corners.
+pub fn corners(vortex: &Vortex) -> Option<[Vector3<f32>; 3]> {
+    let normal = vortex.normal.truncate();
+    if normal.magnitude2() == 0.0 || vortex.lifetime == Vector4::zero() {
+        return None;
+    }
+    let (t1, t2) = tangents(normal.normalize());
+    let l = vortex.lifetime;
+    let a = t1 * l.x + t2 * l.y;
+    let b = t1 * l.z + t2 * l.w;
+    Some([a, b, -a - b])
+}
+
+/// `lifetime` of the panel `vortex` once its normal is rotated by `rotation`, its corners
+/// rotated along.
+pub fn rotate_corners(vortex: &Vortex, rotation: Matrix3<f32>) -> Vector4<f32> {
+    match corners(vortex) {
+        Some(corners) => pack_corners(
+            rotation * vortex.normal.truncate(),
+            corners.map(|corner| rotation * corner),
+        ),
+        None => vortex.lifetime,
+    }
+}
+
+/// `∫ (x - y) / |x - y|³ dA` over the triangle `corners`, whose winding gives the normal.
+/// The tangential part sums the edges, the normal part is the solid angle the triangle
+/// subtends. On the plane of the triangle it is the principal value, the normal part left
+/// out, so a panel induces the mean of the velocities on its two sides at its centroid.
+pub fn influence(point: Vector3<f32>, corners: [Vector3<f32>; 3]) -> Vector3<f32> {
+    let normal = (corners[1] - corners[0])
+        .cross(corners[2] - corners[0])
+        .normalize();
+    let r = corners.map(|corner| corner - point);
+    let d = r.map(|r| r.magnitude());
+
+    let mut tangential = Vector3::zero();
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+        let edge = corners[j] - corners[i];
+        let length = edge.magnitude();
+        // Points on the edge itself get no contribution from it.
+        let denominator = d[i] + d[j] - length;
+        if denominator <= 1e-6 * length {
+            continue;
+        }
+        let outward = edge.cross(normal) / length;
+        tangential += outward * ((d[i] + d[j] + length) / denominator).ln();
+    }
+
+    let triple = r[0].dot(r[1].cross(r[2]));
+    let denominator =
+        d[0] * d[1] * d[2] + r[0].dot(r[1]) * d[2] + r[0].dot(r[2]) * d[1] + r[1].dot(r[2]) * d[0];
+    let solid_angle = if triple.abs() <= 1e-6 * d[0] * d[1] * d[2] {
+        0.0
+    } else {
+        -2.0 * triple.atan2(denominator)
+    };
+    tangential + normal * solid_angle
+}
+
+/// Velocity at `point` of the panel `vortex` with the given `corners`: a constant vortex
+/// sheet of strength `vorticity / area` over the triangle, the singular Biot–Savart kernel
+/// integrated exactly.
+pub fn get_velocity(
+    point: Vector3<f32>,
+    vortex: &Vortex,
+    corners: [Vector3<f32>; 3],
+) -> Vector3<f32> {
+    let center = vortex.position.truncate();
+    let sheet = vortex.vorticity.truncate() / vortex.normal.w;
+    -sheet.cross(influence(point, corners.map(|corner| center + corner)))
+}
+
+/// Whether `point` is close enough to the panel with `corners` around `center` for
+/// `get_velocity` to differ from the point vortex at the centroid, which `Kernel` uses
+/// beyond and for the velocity gradient of a panel everywhere.
+pub fn is_near(point: Vector3<f32>, center: Vector3<f32>, corners: [Vector3<f32>; 3]) -> bool {
+    let radius = corners
+        .iter()
+        .map(|corner| corner.magnitude2())
+        .fold(0.0f32, f32::max)
+        .sqrt();
+    (point - center).magnitude() < NEAR_FIELD_RADII * radius
+}
@@ -0,0 +1,229 @@
+use cgmath::{InnerSpace, Vector3};
+use rayon::prelude::*;
+
+use crate::structures::vortex::Vortex;
+
+use super::{
+    kernel::{Kernel, STRENGTH_TO_CIRCULATION},
+    treecode::{Evaluator, VelocityField},
+};
+
+/// Recovers the pressure of the fluid, at rest far away, from its vortex elements.
+///
+/// The total head `H = p / ρ + |u|² / 2` solves `∇²H = ∇·(u × ω)`, which the free space
+/// Green's function turns into a sum over the vortex elements, each moving with its own
+/// velocity and the boundary vorticies with their bodies. That leaves out the vorticity the
+/// boundaries generate, whose rate `Ġ`, the change of the boundary strengths in the body
+/// frames, adds the unsteady Bernoulli term `χ`, `∇χ = -u[Ġ]` in the fluid, integrated along
+/// a ray to infinity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pressure {
+    pub density: f32,
+    /// Pressure far from the bodies
+    pub reference_pressure: f32,
+    /// Speed the pressure coefficient is relative to
+    pub reference_speed: f32,
+    pub kernel: Kernel,
+    pub evaluator: Evaluator,
+    /// Samples of the ray integral of the unsteady term
+    pub ray_samples: usize,
+    /// Half the ray samples are closer than this, about the size of the bodies
+    pub ray_length: f32,
+}
+
+impl Pressure {
+    pub fn new() -> Pressure {
+        Pressure {
+            density: 1.0,
+            reference_pressure: 0.0,
+            reference_speed: 1.0,
+            kernel: Kernel::default(),
+            evaluator: Evaluator::default(),
+            ray_samples: 64,
+            ray_length: 1.0,
+        }
+    }
+
+    pub fn with_density(mut self, density: f32) -> Pressure {
+        self.density = density;
+        self
+    }
+
+    pub fn with_reference_pressure(mut self, reference_pressure: f32) -> Pressure {
+        self.reference_pressure = reference_pressure;
+        self
+    }
+
+    pub fn with_reference_speed(mut self, reference_speed: f32) -> Pressure {
+        self.reference_speed = reference_speed;
+        self
+    }
+
+    /// The kernel the vorticies are evaluated with, `BoundaryVorticies::with_pressure` sets
+    /// the one of its scene.
+    pub fn with_kernel(mut self, kernel: Kernel) -> Pressure {
+        self.kernel = kernel;
+        self
+    }
+
+    pub fn with_evaluator(mut self, evaluator: Evaluator) -> Pressure {
+        self.evaluator = evaluator;
+        self
+    }
+
+    pub fn with_ray(mut self, ray_samples: usize, ray_length: f32) -> Pressure {
+        self.ray_samples = ray_samples;
+        self.ray_length = ray_length;
+        self
+    }
+
+    /// `(p - p∞) / (ρ U² / 2)` with `U` the `reference_speed`.
+    pub fn coefficient(&self, pressure: f32) -> f32 {
+        (pressure - self.reference_pressure)
+            / (0.5 * self.density * self.reference_speed * self.reference_speed)
+    }
+
+    /// `sources` are the active vorticies followed by the boundary vorticies, all in the
+    /// world frame. The active ones move with the flow, the boundary ones with their bodies at
+    /// `boundary_velocities`. `rates` are the boundary vorticies again, with the rate of
+    /// change of their body frame strengths, turned to the world frame, as the vorticity.
+    pub fn field<'a>(
+        &self,
+        sources: &'a [Vortex],
+        boundary_velocities: &[Vector3<f32>],
+        rates: &'a [Vortex],
+    ) -> PressureField<'a> {
+        let velocity_field = self.evaluator.prepare(sources, &self.kernel);
+        let active = sources.len() - boundary_velocities.len();
+        let mut velocities = sources[..active]
+            .par_iter()
+            .enumerate()
+            .map(|(i, vortex)| velocity_field.get_velocity(vortex.position.truncate(), Some(i)))
+            .collect::<Vec<_>>();
+        velocities.extend(boundary_velocities);
+        PressureField {
+            pressure: *self,
+            sources,
+            velocities,
+            velocity_field,
+            rate_field: self.evaluator.prepare(rates, &self.kernel),
+        }
+    }
+}
+
+/// The vortex elements of a `Pressure::field`, ready to be sampled.
+pub struct PressureField<'a> {
+    pressure: Pressure,
+    sources: &'a [Vortex],
+    /// Velocity each of `sources` moves with
+    velocities: Vec<Vector3<f32>>,
+    velocity_field: VelocityField<'a>,
+    rate_field: VelocityField<'a>,
+}
+
+impl PressureField<'_> {
+    pub fn velocity(&self, point: Vector3<f32>) -> Vector3<f32> {
+        self.velocity_field.get_velocity(point, None)
+    }
+
+    /// The part of `H - H∞` that moves with the vortex elements, the free space solution of
+    /// `∇²H = ∇·(u × ω)`.
+    pub fn total_head(&self, point: Vector3<f32>) -> f32 {
+        let kernel = &self.pressure.kernel;
+        self.sources
+            .iter()
+            .zip(&self.velocities)
+            .map(|(source, velocity)| {
+                let vorticity = source.vorticity.truncate();
+                let diff = point - source.position.truncate();
+                let distance = diff.magnitude();
+                if vorticity.magnitude() < 0.0001 || distance < 0.0001 {
+                    return 0.0;
+                }
+                -kernel.factor(distance) * velocity.cross(vorticity).dot(diff)
+            })
+            .sum()
+    }
+
+    /// `χ` at `point`, integrated along the ray leaving it in `direction`, which has to stay
+    /// in the fluid. A zero `direction` takes the ray along `x`.
+    pub fn unsteady_head(&self, point: Vector3<f32>, direction: Vector3<f32>) -> f32 {
+        let Pressure {
+            ray_samples,
+            ray_length,
+            ..
+        } = self.pressure;
+        let direction = if direction.magnitude2() > 0.0 {
+            direction.normalize()
+        } else {
+            Vector3::unit_x()
+        };
+        (0..ray_samples)
+            .map(|k| {
+                // `s = L τ / (1 - τ)` maps the whole ray onto `τ ∈ [0, 1)`.
+                let tau = (k as f32 + 0.5) / ray_samples as f32;
+                let s = ray_length * tau / (1.0 - tau);
+                let ds = ray_length / ((1.0 - tau) * (1.0 - tau) * ray_samples as f32);
+                let velocity = self.rate_field.get_velocity(point + direction * s, None);
+                velocity.dot(direction) * ds
+            })
+            .sum()
+    }
+
+    /// Pressure at `point` in the fluid, see `unsteady_head` for `direction`.
+    pub fn pressure(&self, point: Vector3<f32>, direction: Vector3<f32>) -> f32 {
+        let velocity = self.velocity(point);
+        let head = self.total_head(point) + self.unsteady_head(point, direction);
+        self.pressure.reference_pressure
+            + self.pressure.density * (head - 0.5 * velocity.magnitude2())
+    }
+
+    /// Pressure on the fluid side of the boundary vortex `sources[index]`, a panel with the
+    /// face area in `normal.w`, see `physics::panel`. Its own strength, spread over the face as
+    /// a sheet, makes the velocity and the head jump from the mean across the sheet, what the
+    /// other elements induce, to the outside value.
+    /// Vorticies without an area only get the mean, with the ray leaving along their normal,
+    /// or away from the world origin without one.
+    pub fn surface_pressure(&self, index: usize) -> f32 {
+        let vortex = &self.sources[index];
+        let point = vortex.position.truncate();
+        let normal = vortex.normal.truncate();
+        if normal.magnitude2() == 0.0 {
+            return self.pressure(point, point);
+        }
+        if vortex.normal.w <= 0.0 {
+            return self.pressure(point, normal);
+        }
+        let normal = normal.normalize();
+        let sheet = vortex.vorticity.truncate() * STRENGTH_TO_CIRCULATION / vortex.normal.w;
+        let velocity = self.velocity_field.get_velocity(point, Some(index));
+        let velocity = velocity + sheet.cross(normal) * 0.5;
+        let jump = self.velocities[index].cross(sheet).dot(normal) * 0.5;
+        let head = self.total_head(point) + jump + self.unsteady_head(point, normal);
+        self.pressure.reference_pressure
+            + self.pressure.density * (head - 0.5 * velocity.magnitude2())
+    }
+
+    /// `surface_pressure` of the boundary vorticies, the last `count` sources.
+    pub fn surface_pressures(&self, count: usize) -> Vec<f32> {
+        let first = self.sources.len() - count;
+        (first..self.sources.len())
+            .into_par_iter()
+            .map(|index| self.surface_pressure(index))
+            .collect()
+    }
+
+    /// `Pressure::coefficient` of `surface_pressures`.
+    pub fn surface_coefficients(&self, count: usize) -> Vec<f32> {
+        self.surface_pressures(count)
+            .into_iter()
+            .map(|pressure| self.pressure.coefficient(pressure))
+            .collect()
+    }
+}
+
+impl Default for Pressure {
+    fn default() -> Self {
+        Pressure::new()
+    }
+}
@@ -0,0 +1,121 @@
+use std::f32::consts::PI;
+
+use cgmath::{Matrix, Matrix3, SquareMatrix, Vector3, Zero};
+
+use crate::structures::{transform::Transform, vortex::Vortex};
+
+use super::{
+    forces::{momentum_rate, DisplacedFluid},
+    kinematics::{rotation, BodyVelocity},
+};
+
+/// Mass properties of a `BoundaryBody` moved by the fluid. The hydrodynamic force and moment
+/// come from the rate of change of the impulse of the body's boundary vorticies and the free
+/// vorticity, see `DisplacedFluid::momentum`. It includes the added mass, so the coupling is
+/// explicit: bodies lighter than their added mass, half the displaced fluid for a sphere,
+/// need to be stepped with care or diverge.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RigidBody {
+    pub mass: f32,
+    /// Inertia tensor about the body origin, the center of mass, in the body frame
+    pub inertia: Matrix3<f32>,
+    /// Volume of fluid the body displaces
+    pub volume: f32,
+    pub fluid_density: f32,
+    /// Acceleration of gravity, the body gets its weight and its buoyancy
+    pub gravity: Vector3<f32>,
+    /// Last hydrodynamic force in the world frame
+    pub force: Vector3<f32>,
+    /// Last hydrodynamic moment about the body origin in the world frame
+    pub moment: Vector3<f32>,
+    /// `momentum` at the last step
+    pub last_momentum: Option<(Vector3<f32>, Vector3<f32>)>,
+}
+
+impl RigidBody {
+    pub fn new(mass: f32, inertia: Matrix3<f32>, volume: f32) -> RigidBody {
+        RigidBody {
+            mass,
+            inertia,
+            volume,
+            fluid_density: 1.0,
+            gravity: Vector3::zero(),
+            force: Vector3::zero(),
+            moment: Vector3::zero(),
+            last_momentum: None,
+        }
+    }
+
+    /// Uniform sphere of `radius` and `density` centered on the body origin.
+    pub fn solid_sphere(radius: f32, density: f32) -> RigidBody {
+        let volume = 4.0 / 3.0 * PI * radius * radius * radius;
+        let mass = density * volume;
+        RigidBody::new(
+            mass,
+            Matrix3::from_scale(0.4 * mass * radius * radius),
+            volume,
+        )
+    }
+
+    pub fn with_fluid_density(mut self, fluid_density: f32) -> RigidBody {
+        self.fluid_density = fluid_density;
+        self
+    }
+
+    pub fn with_gravity(mut self, gravity: Vector3<f32>) -> RigidBody {
+        self.gravity = gravity;
+        self
+    }
+
+    /// Inertia tensor about the body origin in the world frame.
+    pub fn world_inertia(&self, transform: &Transform) -> Matrix3<f32> {
+        let rotation = transform.rotation_matrix();
+        rotation * self.inertia * rotation.transpose()
+    }
+
+    /// The fluid the body displaces, with the mass distribution of the body.
+    pub fn displaced_fluid(&self) -> DisplacedFluid {
+        DisplacedFluid::new(self.fluid_density)
+            .with_volume(self.volume, self.inertia * (self.volume / self.mass))
+    }
+
+    /// See `DisplacedFluid::momentum`.
+    pub fn momentum(
+        &self,
+        vorticies: &[Vortex],
+        free: &[Vortex],
+        transform: &Transform,
+        velocity: &BodyVelocity,
+    ) -> (Vector3<f32>, Vector3<f32>) {
+        self.displaced_fluid()
+            .momentum(vorticies, free, transform, velocity)
+    }
+
+    /// Takes the force and moment of the fluid from the change of `momentum` over `dt`
+    /// and advances `transform` and `velocity` with them, semi-implicit Euler.
+    pub fn step(
+        &mut self,
+        vorticies: &[Vortex],
+        free: &[Vortex],
+        transform: &mut Transform,
+        velocity: &mut BodyVelocity,
+        dt: f32,
+    ) {
+        let momentum = self.momentum(vorticies, free, transform, velocity);
+        if let Some(last) = self.last_momentum.replace(momentum) {
+            (self.force, self.moment) = momentum_rate(last, momentum, transform.translation, dt);
+        }
+
+        let weight = self.gravity * (self.mass - self.fluid_density * self.volume);
+        velocity.linear += (self.force + weight) / self.mass * dt;
+        let inertia = self.world_inertia(transform);
+        let gyroscopic = velocity.angular.cross(inertia * velocity.angular);
+        if let Some(inverse) = inertia.invert() {
+            velocity.angular += inverse * (self.moment - gyroscopic) * dt;
+        }
+
+        *transform = transform
+            .with_translation(transform.translation + velocity.linear * dt)
+            .with_rotation(rotation(velocity.angular * dt) * transform.rotation);
+    }
+}
@@ -0,0 +1,232 @@
+use std::ops::Range;
+
+use cgmath::{InnerSpace, Vector4};
+
+use crate::structures::vortex::Vortex;
+
+/// `lifetime.z` of a shed vortex. When its lifetime runs out it goes dormant, zero strength,
+/// until its slot is shed into again, instead of respawning at random.
+pub const SHED: f32 = 1.0;
+
+/// Which boundary vorticies release vorticity into the flow.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SheddingCriterion {
+    /// Every boundary vortex, the whole surface sheds its vorticity.
+    #[default]
+    Surface,
+    /// Only the vorticies at edges where the normals of neighbouring vorticies turn by more
+    /// than `angle` radians, where the flow separates. A `rate` above `1 / dt` releases the
+    /// whole edge strength every time, the Kutta-type condition of the flow leaving the edge
+    /// smoothly.
+    SharpEdges { angle: f32 },
+}
+
+/// Converts part of the boundary vorticity into active vorticies every step, written into
+/// `slots` of the active vortex buffer, which `ActiveVorticies::with_shedding_capacity`
+/// reserves. The slots are reused in a ring, overwriting the oldest shed vortex.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shedding {
+    pub slots: Range<usize>,
+    /// Fraction of the strength of a candidate shed per unit time
+    pub rate: f32,
+    /// Most vorticies shed per body and step. The candidates take turns, so each sheds a
+    /// proportionally larger part when it does.
+    pub budget: usize,
+    /// How far off the surface, along the normal, the shed vorticies are placed
+    pub offset: f32,
+    /// Lifetime of a shed vortex
+    pub lifetime: f32,
+    pub criterion: SheddingCriterion,
+}
+
+impl Shedding {
+    pub fn new(slots: Range<usize>) -> Shedding {
+        Shedding {
+            slots,
+            rate: 1.0,
+            budget: 64,
+            offset: 0.05,
+            lifetime: 5.0,
+            criterion: SheddingCriterion::default(),
+        }
+    }
+
+    pub fn with_rate(mut self, rate: f32) -> Shedding {
+        self.rate = rate;
+        self
+    }
+
+    pub fn with_budget(mut self, budget: usize) -> Shedding {
+        self.budget = budget;
+        self
+    }
+
+    pub fn with_offset(mut self, offset: f32) -> Shedding {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_lifetime(mut self, lifetime: f32) -> Shedding {
+        self.lifetime = lifetime;
+        self
+    }
+
+    pub fn with_criterion(mut self, criterion: SheddingCriterion) -> Shedding {
+        self.criterion = criterion;
+        self
+    }
+
+    /// Content of a slot nothing was shed into yet.
+    pub fn dormant_vortex() -> Vortex {
+        Vortex {
+            lifetime: Vector4::new(0.0, 0.0, SHED, 0.0),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_dormant(vortex: &Vortex) -> bool {
+        vortex.lifetime.z == SHED && vortex.lifetime.x <= 0.0
+    }
+
+    /// Indices of the vorticies of a boundary set that `criterion` lets shed.
+    pub fn candidates(&self, vorticies: &[Vortex]) -> Vec<u32> {
+        match self.criterion {
+            SheddingCriterion::Surface => (0..vorticies.len() as u32).collect(),
+            SheddingCriterion::SharpEdges { angle } => (0..vorticies.len())
+                .filter(|i| Shedding::turn(vorticies, *i) > angle)
+                .map(|i| i as u32)
+                .collect(),
+        }
+    }
+
+    /// Largest angle between the normal of vortex `i` and the ones of its neighbours, the
+    /// vorticies closer than one and a half times its nearest one. Vertices duplicated at a
+    /// hard edge are coincident and always neighbours.
+    fn turn(vorticies: &[Vortex], i: usize) -> f32 {
+        let position = vorticies[i].position.truncate();
+        let normal = vorticies[i].normal.truncate();
+        if normal.magnitude2() == 0.0 {
+            return 0.0;
+        }
+        let distances = vorticies
+            .iter()
+            .map(|v| (v.position.truncate() - position).magnitude())
+            .collect::<Vec<_>>();
+        let nearest = distances
+            .iter()
+            .enumerate()
+            .filter(|(j, d)| *j != i && **d > 1e-6)
+            .map(|(_, d)| *d)
+            .fold(f32::MAX, f32::min);
+        vorticies
+            .iter()
+            .zip(distances)
+            .enumerate()
+            .filter(|(j, (v, d))| {
+                *j != i && *d < 1.5 * nearest && v.normal.truncate().magnitude2() > 0.0
+            })
+            .map(|(_, (v, _))| normal.angle(v.normal.truncate()).0)
+            .fold(0.0, f32::max)
+    }
+
+    /// The vortex `fraction` of `boundary`, a boundary vortex in the world frame, sheds.
+    pub fn shed_vortex(&self, boundary: &Vortex, fraction: f32) -> Vortex {
+        let normal = boundary.normal.truncate();
+        let offset = if normal.magnitude2() > 0.0 {
+            normal.normalize() * self.offset
+        } else {
+            normal
+        };
+        Vortex {
+            position: (boundary.position.truncate() + offset).extend(1.0),
+            vorticity: (boundary.vorticity.truncate() * fraction).extend(1.0),
+            lifetime: Vector4::new(self.lifetime, self.lifetime, SHED, 0.0),
+            ..Default::default()
+        }
+    }
+}
+
+/// One step of shedding of a boundary set: `count` candidates from the `first` on, wrapping
+/// around, shed `fraction` of their strength into the slots from `slot` on, also wrapping.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Release {
+    pub first: usize,
+    pub count: usize,
+    pub slot: usize,
+    pub fraction: f32,
+}
+
+/// Which candidates shed next and where to, for every set of every boundary body.
+#[derive(Debug, Clone)]
+pub struct Shedder {
+    pub shedding: Shedding,
+    /// `Shedding::candidates` of every set of every body
+    pub candidates: Vec<Vec<Vec<u32>>>,
+    /// Next candidate of every body
+    pub rounds: Vec<usize>,
+    /// Next slot, counted from `shedding.slots.start`
+    pub cursor: usize,
+}
+
+impl Shedder {
+    /// `bodies` are the boundary sets of every body.
+    pub fn new(shedding: Shedding, bodies: &[&[Vec<Vortex>]]) -> Shedder {
+        let candidates = bodies
+            .iter()
+            .map(|sets| sets.iter().map(|set| shedding.candidates(set)).collect())
+            .collect();
+        Shedder {
+            shedding,
+            candidates,
+            rounds: vec![0; bodies.len()],
+            cursor: 0,
+        }
+    }
+
+    /// Advances the turns of `body`, shedding from `set`, by one step of `dt`.
+    pub fn next(&mut self, body: usize, set: usize, dt: f32) -> Option<Release> {
+        let candidate_count = self.candidates[body][set].len();
+        let capacity = self.shedding.slots.len();
+        let count = self.shedding.budget.min(candidate_count).min(capacity);
+        if count == 0 {
+            return None;
+        }
+        let fraction = self.shedding.rate * dt * candidate_count as f32 / count as f32;
+        let release = Release {
+            first: self.rounds[body] % candidate_count,
+            count,
+            slot: self.shedding.slots.start + self.cursor,
+            fraction: fraction.min(1.0),
+        };
+        self.rounds[body] = (release.first + count) % candidate_count;
+        self.cursor = (self.cursor + count) % capacity;
+        Some(release)
+    }
+
+    /// CPU counterpart of `boundary_shedding.comp`: the slots and vorticies `body` sheds from
+    /// `world`, its current set `set` in the world frame.
+    pub fn shed(
+        &mut self,
+        body: usize,
+        set: usize,
+        world: &[Vortex],
+        dt: f32,
+    ) -> Vec<(usize, Vortex)> {
+        let Some(release) = self.next(body, set, dt) else {
+            return vec![];
+        };
+        let candidates = &self.candidates[body][set];
+        let slots = &self.shedding.slots;
+        (0..release.count)
+            .map(|j| {
+                let candidate = candidates[(release.first + j) % candidates.len()] as usize;
+                let slot = slots.start + (release.slot - slots.start + j) % slots.len();
+                (
+                    slot,
+                    self.shedding
+                        .shed_vortex(&world[candidate], release.fraction),
+                )
+            })
+            .collect()
+    }
+}
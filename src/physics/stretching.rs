@@ -0,0 +1,28 @@
+use cgmath::{Matrix, Matrix3, Vector3, Zero};
+
+/// Discretization of the stretching term. The discriminants are the values `vortex.comp`
+/// switches on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StretchingScheme {
+    /// `(α · ∇) u`
+    #[default]
+    Classical = 0,
+    /// `(∇u)ᵀ α`, conserves total vorticity
+    Transpose = 1,
+    /// Average of the two above
+    Mixed = 2,
+    /// Leaves the strengths to the other terms
+    Disabled = 3,
+}
+
+impl StretchingScheme {
+    /// `dα/dt` for a vortex of strength `vorticity` in a flow with velocity gradient `gradient`.
+    pub fn stretching(&self, gradient: Matrix3<f32>, vorticity: Vector3<f32>) -> Vector3<f32> {
+        match self {
+            StretchingScheme::Classical => gradient * vorticity,
+            StretchingScheme::Transpose => gradient.transpose() * vorticity,
+            StretchingScheme::Mixed => (gradient + gradient.transpose()) * vorticity * 0.5,
+            StretchingScheme::Disabled => Vector3::zero(),
+        }
+    }
+}
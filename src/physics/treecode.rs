@@ -0,0 +1,386 @@
+use cgmath::{InnerSpace, Matrix3, Vector3, Zero};
+use rayon::prelude::*;
+
+use crate::structures::vortex::Vortex;
+
+use super::kernel::Kernel;
+
+/// How the Biot–Savart sum over a set of vorticies is evaluated on the CPU.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Evaluator {
+    /// All pairs, `O(N M)`
+    #[default]
+    Direct,
+    /// Barnes–Hut octree, `O(M log N)`. A cell of edge length `s` seen from distance `d`
+    /// is replaced by its total strength at its centroid when `s / d < theta`, so `theta = 0`
+    /// is exact and larger values trade accuracy for speed. Cells with at most `leaf_size`
+    /// vorticies are always summed directly.
+    Treecode { theta: f32, leaf_size: usize },
+}
+
+impl Evaluator {
+    pub fn treecode(theta: f32) -> Evaluator {
+        Evaluator::Treecode {
+            theta,
+            leaf_size: 16,
+        }
+    }
+
+    /// Prepares `sources` for repeated evaluation, building the octree if needed.
+    pub fn prepare<'a>(&self, sources: &'a [Vortex], kernel: &Kernel) -> VelocityField<'a> {
+        match self {
+            Evaluator::Direct => VelocityField::Direct(sources, *kernel),
+            Evaluator::Treecode { theta, leaf_size } => {
+                VelocityField::Treecode(Octree::new(sources, *kernel, *theta, *leaf_size))
+            }
+        }
+    }
+
+    /// Velocities induced by `sources` at every point of `points`.
+    pub fn get_velocities(
+        &self,
+        points: &[Vector3<f32>],
+        sources: &[Vortex],
+        kernel: &Kernel,
+    ) -> Vec<Vector3<f32>> {
+        let field = self.prepare(sources, kernel);
+        points
+            .par_iter()
+            .map(|point| field.get_velocity(*point, None))
+            .collect()
+    }
+
+    /// `get_velocities` without the `MIN_STRENGTH` cutoff, so linear in the strengths of
+    /// `sources`.
+    pub fn get_linear_velocities(
+        &self,
+        points: &[Vector3<f32>],
+        sources: &[Vortex],
+        kernel: &Kernel,
+    ) -> Vec<Vector3<f32>> {
+        let field = self.prepare(sources, kernel);
+        points
+            .par_iter()
+            .map(|point| field.get_linear_velocity(*point, None))
+            .collect()
+    }
+}
+
+/// Velocity field of a set of vorticies, ready to be sampled with an `Evaluator`.
+pub enum VelocityField<'a> {
+    Direct(&'a [Vortex], Kernel),
+    Treecode(Octree),
+}
+
+impl VelocityField<'_> {
+    /// Velocity induced at `point`, leaving out the source at index `exclude`.
+    pub fn get_velocity(&self, point: Vector3<f32>, exclude: Option<usize>) -> Vector3<f32> {
+        match self {
+            VelocityField::Direct(sources, kernel) => sources
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| Some(*i) != exclude)
+                .map(|(_, source)| kernel.get_velocity(point, source))
+                .sum(),
+            VelocityField::Treecode(tree) => tree.get_velocity(point, exclude),
+        }
+    }
+
+    /// `get_velocity` without the `MIN_STRENGTH` cutoff.
+    pub fn get_linear_velocity(&self, point: Vector3<f32>, exclude: Option<usize>) -> Vector3<f32> {
+        match self {
+            VelocityField::Direct(sources, kernel) => sources
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| Some(*i) != exclude)
+                .map(|(_, source)| kernel.get_linear_velocity(point, source))
+                .sum(),
+            VelocityField::Treecode(tree) => tree.get_linear_velocity(point, exclude),
+        }
+    }
+
+    /// Jacobian of `get_velocity` at `point`, leaving out the source at index `exclude`.
+    pub fn get_velocity_gradient(
+        &self,
+        point: Vector3<f32>,
+        exclude: Option<usize>,
+    ) -> Matrix3<f32> {
+        match self {
+            VelocityField::Direct(sources, kernel) => sources
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| Some(*i) != exclude)
+                .map(|(_, source)| kernel.get_velocity_gradient(point, source))
+                .sum(),
+            VelocityField::Treecode(tree) => tree.get_velocity_gradient(point, exclude),
+        }
+    }
+
+    /// Calls `visit` with the index of every source within `radius` of `point`, for the
+    /// short range interactions.
+    pub fn for_each_neighbour<F>(&self, point: Vector3<f32>, radius: f32, visit: F)
+    where
+        F: FnMut(usize),
+    {
+        match self {
+            VelocityField::Direct(sources, _) => sources
+                .iter()
+                .enumerate()
+                .filter(|(_, source)| (source.position.truncate() - point).magnitude() <= radius)
+                .map(|(i, _)| i)
+                .for_each(visit),
+            VelocityField::Treecode(tree) => tree.for_each_neighbour(point, radius, visit),
+        }
+    }
+}
+
+struct Cell {
+    center: Vector3<f32>,
+    half_size: f32,
+    centroid: Vector3<f32>,
+    strength: Vector3<f32>,
+    start: usize,
+    end: usize,
+    children: Vec<usize>,
+}
+
+/// Barnes–Hut octree over a set of vorticies with monopole cell expansions.
+pub struct Octree {
+    cells: Vec<Cell>,
+    vorticies: Vec<Vortex>,
+    indices: Vec<usize>,
+    kernel: Kernel,
+    theta: f32,
+    leaf_size: usize,
+}
+
+const MAX_DEPTH: usize = 32;
+
+impl Octree {
+    pub fn new(vorticies: &[Vortex], kernel: Kernel, theta: f32, leaf_size: usize) -> Octree {
+        let mut tree = Octree {
+            cells: vec![],
+            vorticies: vorticies.to_vec(),
+            indices: (0..vorticies.len()).collect(),
+            kernel,
+            theta,
+            leaf_size: leaf_size.max(1),
+        };
+        if vorticies.is_empty() {
+            return tree;
+        }
+
+        let positions = vorticies.iter().map(|v| v.position.truncate());
+        let (min, max) = positions.fold(
+            (
+                Vector3::new(f32::MAX, f32::MAX, f32::MAX),
+                Vector3::new(f32::MIN, f32::MIN, f32::MIN),
+            ),
+            |(min, max), p| {
+                (
+                    Vector3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z)),
+                    Vector3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z)),
+                )
+            },
+        );
+        let extent = max - min;
+        let half_size = extent.x.max(extent.y).max(extent.z) * 0.5 + 0.0001;
+        tree.build((min + max) * 0.5, half_size, 0, vorticies.len(), 0);
+        tree
+    }
+
+    fn build(
+        &mut self,
+        center: Vector3<f32>,
+        half_size: f32,
+        start: usize,
+        end: usize,
+        depth: usize,
+    ) -> usize {
+        let (centroid, strength) = self.moments(start, end, center);
+        let index = self.cells.len();
+        self.cells.push(Cell {
+            center,
+            half_size,
+            centroid,
+            strength,
+            start,
+            end,
+            children: vec![],
+        });
+        if end - start <= self.leaf_size || depth >= MAX_DEPTH {
+            return index;
+        }
+
+        let octant = |p: Vector3<f32>| {
+            (p.x > center.x) as usize
+                | ((p.y > center.y) as usize) << 1
+                | ((p.z > center.z) as usize) << 2
+        };
+        let vorticies = &self.vorticies;
+        self.indices[start..end]
+            .sort_unstable_by_key(|i| octant(vorticies[*i].position.truncate()));
+
+        let mut children = vec![];
+        let mut child_start = start;
+        for o in 0..8 {
+            let child_end = child_start
+                + self.indices[child_start..end]
+                    .iter()
+                    .take_while(|i| octant(self.vorticies[**i].position.truncate()) == o)
+                    .count();
+            if child_end > child_start {
+                let quarter = half_size * 0.5;
+                let offset = Vector3::new(
+                    if o & 1 == 1 { quarter } else { -quarter },
+                    if o & 2 == 2 { quarter } else { -quarter },
+                    if o & 4 == 4 { quarter } else { -quarter },
+                );
+                children.push(self.build(
+                    center + offset,
+                    quarter,
+                    child_start,
+                    child_end,
+                    depth + 1,
+                ));
+            }
+            child_start = child_end;
+        }
+        self.cells[index].children = children;
+        index
+    }
+
+    /// Total strength and its centroid weighted by `|ω|`.
+    fn moments(
+        &self,
+        start: usize,
+        end: usize,
+        center: Vector3<f32>,
+    ) -> (Vector3<f32>, Vector3<f32>) {
+        let mut strength = Vector3::zero();
+        let mut weighted = Vector3::zero();
+        let mut weight = 0.0;
+        for i in &self.indices[start..end] {
+            let vortex = &self.vorticies[*i];
+            let vorticity = vortex.vorticity.truncate();
+            strength += vorticity;
+            weighted += vortex.position.truncate() * vorticity.magnitude();
+            weight += vorticity.magnitude();
+        }
+        if weight > 0.0 {
+            (weighted / weight, strength)
+        } else {
+            (center, strength)
+        }
+    }
+
+    /// Velocity induced at `point`, leaving out the vortex at index `exclude` of the
+    /// slice the tree was built from.
+    pub fn get_velocity(&self, point: Vector3<f32>, exclude: Option<usize>) -> Vector3<f32> {
+        let mut velocity = Vector3::zero();
+        self.walk(point, exclude, |source| {
+            velocity += self.kernel.get_velocity(point, source)
+        });
+        velocity
+    }
+
+    /// `get_velocity` without the `MIN_STRENGTH` cutoff, with the same cells accepted.
+    pub fn get_linear_velocity(&self, point: Vector3<f32>, exclude: Option<usize>) -> Vector3<f32> {
+        let mut velocity = Vector3::zero();
+        self.walk(point, exclude, |source| {
+            velocity += self.kernel.get_linear_velocity(point, source)
+        });
+        velocity
+    }
+
+    /// Jacobian of `get_velocity` at `point`, with the same cells accepted.
+    pub fn get_velocity_gradient(
+        &self,
+        point: Vector3<f32>,
+        exclude: Option<usize>,
+    ) -> Matrix3<f32> {
+        let mut gradient = Matrix3::zero();
+        self.walk(point, exclude, |source| {
+            gradient += self.kernel.get_velocity_gradient(point, source)
+        });
+        gradient
+    }
+
+    /// Calls `visit` with the index of every vortex within `radius` of `point`, opening only
+    /// the cells whose box reaches that far.
+    pub fn for_each_neighbour<F>(&self, point: Vector3<f32>, radius: f32, mut visit: F)
+    where
+        F: FnMut(usize),
+    {
+        if self.cells.is_empty() {
+            return;
+        }
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let cell = &self.cells[index];
+            let offset = point - cell.center;
+            let outside = Vector3::new(
+                (offset.x.abs() - cell.half_size).max(0.0),
+                (offset.y.abs() - cell.half_size).max(0.0),
+                (offset.z.abs() - cell.half_size).max(0.0),
+            );
+            if outside.magnitude() > radius {
+                continue;
+            }
+            if cell.children.is_empty() {
+                for i in &self.indices[cell.start..cell.end] {
+                    let position = self.vorticies[*i].position.truncate();
+                    if (position - point).magnitude() <= radius {
+                        visit(*i);
+                    }
+                }
+            } else {
+                stack.extend(cell.children.iter());
+            }
+        }
+    }
+
+    /// Calls `source` with every vortex of the opened leaves but `exclude`, and with a vortex
+    /// of the total strength at the centroid of every accepted cell.
+    fn walk<F>(&self, point: Vector3<f32>, exclude: Option<usize>, mut source: F)
+    where
+        F: FnMut(&Vortex),
+    {
+        if self.cells.is_empty() {
+            return;
+        }
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let cell = &self.cells[index];
+            if cell.children.is_empty() {
+                for i in &self.indices[cell.start..cell.end] {
+                    if Some(*i) != exclude {
+                        source(&self.vorticies[*i]);
+                    }
+                }
+            } else if self.is_far(cell, point) {
+                source(&Vortex {
+                    position: cell.centroid.extend(1.0),
+                    vorticity: cell.strength.extend(1.0),
+                    ..Default::default()
+                });
+            } else {
+                stack.extend(cell.children.iter());
+            }
+        }
+    }
+
+    /// Barnes–Hut acceptance. A cell containing `point` is always opened, so a vortex
+    /// excluded at its own position is reached through its leaf.
+    fn is_far(&self, cell: &Cell, point: Vector3<f32>) -> bool {
+        let offset = point - cell.center;
+        let inside = offset.x.abs() <= cell.half_size
+            && offset.y.abs() <= cell.half_size
+            && offset.z.abs() <= cell.half_size;
+        if inside {
+            return false;
+        }
+        let distance = (point - cell.centroid).magnitude();
+        2.0 * cell.half_size < self.theta * distance
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::{
+    structures::{
+        field_sample::FieldSample,
+        grid::{Grid, ScalarVolume},
+    },
+    util,
+};
+
+/// Scalar fields that single out coherent vortical structures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Identification {
+    QCriterion,
+    Lambda2,
+    Helicity,
+    VorticityMagnitude,
+}
+
+impl Identification {
+    pub const ALL: [Identification; 4] = [
+        Identification::QCriterion,
+        Identification::Lambda2,
+        Identification::Helicity,
+        Identification::VorticityMagnitude,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Identification::QCriterion => "q_criterion",
+            Identification::Lambda2 => "lambda2",
+            Identification::Helicity => "helicity",
+            Identification::VorticityMagnitude => "vorticity_magnitude",
+        }
+    }
+
+    pub fn evaluate(&self, sample: &FieldSample) -> f32 {
+        match self {
+            Identification::QCriterion => sample.q_criterion(),
+            Identification::Lambda2 => sample.lambda2(),
+            Identification::Helicity => sample.helicity(),
+            Identification::VorticityMagnitude => sample.vorticity_magnitude(),
+        }
+    }
+}
+
+/// Field samples at the points of a grid, from `CpuFieldSampler::sample_grid` or
+/// `FieldSampler::sample_grid`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridSamples {
+    pub grid: Grid,
+    pub samples: Vec<FieldSample>,
+}
+
+impl GridSamples {
+    pub fn volume(&self, identification: Identification) -> ScalarVolume {
+        ScalarVolume {
+            grid: self.grid,
+            values: self
+                .samples
+                .iter()
+                .map(|sample| identification.evaluate(sample))
+                .collect(),
+        }
+    }
+
+    /// Writes every `Identification`, and the velocity, as legacy VTK structured points.
+    pub fn write_vtk<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let Grid {
+            origin,
+            spacing,
+            dimensions: [nx, ny, nz],
+        } = self.grid;
+        writeln!(writer, "# vtk DataFile Version 3.0")?;
+        writeln!(writer, "vortex identification fields")?;
+        writeln!(writer, "ASCII")?;
+        writeln!(writer, "DATASET STRUCTURED_POINTS")?;
+        writeln!(writer, "DIMENSIONS {} {} {}", nx, ny, nz)?;
+        writeln!(writer, "ORIGIN {} {} {}", origin.x, origin.y, origin.z)?;
+        writeln!(writer, "SPACING {} {} {}", spacing, spacing, spacing)?;
+        writeln!(writer, "POINT_DATA {}", self.grid.count())?;
+        for identification in Identification::ALL {
+            writeln!(writer, "SCALARS {} float 1", identification.name())?;
+            writeln!(writer, "LOOKUP_TABLE default")?;
+            for value in self.volume(identification).values {
+                writeln!(writer, "{}", value)?;
+            }
+        }
+        writeln!(writer, "VECTORS velocity float")?;
+        for sample in &self.samples {
+            let u = sample.velocity;
+            writeln!(writer, "{} {} {}", u.x, u.y, u.z)?;
+        }
+        Ok(())
+    }
+
+    pub fn save_vtk(&self, path: &Path) -> io::Result<()> {
+        util::save_with(path, |writer| self.write_vtk(writer))
+    }
+}
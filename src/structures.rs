@@ -1,5 +1,11 @@
 pub mod computed_inverse;
 pub mod cube;
+pub mod field_sample;
+pub mod grid;
+pub mod kernel_info;
 pub mod particle;
+pub mod particle_emitter;
 pub mod ray;
+pub mod transform;
+pub mod triangle_mesh;
 pub mod vortex;
@@ -1,9 +1,95 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use log::{info, warn};
 use nalgebra::DMatrix;
 
 use serde::{Deserialize, Serialize};
 
+use crate::{
+    physics::{boundary_condition::BoundaryCondition, kernel::Kernel},
+    util,
+};
+
+use super::vortex::Vortex;
+
+/// Version of the layout of the boundary matrix and its inverse, part of the checksum. Bump
+/// it whenever `BoundaryVorticies::create_matrix` or `create_inverse` change what they
+/// compute, so the inverses cached by older versions are not loaded.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Boundary pseudo-inverse persisted with postcard, valid as long as `original_checksum`
+/// matches the layout it was computed for.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ComputedInverse {
     pub original_checksum: u32,
     pub inverse: DMatrix<f32>,
 }
+
+impl ComputedInverse {
+    /// crc32 of everything the inverse depends on: the `FORMAT_VERSION`, the boundary vortex
+    /// positions, normals, weights and panel corners, the kernel, the boundary condition and
+    /// the pseudo-inverse tolerance.
+    pub fn checksum(
+        vorticies: &[Vortex],
+        kernel: &Kernel,
+        boundary_condition: BoundaryCondition,
+        tolerance: f32,
+    ) -> u32 {
+        let mut hasher = crc32fast::Hasher::new();
+        hasher.update(&FORMAT_VERSION.to_le_bytes());
+        for vortex in vorticies {
+            let (p, n, c) = (vortex.position, vortex.normal, vortex.lifetime);
+            for component in [p.x, p.y, p.z, n.x, n.y, n.z, n.w, c.x, c.y, c.z, c.w] {
+                hasher.update(&component.to_le_bytes());
+            }
+        }
+        hasher.update(&(kernel.smoothing as u32).to_le_bytes());
+        hasher.update(&kernel.core_radius.to_le_bytes());
+        hasher.update(&(boundary_condition as u32).to_le_bytes());
+        hasher.update(&tolerance.to_le_bytes());
+        hasher.finalize()
+    }
+
+    /// Reads the inverse stored at `path`, `None` if there is none or it was computed for
+    /// a different checksum.
+    pub fn load(path: &Path, checksum: u32) -> Option<DMatrix<f32>> {
+        let bytes = fs::read(path).ok()?;
+        let computed: ComputedInverse = postcard::from_bytes(&bytes).ok()?;
+        (computed.original_checksum == checksum).then_some(computed.inverse)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let bytes = postcard::to_stdvec(self)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        util::save_with(path, |writer| writer.write_all(&bytes))
+    }
+
+    /// Loads the inverse from `path` if its checksum matches, otherwise computes it and
+    /// overwrites the stale file.
+    pub fn load_or_compute<F>(path: &Path, checksum: u32, compute: F) -> DMatrix<f32>
+    where
+        F: FnOnce() -> DMatrix<f32>,
+    {
+        if let Some(inverse) = ComputedInverse::load(path, checksum) {
+            return inverse;
+        }
+        let start = std::time::Instant::now();
+        let computed = ComputedInverse {
+            original_checksum: checksum,
+            inverse: compute(),
+        };
+        info!(
+            "Computed inverse {:08x} in {} s",
+            checksum,
+            start.elapsed().as_secs_f32()
+        );
+        if let Err(error) = computed.save(path) {
+            warn!("Could not cache inverse to {:?}: {}", path, error);
+        }
+        computed.inverse
+    }
+}
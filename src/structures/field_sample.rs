@@ -0,0 +1,67 @@
+use cgmath::{InnerSpace, Matrix, Matrix3, Vector3, Zero};
+use nalgebra::SymmetricEigen;
+
+/// Velocity and its derivatives at a point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldSample {
+    pub velocity: Vector3<f32>,
+    /// Jacobian `∂u_i / ∂x_j`, column `j` being the derivative along `x_j`
+    pub gradient: Matrix3<f32>,
+}
+
+impl Default for FieldSample {
+    fn default() -> Self {
+        Self {
+            velocity: Vector3::zero(),
+            gradient: Matrix3::zero(),
+        }
+    }
+}
+
+impl FieldSample {
+    /// Curl of the velocity.
+    pub fn vorticity(&self) -> Vector3<f32> {
+        let g = &self.gradient;
+        Vector3::new(g.y.z - g.z.y, g.z.x - g.x.z, g.x.y - g.y.x)
+    }
+
+    pub fn divergence(&self) -> f32 {
+        self.gradient.x.x + self.gradient.y.y + self.gradient.z.z
+    }
+
+    pub fn vorticity_magnitude(&self) -> f32 {
+        self.vorticity().magnitude()
+    }
+
+    /// `u · ω`, signed by the handedness of the swirl.
+    pub fn helicity(&self) -> f32 {
+        self.velocity.dot(self.vorticity())
+    }
+
+    /// Symmetric and antisymmetric parts of the gradient, the strain rate `S` and the spin
+    /// `Ω`.
+    pub fn strain_and_spin(&self) -> (Matrix3<f32>, Matrix3<f32>) {
+        let transpose = self.gradient.transpose();
+        (
+            (self.gradient + transpose) * 0.5,
+            (self.gradient - transpose) * 0.5,
+        )
+    }
+
+    /// `(|Ω|² - |S|²) / 2`, positive where rotation dominates strain.
+    pub fn q_criterion(&self) -> f32 {
+        let (strain, spin) = self.strain_and_spin();
+        let norm2 = |m: Matrix3<f32>| m.x.magnitude2() + m.y.magnitude2() + m.z.magnitude2();
+        0.5 * (norm2(spin) - norm2(strain))
+    }
+
+    /// Middle eigenvalue of `S² + Ω²`, negative inside vortex cores.
+    pub fn lambda2(&self) -> f32 {
+        let (strain, spin) = self.strain_and_spin();
+        let m = strain * strain + spin * spin;
+        let m = nalgebra::Matrix3::from_fn(|i, j| m[j][i]);
+        let mut eigenvalues = SymmetricEigen::new(m).eigenvalues;
+        eigenvalues.as_mut_slice().sort_by(f32::total_cmp);
+        eigenvalues[1]
+    }
+}
@@ -0,0 +1,71 @@
+use cgmath::Vector3;
+
+/// Uniform grid of sample points, `x` varying fastest like VTK structured points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Grid {
+    /// The first point, the corner with the smallest coordinates
+    pub origin: Vector3<f32>,
+    pub spacing: f32,
+    /// Number of points along each axis
+    pub dimensions: [usize; 3],
+}
+
+impl Grid {
+    pub fn new(origin: Vector3<f32>, spacing: f32, dimensions: [usize; 3]) -> Grid {
+        Grid {
+            origin,
+            spacing,
+            dimensions,
+        }
+    }
+
+    /// Grid with about `spacing` between the points that covers `min` to `max`.
+    pub fn from_bounds(min: Vector3<f32>, max: Vector3<f32>, spacing: f32) -> Grid {
+        let size = max - min;
+        let dimensions = [size.x, size.y, size.z].map(|size| (size / spacing).ceil() as usize + 1);
+        Grid::new(min, spacing, dimensions)
+    }
+
+    pub fn count(&self) -> usize {
+        self.dimensions.iter().product()
+    }
+
+    pub fn index(&self, i: usize, j: usize, k: usize) -> usize {
+        i + self.dimensions[0] * (j + self.dimensions[1] * k)
+    }
+
+    pub fn point(&self, i: usize, j: usize, k: usize) -> Vector3<f32> {
+        self.origin + Vector3::new(i as f32, j as f32, k as f32) * self.spacing
+    }
+
+    /// Every point, in the order of `index`.
+    pub fn points(&self) -> Vec<Vector3<f32>> {
+        let [nx, ny, nz] = self.dimensions;
+        (0..nz)
+            .flat_map(|k| (0..ny).flat_map(move |j| (0..nx).map(move |i| (i, j, k))))
+            .map(|(i, j, k)| self.point(i, j, k))
+            .collect()
+    }
+}
+
+/// One value at every point of a `Grid`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScalarVolume {
+    pub grid: Grid,
+    pub values: Vec<f32>,
+}
+
+impl ScalarVolume {
+    pub fn get(&self, i: usize, j: usize, k: usize) -> f32 {
+        self.values[self.grid.index(i, j, k)]
+    }
+
+    /// Smallest and largest value.
+    pub fn range(&self) -> (f32, f32) {
+        self.values
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+                (min.min(*value), max.max(*value))
+            })
+    }
+}
@@ -0,0 +1,6 @@
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct KernelInfo {
+    pub smoothing: u32,
+    pub core_radius: f32,
+}
@@ -1,8 +1,26 @@
 use cgmath::Vector4;
 
 #[repr(C)]
+#[derive(Clone, Copy)]
 pub struct Particle {
     pub position: Vector4<f32>,
     pub lifetime: Vector4<f32>,
+    /// The velocity over the last step, the particle's own one for inertial particles.
     pub velocity: Vector4<f32>,
+    /// User defined channels carried along, like a dye colour, a temperature, a
+    /// concentration or the index of the emitter the particle came from. There are exactly
+    /// four, the `vec4 scalars` of `particle.comp`; more quantities need a second set of
+    /// particles.
+    pub scalars: Vector4<f32>,
+}
+
+impl Default for Particle {
+    fn default() -> Self {
+        Self {
+            position: Vector4::new(0., 0., 0., 1.),
+            lifetime: Vector4::new(0., 0., 0., 0.),
+            velocity: Vector4::new(0., 0., 0., 0.),
+            scalars: Vector4::new(0., 0., 0., 0.),
+        }
+    }
 }
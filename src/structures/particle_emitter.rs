@@ -0,0 +1,30 @@
+use cgmath::{Vector3, Vector4};
+
+/// Sphere the particles respawn in, with the scalars they start with. Binding 16 holds them
+/// for `particle.comp`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParticleEmitter {
+    /// The center, with the radius in `w`
+    pub position: Vector4<f32>,
+    /// Taken over by every particle respawning here, one value per channel of
+    /// `Particle::scalars`
+    pub scalars: Vector4<f32>,
+}
+
+impl ParticleEmitter {
+    pub fn new(center: Vector3<f32>, radius: f32, scalars: Vector4<f32>) -> ParticleEmitter {
+        ParticleEmitter {
+            position: center.extend(radius),
+            scalars,
+        }
+    }
+
+    pub fn center(&self) -> Vector3<f32> {
+        self.position.truncate()
+    }
+
+    pub fn radius(&self) -> f32 {
+        self.position.w
+    }
+}
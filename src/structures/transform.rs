@@ -0,0 +1,67 @@
+use std::{cell::RefCell, rc::Rc};
+
+use cgmath::{InnerSpace, Matrix3, Matrix4, One, Quaternion, Vector3, Zero};
+
+use crate::physics::panel;
+
+use super::vortex::Vortex;
+
+pub type SharedTransform = Rc<RefCell<Transform>>;
+
+/// Rigid placement of a boundary body, body frame to world frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub translation: Vector3<f32>,
+    pub rotation: Quaternion<f32>,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            translation: Vector3::zero(),
+            rotation: Quaternion::one(),
+        }
+    }
+}
+
+impl Transform {
+    pub fn new() -> Transform {
+        Transform::default()
+    }
+
+    pub fn with_translation(mut self, translation: Vector3<f32>) -> Transform {
+        self.translation = translation;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: Quaternion<f32>) -> Transform {
+        self.rotation = rotation.normalize();
+        self
+    }
+
+    pub fn rotation_matrix(&self) -> Matrix3<f32> {
+        Matrix3::from(self.rotation)
+    }
+
+    /// The matrix `BoundaryInfo` hands to the shaders.
+    pub fn matrix(&self) -> Matrix4<f32> {
+        Matrix4::from_translation(self.translation) * Matrix4::from(self.rotation)
+    }
+
+    /// `vortex` of the body frame in the world frame, its position moved and its normal,
+    /// vorticity and panel corners rotated.
+    pub fn to_world(&self, vortex: &Vortex) -> Vortex {
+        let rotation = self.rotation_matrix();
+        Vortex {
+            position: (rotation * vortex.position.truncate() + self.translation).extend(1.),
+            normal: (rotation * vortex.normal.truncate()).extend(vortex.normal.w),
+            vorticity: (rotation * vortex.vorticity.truncate()).extend(vortex.vorticity.w),
+            lifetime: panel::rotate_corners(vortex, rotation),
+        }
+    }
+
+    /// A world frame direction, like a velocity, in the body frame.
+    pub fn direction_to_body(&self, direction: Vector3<f32>) -> Vector3<f32> {
+        self.rotation.conjugate() * direction
+    }
+}
@@ -0,0 +1,158 @@
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+use cgmath::{InnerSpace, Vector3};
+use serde_json::json;
+
+use crate::util;
+
+/// Indexed triangle mesh with a normal at every vertex.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TriangleMesh {
+    pub vertices: Vec<Vector3<f32>>,
+    pub normals: Vec<Vector3<f32>>,
+    /// Three per triangle, counterclockwise seen from the side the normals point to
+    pub indices: Vec<u32>,
+}
+
+impl TriangleMesh {
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    pub fn triangles(&self) -> impl Iterator<Item = [Vector3<f32>; 3]> + '_ {
+        self.indices
+            .chunks(3)
+            .map(|triangle| [0, 1, 2].map(|i| self.vertices[triangle[i] as usize]))
+    }
+
+    pub fn area(&self) -> f32 {
+        self.triangles()
+            .map(|[a, b, c]| 0.5 * (b - a).cross(c - a).magnitude())
+            .sum()
+    }
+
+    /// Writes the mesh as Wavefront OBJ with vertex normals.
+    pub fn write_obj<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for v in &self.vertices {
+            writeln!(writer, "v {} {} {}", v.x, v.y, v.z)?;
+        }
+        for n in &self.normals {
+            writeln!(writer, "vn {} {} {}", n.x, n.y, n.z)?;
+        }
+        for triangle in self.indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| triangle[i] + 1);
+            writeln!(writer, "f {a}//{a} {b}//{b} {c}//{c}")?;
+        }
+        Ok(())
+    }
+
+    /// Writes the mesh as binary glTF, a single primitive with positions, normals and
+    /// 32-bit indices.
+    pub fn write_glb<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let positions = bytemuck::cast_slice::<_, u8>(
+            &self
+                .vertices
+                .iter()
+                .flat_map(|v| [v.x, v.y, v.z])
+                .collect::<Vec<f32>>(),
+        )
+        .to_vec();
+        let normals = bytemuck::cast_slice::<_, u8>(
+            &self
+                .normals
+                .iter()
+                .flat_map(|n| [n.x, n.y, n.z])
+                .collect::<Vec<f32>>(),
+        )
+        .to_vec();
+        let indices = bytemuck::cast_slice::<_, u8>(&self.indices).to_vec();
+        let (min, max) = self.vertices.iter().fold(
+            ([f32::INFINITY; 3], [f32::NEG_INFINITY; 3]),
+            |(min, max), v| {
+                (
+                    [min[0].min(v.x), min[1].min(v.y), min[2].min(v.z)],
+                    [max[0].max(v.x), max[1].max(v.y), max[2].max(v.z)],
+                )
+            },
+        );
+        let mut binary = [positions.as_slice(), &normals, &indices].concat();
+        let document = json!({
+            "asset": { "version": "2.0" },
+            "scene": 0,
+            "scenes": [{ "nodes": [0] }],
+            "nodes": [{ "mesh": 0 }],
+            "meshes": [{
+                "primitives": [{
+                    "attributes": { "POSITION": 0, "NORMAL": 1 },
+                    "indices": 2,
+                }],
+            }],
+            "buffers": [{ "byteLength": binary.len() }],
+            "bufferViews": [
+                { "buffer": 0, "byteOffset": 0, "byteLength": positions.len(), "target": 34962 },
+                {
+                    "buffer": 0,
+                    "byteOffset": positions.len(),
+                    "byteLength": normals.len(),
+                    "target": 34962,
+                },
+                {
+                    "buffer": 0,
+                    "byteOffset": positions.len() + normals.len(),
+                    "byteLength": indices.len(),
+                    "target": 34963,
+                },
+            ],
+            "accessors": [
+                {
+                    "bufferView": 0,
+                    "componentType": 5126,
+                    "count": self.vertices.len(),
+                    "type": "VEC3",
+                    "min": min,
+                    "max": max,
+                },
+                {
+                    "bufferView": 1,
+                    "componentType": 5126,
+                    "count": self.normals.len(),
+                    "type": "VEC3",
+                },
+                {
+                    "bufferView": 2,
+                    "componentType": 5125,
+                    "count": self.indices.len(),
+                    "type": "SCALAR",
+                },
+            ],
+        });
+        let mut json = document.to_string().into_bytes();
+        // Chunks are 4 byte aligned, JSON padded with spaces and binary data with zeros.
+        json.resize(json.len().next_multiple_of(4), b' ');
+        binary.resize(binary.len().next_multiple_of(4), 0);
+
+        let length = 12 + 8 + json.len() + 8 + binary.len();
+        writer.write_all(b"glTF")?;
+        writer.write_all(&2u32.to_le_bytes())?;
+        writer.write_all(&(length as u32).to_le_bytes())?;
+        writer.write_all(&(json.len() as u32).to_le_bytes())?;
+        writer.write_all(b"JSON")?;
+        writer.write_all(&json)?;
+        writer.write_all(&(binary.len() as u32).to_le_bytes())?;
+        writer.write_all(b"BIN\0")?;
+        writer.write_all(&binary)
+    }
+
+    /// `write_glb` for paths ending in `.glb`, `write_obj` otherwise.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        util::save_with(path, |writer| {
+            match path.extension().and_then(|extension| extension.to_str()) {
+                Some("glb") => self.write_glb(writer),
+                _ => self.write_obj(writer),
+            }
+        })
+    }
+}
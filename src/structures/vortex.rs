@@ -1,4 +1,4 @@
-use cgmath::Vector4;
+use cgmath::{Matrix4, Vector4};
 use std::fmt::{Debug, Formatter};
 
 #[repr(C)]
@@ -32,10 +32,26 @@ impl Default for Vortex {
     }
 }
 
+/// Layout and placement of one boundary body, binding 50 holds one per body.
 #[repr(C)]
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy)]
 pub struct BoundaryInfo {
-    pub active_index: i32,
-    pub active_count: i32,
-    pub count: i32,
+    /// `Transform::matrix` of the body
+    pub transform: Matrix4<f32>,
+    /// `BodyVelocity::linear`
+    pub velocity: Vector4<f32>,
+    /// `BodyVelocity::angular`, about the translation of `transform`
+    pub angular_velocity: Vector4<f32>,
+    pub active_index: u32,
+    /// Number of vorticies in each set
+    pub active_count: u32,
+    /// Number of sets
+    pub count: u32,
+    /// First vortex of the body in binding 10
+    pub offset: u32,
+    /// First vortex of the body in binding 11 and first error in binding 30
+    pub current_offset: u32,
+    /// First element of the body's inverses in binding 20
+    pub matrix_offset: u32,
+    pub padding: [u32; 2],
 }
@@ -1 +1,26 @@
+pub mod benchmark;
+pub mod boundary_body;
+pub mod boundary_condition;
+pub mod boundary_solver;
+pub mod buoyancy;
+pub mod computed_inverse;
+pub mod cpu_backend;
+pub mod determinism;
+pub mod diffusion;
+pub mod field_sampler;
+pub mod fixtures;
+pub mod flow_lines;
+pub mod forces;
+pub mod inertia;
+pub mod integrator;
+pub mod isosurface;
+pub mod kernel;
+pub mod kinematics;
+pub mod panels;
+pub mod particle_scalars;
+pub mod pressure;
+pub mod rigid_body;
+pub mod shedding;
 pub mod tests;
+pub mod treecode;
+pub mod vortex_identification;
@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs, time::Instant};
+
+    use cgmath::{InnerSpace, Vector3};
+
+    use crate::{
+        compute_shader_program::ComputeShaderProgram,
+        gl, init_gl,
+        objects::{active_vorticies::ActiveVorticies, particles::Particles, scene::Scene},
+        physics::kernel::Kernel,
+        structures::particle::Particle,
+        util,
+    };
+
+    use all_asserts::assert_le;
+
+    fn run(program: &ComputeShaderProgram, tiled: bool, number_of_particles: usize) -> f32 {
+        unsafe {
+            program.use_program();
+            gl::Uniform1ui(0, tiled as u32);
+            gl::Finish();
+            let start = Instant::now();
+            for _ in 0..10 {
+                gl::DispatchCompute(util::number_of_workgroups(number_of_particles), 1, 1);
+                gl::MemoryBarrier(gl::SHADER_STORAGE_BARRIER_BIT);
+            }
+            gl::Finish();
+            start.elapsed().as_secs_f32() * 1000.0 / 10.0
+        }
+    }
+
+    fn read_velocities() -> Vec<Vector3<f32>> {
+        util::get_bound_buffer::<Particle>(1)
+            .iter()
+            .map(|p| p.velocity.truncate())
+            .collect()
+    }
+
+    #[test]
+    #[ignore = "needs an OpenGL 4.3 context, run with --ignored --nocapture"]
+    fn tiled_velocity_speedup() {
+        let (_glfw, _window, _events) = init_gl();
+        let program = ComputeShaderProgram::new(
+            "resources/shaders/velocity_benchmark.comp",
+            HashMap::from([
+                (
+                    "get_velocity",
+                    fs::read_to_string("resources/gpu_methods/get_velocity.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+                (
+                    "panel",
+                    fs::read_to_string("resources/gpu_methods/panel.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+                (
+                    "get_tiled_velocity",
+                    fs::read_to_string("resources/gpu_methods/get_tiled_velocity.glsl")
+                        .unwrap()
+                        .as_str(),
+                ),
+            ]),
+        );
+
+        for (number_of_vorticies, number_of_particles) in
+            [(1024, 16384), (4096, 65536), (16384, 65536)]
+        {
+            let mut scene = Scene::new().with_kernel(Kernel::rosenhead_moore(0.05));
+            scene
+                .add(ActiveVorticies::new_random(
+                    number_of_vorticies,
+                    1.0,
+                    2.0,
+                    0.5,
+                    1.0,
+                    0,
+                ))
+                .add(Particles::new_inside_unit_sphere(number_of_particles));
+
+            let direct_ms = run(&program, false, number_of_particles);
+            let direct = read_velocities();
+            let tiled_ms = run(&program, true, number_of_particles);
+            let tiled = read_velocities();
+
+            println!(
+                "{} vorticies x {} particles: direct {:.2} ms, tiled {:.2} ms, speedup {:.2}x",
+                number_of_vorticies,
+                number_of_particles,
+                direct_ms,
+                tiled_ms,
+                direct_ms / tiled_ms
+            );
+            for (d, t) in direct.iter().zip(tiled.iter()) {
+                assert_le!((d - t).magnitude(), 0.001 * d.magnitude().max(1.0));
+            }
+        }
+    }
+}
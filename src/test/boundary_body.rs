@@ -0,0 +1,158 @@
+#[cfg(test)]
+mod tests {
+    use cgmath::{Deg, InnerSpace, Quaternion, Rotation3, Vector3, Vector4};
+
+    use crate::{
+        objects::{boundary_body::BoundaryBody, boundary_vorticies::BoundaryVorticies},
+        physics::{boundary_condition::BoundaryCondition, kernel::Kernel},
+        structures::{transform::Transform, vortex::Vortex},
+        test::fixtures,
+    };
+
+    use all_asserts::{assert_le, assert_lt};
+
+    fn cube() -> BoundaryBody {
+        fixtures::panel_body("resources/models/cube.glb")
+    }
+
+    fn transform() -> Transform {
+        Transform::new()
+            .with_translation(Vector3::new(3., -1., 0.5))
+            .with_rotation(Quaternion::from_axis_angle(
+                Vector3::new(1., 2., 0.).normalize(),
+                Deg(35.),
+            ))
+    }
+
+    /// Velocity `boundary_condition` constrains at the control points of all bodies, induced
+    /// by `active` and every body.
+    fn residual(
+        bodies: &[BoundaryBody],
+        active: &[Vortex],
+        boundary_condition: BoundaryCondition,
+    ) -> f32 {
+        let boundary = bodies
+            .iter()
+            .flat_map(|body| body.world_vorticies(0))
+            .collect::<Vec<_>>();
+        let errors =
+            BoundaryVorticies::create_error_vector(&boundary, active, true, &Kernel::default());
+        boundary_condition
+            .constrain_errors(&boundary, &errors)
+            .iter()
+            .map(|e| e.magnitude2())
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    #[test]
+    fn transform_moves_and_rotates_vorticies() {
+        let transform = transform();
+        let vortex = Vortex {
+            position: Vector4::new(1., 0., 0., 1.),
+            normal: Vector4::new(1., 0., 0., 0.5),
+            vorticity: Vector4::new(0., 1., 0., 1.),
+            ..Default::default()
+        };
+        let world = transform.to_world(&vortex);
+
+        let position = transform.matrix() * vortex.position;
+        assert_le!((world.position - position).magnitude(), 1e-6);
+        assert_le!((world.normal.truncate().magnitude() - 1.).abs(), 1e-6);
+        assert_eq!(world.normal.w, 0.5);
+        let vorticity = transform.direction_to_body(world.vorticity.truncate());
+        assert_le!((vorticity - vortex.vorticity.truncate()).magnitude(), 1e-6);
+    }
+
+    #[test]
+    fn moved_body_is_corrected_like_the_original() {
+        let kernel = Kernel::default();
+        let active = Vortex {
+            position: Vector4::new(0.4, 1.8, -0.2, 1.),
+            vorticity: Vector4::new(1., 0., 0.5, 1.),
+            ..Default::default()
+        };
+        for boundary_condition in [BoundaryCondition::NoSlip, BoundaryCondition::Slip] {
+            let mut original = [cube()];
+            let inverses = fixtures::inverses(&original, boundary_condition);
+            BoundaryVorticies::correct_bodies(
+                &mut original,
+                &inverses,
+                &[active],
+                &kernel,
+                boundary_condition,
+            );
+
+            let mut moved = [cube().with_transform(transform())];
+            let moved_active = transform().to_world(&active);
+            BoundaryVorticies::correct_bodies(
+                &mut moved,
+                &inverses,
+                &[moved_active],
+                &kernel,
+                boundary_condition,
+            );
+
+            let original_residual = residual(&original, &[active], boundary_condition);
+            let moved_residual = residual(&moved, &[moved_active], boundary_condition);
+            println!(
+                "{:?}: original {}, moved {}",
+                boundary_condition, original_residual, moved_residual
+            );
+            assert_le!((original_residual - moved_residual).abs(), 1e-3);
+            for (a, b) in original[0].vorticies[0].iter().zip(&moved[0].vorticies[0]) {
+                assert_le!((a.vorticity - b.vorticity).magnitude(), 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn bodies_cancel_each_others_velocity() {
+        let kernel = Kernel::default();
+        let boundary_condition = BoundaryCondition::Slip;
+        let active = [Vortex {
+            position: Vector4::new(0., 0.3, 0., 1.),
+            vorticity: Vector4::new(0., 0.2, 1., 1.),
+            ..Default::default()
+        }];
+        let bodies = [
+            cube().with_transform(Transform::new().with_translation(Vector3::new(-1.6, 0., 0.))),
+            cube().with_transform(Transform::new().with_translation(Vector3::new(1.6, 0., 0.))),
+        ];
+        let inverses = fixtures::inverses(&bodies, boundary_condition);
+
+        // Every body on its own, blind to the other.
+        let mut independent = bodies.clone();
+        for (body, inverse) in independent.iter_mut().zip(&inverses) {
+            BoundaryVorticies::correct_bodies(
+                std::slice::from_mut(body),
+                std::slice::from_ref(inverse),
+                &active,
+                &kernel,
+                boundary_condition,
+            );
+        }
+
+        let mut coupled = bodies.clone();
+        let mut residuals = vec![];
+        for _ in 0..5 {
+            BoundaryVorticies::correct_bodies(
+                &mut coupled,
+                &inverses,
+                &active,
+                &kernel,
+                boundary_condition,
+            );
+            residuals.push(residual(&coupled, &active, boundary_condition));
+        }
+
+        let before = residual(&bodies, &active, boundary_condition);
+        let independent = residual(&independent, &active, boundary_condition);
+        println!(
+            "before {}, independent {}, sweeps {:?}",
+            before, independent, residuals
+        );
+        assert_lt!(residuals[4], residuals[0]);
+        assert_lt!(residuals[4], 0.1 * independent);
+    }
+}
@@ -0,0 +1,149 @@
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Vector3};
+
+    use crate::{
+        objects::boundary_vorticies::{BoundaryVorticies, PSEUDO_INVERSE_TOLERANCE},
+        physics::{
+            boundary_condition::BoundaryCondition, boundary_solver::IterativeSolver, kernel::Kernel,
+        },
+        structures::vortex::Vortex,
+        util::get_vertices_and_normals_from_gltf,
+    };
+
+    use all_asserts::assert_le;
+
+    const SPHERE_MODELS: [&str; 3] = [
+        "resources/models/sphere_3.glb",
+        "resources/models/sphere_4.glb",
+        "resources/models/sphere/sphere_4_n.glb",
+    ];
+
+    fn active_vorticies() -> Vec<Vortex> {
+        [
+            (Vector3::new(0.3, 1.5, 0.), Vector3::new(0., 0., 1.)),
+            (Vector3::new(-1.4, -0.2, 0.6), Vector3::new(0.5, 1., 0.)),
+            (Vector3::new(0.1, -0.4, -1.8), Vector3::new(1., 0., -0.5)),
+        ]
+        .into_iter()
+        .map(|(position, vorticity)| Vortex {
+            position: position.extend(1.),
+            vorticity: vorticity.extend(1.),
+            ..Default::default()
+        })
+        .collect()
+    }
+
+    fn norm(vectors: &[Vector3<f32>]) -> f32 {
+        vectors.iter().map(|v| v.magnitude2()).sum::<f32>().sqrt()
+    }
+
+    /// Total and normal velocity left at the control points after correcting `boundary` with
+    /// `corrections`, relative to before.
+    fn residuals(
+        boundary: &[Vortex],
+        active: &[Vortex],
+        corrections: &[Vector3<f32>],
+    ) -> (f32, f32) {
+        let kernel = Kernel::default();
+        let before = BoundaryVorticies::create_error_vector(boundary, active, false, &kernel);
+        let corrected = boundary
+            .iter()
+            .zip(corrections)
+            .map(|(v, c)| Vortex {
+                vorticity: c.extend(1.0),
+                ..*v
+            })
+            .collect::<Vec<_>>();
+        let after = BoundaryVorticies::create_error_vector(&corrected, active, true, &kernel);
+        let slip = BoundaryCondition::Slip;
+        (
+            norm(&after) / norm(&before),
+            norm(&slip.constrain_errors(boundary, &after))
+                / norm(&slip.constrain_errors(boundary, &before)),
+        )
+    }
+
+    fn pseudo_inverse_corrections(
+        boundary: &Vec<Vortex>,
+        active: &[Vortex],
+        boundary_condition: BoundaryCondition,
+    ) -> Vec<Vector3<f32>> {
+        let kernel = Kernel::default();
+        let inverse = BoundaryVorticies::create_matrix(boundary, &kernel, boundary_condition)
+            .pseudo_inverse(PSEUDO_INVERSE_TOLERANCE)
+            .unwrap();
+        let errors = BoundaryVorticies::create_error_vector(boundary, active, false, &kernel);
+        BoundaryVorticies::calculate_corrections(
+            &inverse,
+            &boundary_condition.constrain_errors(boundary, &errors),
+        )
+    }
+
+    /// The sphere models, `sphere_3` and `sphere_4` have no vertex normals but on a unit
+    /// sphere they are the positions.
+    fn sphere(model: &str) -> Vec<Vortex> {
+        BoundaryVorticies::vorticies_from_positions(
+            get_vertices_and_normals_from_gltf(model)
+                .into_iter()
+                .map(|(position, normal)| {
+                    if normal.magnitude2() > 0. {
+                        (position, normal)
+                    } else {
+                        (position, position)
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn pseudo_inverse_residual_of_each_condition() {
+        let active = active_vorticies();
+        // The larger spheres make the dense pseudo-inverse too slow for a unit test.
+        let boundary = sphere(SPHERE_MODELS[0]);
+
+        let no_slip = pseudo_inverse_corrections(&boundary, &active, BoundaryCondition::NoSlip);
+        let (no_slip_total, no_slip_normal) = residuals(&boundary, &active, &no_slip);
+        println!("NoSlip: total {}, normal {}", no_slip_total, no_slip_normal);
+
+        let slip = pseudo_inverse_corrections(&boundary, &active, BoundaryCondition::Slip);
+        let (slip_total, slip_normal) = residuals(&boundary, &active, &slip);
+        println!("Slip: total {}, normal {}", slip_total, slip_normal);
+
+        assert_le!(no_slip_total, 0.6);
+        assert_le!(slip_normal, 0.01);
+        assert_le!(slip_normal, no_slip_normal);
+    }
+
+    #[test]
+    fn iterative_residual_of_each_condition_on_spheres() {
+        let active = active_vorticies();
+        let kernel = Kernel::default();
+        let solver = IterativeSolver::new().with_max_iterations(100);
+        println!("model, condition, iterations, total residual, normal residual");
+        for model in SPHERE_MODELS {
+            let boundary = sphere(model);
+            let errors = BoundaryVorticies::create_error_vector(&boundary, &active, false, &kernel);
+            for boundary_condition in [BoundaryCondition::NoSlip, BoundaryCondition::Slip] {
+                let solution = solver.solve(&boundary, &errors, &[], &kernel, boundary_condition);
+                let (total, normal) = residuals(&boundary, &active, &solution.corrections);
+                println!(
+                    "{}, {:?}, {}, {}, {}",
+                    model, boundary_condition, solution.iterations, total, normal
+                );
+
+                match boundary_condition {
+                    BoundaryCondition::NoSlip => {
+                        assert_le!((total - solution.residual).abs(), 0.01);
+                        assert_le!(total, 0.6);
+                    }
+                    BoundaryCondition::Slip => {
+                        assert_le!((normal - solution.residual).abs(), 0.01);
+                        assert_le!(normal, 0.05);
+                    }
+                }
+            }
+        }
+    }
+}
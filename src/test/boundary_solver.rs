@@ -0,0 +1,166 @@
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Vector3, Vector4};
+
+    use crate::{
+        objects::boundary_vorticies::BoundaryVorticies,
+        physics::{
+            boundary_condition::BoundaryCondition,
+            boundary_solver::IterativeSolver,
+            kernel::{Kernel, MIN_STRENGTH},
+            treecode::Evaluator,
+        },
+        structures::vortex::Vortex,
+        util::get_vertices_and_normals_from_gltf,
+    };
+
+    use all_asserts::{assert_le, assert_lt};
+
+    fn setup() -> (Vec<Vortex>, Vec<Vortex>) {
+        let boundary = BoundaryVorticies::vorticies_from_positions(
+            get_vertices_and_normals_from_gltf("resources/models/sphere_3.glb"),
+        );
+        let active = vec![Vortex {
+            position: Vector4::new(0.3, 1.5, 0., 1.),
+            vorticity: Vector4::new(0., 0., 1., 1.),
+            ..Default::default()
+        }];
+        (boundary, active)
+    }
+
+    /// `|M c + e|` of the corrections `c`, measured like `create_error_vector` does.
+    fn remaining_error(
+        boundary: &[Vortex],
+        active: &[Vortex],
+        corrections: &[Vector3<f32>],
+    ) -> f32 {
+        let corrected = boundary
+            .iter()
+            .zip(corrections)
+            .map(|(v, c)| Vortex {
+                vorticity: c.extend(1.0),
+                ..*v
+            })
+            .collect::<Vec<_>>();
+        BoundaryVorticies::create_error_vector(&corrected, active, true, &Kernel::default())
+            .iter()
+            .map(|e| e.magnitude2())
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    #[test]
+    fn iterative_solver_matches_pseudo_inverse() {
+        let (boundary, active) = setup();
+        let kernel = Kernel::default();
+        let errors = BoundaryVorticies::create_error_vector(&boundary, &active, false, &kernel);
+
+        let inverse =
+            BoundaryVorticies::create_matrix(&boundary, &kernel, BoundaryCondition::NoSlip)
+                .pseudo_inverse(0.0001)
+                .unwrap();
+        let direct = BoundaryVorticies::calculate_corrections(&inverse, &errors);
+        let iterative = IterativeSolver::new().with_max_iterations(500).solve(
+            &boundary,
+            &errors,
+            &[],
+            &kernel,
+            BoundaryCondition::NoSlip,
+        );
+
+        let before = remaining_error(
+            &boundary,
+            &active,
+            &vec![Vector3::new(0., 0., 0.); boundary.len()],
+        );
+        let direct_after = remaining_error(&boundary, &active, &direct);
+        let iterative_after = remaining_error(&boundary, &active, &iterative.corrections);
+        assert_le!(direct_after, 0.6 * before);
+        assert_le!(iterative_after, direct_after + 0.1 * before);
+    }
+
+    #[test]
+    fn warm_start_saves_iterations() {
+        let (boundary, active) = setup();
+        let kernel = Kernel::default();
+        let errors = BoundaryVorticies::create_error_vector(&boundary, &active, false, &kernel);
+        let solver = IterativeSolver::new().with_tolerance(0.6);
+
+        let cold = solver.solve(&boundary, &errors, &[], &kernel, BoundaryCondition::NoSlip);
+        let warm = solver.solve(
+            &boundary,
+            &errors,
+            &cold.corrections,
+            &kernel,
+            BoundaryCondition::NoSlip,
+        );
+
+        assert_lt!(warm.iterations, cold.iterations);
+        assert_le!(warm.residual, 0.6);
+    }
+
+    #[test]
+    fn iterative_solver_works_through_treecode() {
+        let (boundary, active) = setup();
+        let kernel = Kernel::default();
+        let errors = BoundaryVorticies::create_error_vector(&boundary, &active, false, &kernel);
+        let solver = IterativeSolver::new();
+
+        let direct = solver.solve(&boundary, &errors, &[], &kernel, BoundaryCondition::NoSlip);
+        let tree = solver.with_evaluator(Evaluator::treecode(0.3)).solve(
+            &boundary,
+            &errors,
+            &[],
+            &kernel,
+            BoundaryCondition::NoSlip,
+        );
+
+        let before = remaining_error(
+            &boundary,
+            &active,
+            &vec![Vector3::new(0., 0., 0.); boundary.len()],
+        );
+        let direct_after = remaining_error(&boundary, &active, &direct.corrections);
+        let tree_after = remaining_error(&boundary, &active, &tree.corrections);
+        assert_le!(tree_after, direct_after + 0.05 * before);
+    }
+
+    /// The solve scales with the errors and the warm start, even once the corrections are
+    /// all far weaker than the strength `Kernel::get_velocity` skips, as those of a nearly
+    /// settled boundary are.
+    #[test]
+    fn solve_is_linear_for_weak_corrections() {
+        let (boundary, active) = setup();
+        let kernel = Kernel::default();
+        let errors = BoundaryVorticies::create_error_vector(&boundary, &active, false, &kernel);
+        let weak = |c: &[Vector3<f32>]| c.iter().map(|c| c * 1e-6).collect::<Vec<_>>();
+        let solver = IterativeSolver::new().with_max_iterations(10);
+        let initial = solver
+            .solve(&boundary, &errors, &[], &kernel, BoundaryCondition::NoSlip)
+            .corrections;
+        assert!(weak(&initial).iter().all(|c| c.magnitude() < MIN_STRENGTH));
+
+        let strong = solver.solve(
+            &boundary,
+            &errors,
+            &initial,
+            &kernel,
+            BoundaryCondition::NoSlip,
+        );
+        let weak_solution = solver.solve(
+            &boundary,
+            &weak(&errors),
+            &weak(&initial),
+            &kernel,
+            BoundaryCondition::NoSlip,
+        );
+
+        let norm = |c: &[Vector3<f32>]| c.iter().map(|c| c.magnitude2()).sum::<f32>().sqrt();
+        let difference = weak(&strong.corrections)
+            .iter()
+            .zip(&weak_solution.corrections)
+            .map(|(strong, weak)| strong - weak)
+            .collect::<Vec<_>>();
+        assert_le!(norm(&difference), 0.01 * norm(&weak(&strong.corrections)));
+    }
+}
@@ -0,0 +1,135 @@
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Vector3, Vector4};
+
+    use crate::{
+        cpu::active_vorticies::CpuActiveVorticies,
+        physics::{
+            buoyancy::{Buoyancy, HeatSource},
+            kernel::{Kernel, STRENGTH_TO_CIRCULATION},
+        },
+        structures::vortex::Vortex,
+        support::camera::PerspectiveCamera,
+        traits::steppable::Steppable,
+    };
+
+    use all_asserts::{assert_gt, assert_le, assert_lt};
+
+    const SPACING: f32 = 0.1;
+
+    /// Vorticies at rest on a lattice of `n³` points around the origin, with the
+    /// temperature `temperature` gives their position.
+    fn lattice(n: usize, temperature: impl Fn(Vector3<f32>) -> f32) -> Vec<Vortex> {
+        let offset = (n - 1) as f32 / 2.;
+        (0..n * n * n)
+            .map(|index| {
+                let (i, j, k) = (index % n, (index / n) % n, index / (n * n));
+                let position = (Vector3::new(i as f32, j as f32, k as f32)
+                    - Vector3::new(offset, offset, offset))
+                    * SPACING;
+                let vortex = Vortex {
+                    position: position.extend(1.),
+                    vorticity: Vector4::new(0., 0., 0., 1.),
+                    lifetime: Vector4::new(100., 100., 0., 0.),
+                    ..Default::default()
+                };
+                Buoyancy::with_temperature(vortex, temperature(position))
+            })
+            .collect()
+    }
+
+    fn simulation(vorticies: Vec<Vortex>, buoyancy: Buoyancy) -> CpuActiveVorticies {
+        CpuActiveVorticies::new(vorticies, 100., 100., 0., 0., 0)
+            .with_kernel(Kernel::rosenhead_moore(2. * SPACING))
+            .with_buoyancy(buoyancy)
+    }
+
+    /// In the interior of a lattice the smoothed gradient of a linear temperature is exact up
+    /// to the discretization of the kernel moments.
+    #[test]
+    fn gradient_of_linear_temperature() {
+        let slope = Vector3::new(1., -2., 0.5);
+        let vorticies = lattice(11, |position| slope.dot(position));
+        let buoyancy = Buoyancy::new(1., SPACING);
+        let center = &vorticies[vorticies.len() / 2];
+        assert_eq!(center.position.truncate(), Vector3::new(0., 0., 0.));
+        let gradient = vorticies
+            .iter()
+            .map(|other| buoyancy.get_gradient(center, other))
+            .fold(Vector3::new(0., 0., 0.), |sum, g| sum + g);
+        assert_le!((gradient - slope).magnitude(), 0.01 * slope.magnitude());
+    }
+
+    /// A warm layer below cold fluid on the left gets vorticity `β g × ∇T`, turning the warm
+    /// side up.
+    #[test]
+    fn baroclinic_vorticity_lifts_warm_fluid() {
+        let camera = PerspectiveCamera::new(100, 100);
+        // Warmer towards -x, the warm side rises, a clockwise roll seen from +z.
+        let vorticies = lattice(7, |position| -position.x);
+        let mut simulation = simulation(vorticies, Buoyancy::new(1., SPACING));
+        simulation.step(0.01, &camera);
+
+        let vorticies = simulation.get_vorticies();
+        let center = vorticies[vorticies.len() / 2];
+        let circulation = center.vorticity.truncate() * STRENGTH_TO_CIRCULATION;
+        assert_lt!(circulation.z, 0.);
+        assert_le!(circulation.x.abs(), 1e-6);
+        assert_le!(circulation.y.abs(), 1e-6);
+        // `V β g × ∇T` with `g = -y` and `∇T = -x`, over one step.
+        let expected = SPACING * SPACING * SPACING * 0.01;
+        assert_le!((circulation.z + expected).abs(), 0.05 * expected);
+    }
+
+    /// Conduction exchanges heat between the vorticies without creating or destroying any.
+    #[test]
+    fn conduction_conserves_heat() {
+        let camera = PerspectiveCamera::new(100, 100);
+        let hot = |position: Vector3<f32>| (-position.magnitude2() / 0.02).exp();
+        let vorticies = lattice(7, hot);
+        let total = |vorticies: &[Vortex]| vorticies.iter().map(Buoyancy::temperature).sum::<f32>();
+        let before = total(&vorticies);
+        let peak = Buoyancy::temperature(&vorticies[vorticies.len() / 2]);
+        let mut simulation =
+            simulation(vorticies, Buoyancy::new(0., SPACING).with_diffusivity(0.01));
+        simulation.step(0.01, &camera);
+        let vorticies = simulation.get_vorticies();
+
+        assert_le!((total(&vorticies) - before).abs(), 1e-4 * before);
+        assert_lt!(Buoyancy::temperature(&vorticies[vorticies.len() / 2]), peak);
+    }
+
+    /// Fluid heated at the center of a still lattice rises in a plume, rolling up like a
+    /// vortex ring around the heat source.
+    #[test]
+    fn heat_source_drives_a_plume() {
+        let camera = PerspectiveCamera::new(100, 100);
+        let vorticies = lattice(9, |_| 0.);
+        let buoyancy = Buoyancy::new(50., SPACING).with_heat_source(HeatSource::new(
+            Vector3::new(0., 0., 0.),
+            0.1,
+            10.,
+        ));
+        let mut simulation = simulation(vorticies, buoyancy);
+        for _ in 0..20 {
+            simulation.step(0.01, &camera);
+        }
+        let vorticies = simulation.get_vorticies();
+
+        let center = vorticies[vorticies.len() / 2];
+        assert_gt!(Buoyancy::temperature(&center), 0.5);
+        assert_gt!(center.position.y, 0.);
+        // On the +x side of the source the roll turns clockwise seen from +z, on the +z
+        // side clockwise seen from -x.
+        let ring = |direction: Vector3<f32>| {
+            vorticies
+                .iter()
+                .filter(|v| (v.position.truncate() - direction * 0.2).magnitude() < 0.11)
+                .map(|v| v.vorticity.truncate() * STRENGTH_TO_CIRCULATION)
+                .fold(Vector3::new(0., 0., 0.), |sum, v| sum + v)
+        };
+        assert_lt!(ring(Vector3::unit_x()).z, 0.);
+        assert_gt!(ring(Vector3::unit_z()).x, 0.);
+        assert_gt!(ring(-Vector3::unit_x()).z, 0.);
+    }
+}
@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, fs};
+
+    use cgmath::Vector3;
+    use nalgebra::DMatrix;
+
+    use crate::{
+        objects::boundary_vorticies::BoundaryVorticies,
+        physics::{boundary_condition::BoundaryCondition, kernel::Kernel},
+        structures::{computed_inverse::ComputedInverse, vortex::Vortex},
+    };
+
+    fn boundary() -> Vec<Vortex> {
+        BoundaryVorticies::vorticies_from_positions(vec![
+            (Vector3::new(1., 0., 0.), Vector3::new(1., 0., 0.)),
+            (Vector3::new(0., 1., 0.), Vector3::new(0., 1., 0.)),
+            (Vector3::new(0., 0., 1.), Vector3::new(0., 0., 1.)),
+        ])
+    }
+
+    #[test]
+    fn checksum_tracks_layout_kernel_condition_and_tolerance() {
+        let vorticies = boundary();
+        let kernel = Kernel::default();
+        let no_slip = BoundaryCondition::NoSlip;
+        let checksum = ComputedInverse::checksum(&vorticies, &kernel, no_slip, 0.0001);
+
+        assert_eq!(
+            checksum,
+            ComputedInverse::checksum(&vorticies, &kernel, no_slip, 0.0001)
+        );
+        assert_ne!(
+            checksum,
+            ComputedInverse::checksum(&vorticies, &kernel, no_slip, 0.001)
+        );
+        assert_ne!(
+            checksum,
+            ComputedInverse::checksum(&vorticies, &Kernel::gaussian(0.1), no_slip, 0.0001)
+        );
+        assert_ne!(
+            checksum,
+            ComputedInverse::checksum(&vorticies, &kernel, BoundaryCondition::Slip, 0.0001)
+        );
+        let mut moved = vorticies.clone();
+        moved[1].position.y = 1.01;
+        assert_ne!(
+            checksum,
+            ComputedInverse::checksum(&moved, &kernel, no_slip, 0.0001)
+        );
+        let mut turned = vorticies.clone();
+        turned[1].normal.x = 0.1;
+        assert_ne!(
+            checksum,
+            ComputedInverse::checksum(&turned, &kernel, no_slip, 0.0001)
+        );
+    }
+
+    #[test]
+    fn inverse_is_cached_until_checksum_changes() {
+        let path = std::env::temp_dir().join("vortex_inverse_cache_test.postcard");
+        let _ = fs::remove_file(&path);
+        let matrix = DMatrix::from_fn(6, 6, |i, j| (i * 6 + j) as f32 * 0.5);
+        let computations = Cell::new(0);
+        let compute = || {
+            computations.set(computations.get() + 1);
+            matrix.clone()
+        };
+
+        let first = ComputedInverse::load_or_compute(&path, 1, compute);
+        let second = ComputedInverse::load_or_compute(&path, 1, compute);
+        assert_eq!(computations.get(), 1);
+        assert_eq!(first, matrix);
+        assert_eq!(second, matrix);
+
+        assert!(ComputedInverse::load(&path, 2).is_none());
+        ComputedInverse::load_or_compute(&path, 2, compute);
+        assert_eq!(computations.get(), 2);
+        assert!(ComputedInverse::load(&path, 1).is_none());
+        assert!(ComputedInverse::load(&path, 2).is_some());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Vector3, Vector4};
+
+    use crate::{
+        cpu::{active_vorticies::CpuActiveVorticies, particles::CpuParticles},
+        physics::{integrator::Integrator, kernel::Kernel, stretching::StretchingScheme},
+        structures::{particle::Particle, vortex::Vortex},
+        support::camera::PerspectiveCamera,
+        traits::steppable::Steppable,
+    };
+
+    use all_asserts::{assert_le, assert_range};
+
+    #[test]
+    fn lifetime_reset() {
+        let camera = PerspectiveCamera::new(100, 100);
+        let mut vorticies = CpuActiveVorticies::new(
+            vec![Vortex {
+                position: Vector4::new(5., 0., 0., 1.),
+                vorticity: Vector4::new(0., 1., 0., 1.),
+                lifetime: Vector4::new(0.05, 3., 0., 0.),
+                ..Default::default()
+            }],
+            1.0,
+            2.0,
+            0.1,
+            0.2,
+            0,
+        );
+
+        vorticies.step(0.1, &camera);
+
+        let vortex = vorticies.get_vorticies()[0];
+        assert_eq!(vortex.lifetime.x, 3.);
+        assert_range!(1.0..2.0, vortex.lifetime.y);
+        assert_le!(vortex.position.truncate().magnitude(), 1.0);
+        assert_range!(0.1..0.2, vortex.vorticity.truncate().magnitude());
+    }
+
+    #[test]
+    fn mirror_keeps_strength() {
+        let camera = PerspectiveCamera::new(100, 100);
+        // Stretching would change the strengths of the two differently.
+        let mut vorticies = CpuActiveVorticies::new_random(16, 5.0, 10.0, 0.5, 1.0, 1)
+            .with_stretching(StretchingScheme::Disabled);
+
+        vorticies.step(0.01, &camera);
+
+        let vorticies = vorticies.get_vorticies();
+        let (non_mirrors, mirrors) = vorticies.split_at(16);
+        for (vortex, mirror) in non_mirrors.iter().zip(mirrors) {
+            let difference =
+                vortex.vorticity.truncate().magnitude() - mirror.vorticity.truncate().magnitude();
+            assert_le!(difference.abs(), 0.001);
+        }
+    }
+
+    #[test]
+    fn particles_orbit_single_vortex() {
+        let camera = PerspectiveCamera::new(100, 100);
+        let vorticies = CpuActiveVorticies::new(
+            vec![Vortex {
+                position: Vector4::new(0., 0., 0., 1.),
+                vorticity: Vector4::new(0., 1., 0., 1.),
+                lifetime: Vector4::new(100., 100., 0., 0.),
+                ..Default::default()
+            }],
+            100.0,
+            100.0,
+            1.0,
+            1.0,
+            0,
+        );
+        let particles = (0..64)
+            .map(|i| {
+                let angle = i as f32 / 64. * std::f32::consts::TAU;
+                Particle {
+                    position: Vector4::new(angle.cos() * 0.5, 0., angle.sin() * 0.5, 1.),
+                    lifetime: Vector4::new(100., 100., 0., 0.),
+                    velocity: Vector4::new(0., 0., 0., 0.),
+                    ..Default::default()
+                }
+            })
+            .collect();
+        let mut particles = CpuParticles::new(particles, vorticies.shared_vorticies())
+            .with_resetting(false)
+            .with_integrator(Integrator::RungeKutta4);
+        let vortex = vorticies.get_vorticies()[0];
+        let speed = Kernel::default()
+            .get_velocity(Vector3::new(0.5, 0., 0.), &vortex)
+            .magnitude();
+
+        for _ in 0..100 {
+            particles.step(0.01, &camera);
+        }
+
+        for particle in particles.particles.iter() {
+            assert_eq!(particle.position.y, 0.);
+            assert_le!(
+                (particle.position.truncate().magnitude() - 0.5).abs(),
+                0.0001
+            );
+            // The velocity of a tracer is per unit time, not the displacement of the step.
+            assert_le!(
+                (particle.velocity.truncate().magnitude() - speed).abs(),
+                0.01 * speed
+            );
+        }
+    }
+}
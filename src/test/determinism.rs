@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cpu::active_vorticies::CpuActiveVorticies,
+        init_gl,
+        objects::{active_vorticies::ActiveVorticies, scene::Scene},
+        physics::{
+            diffusion::Diffusion, integrator::Integrator, kernel::Kernel, treecode::Evaluator,
+        },
+        structures::vortex::Vortex,
+        support::camera::PerspectiveCamera,
+        traits::steppable::Steppable,
+        util,
+    };
+
+    fn to_bits(vorticies: &[Vortex]) -> Vec<u32> {
+        vorticies
+            .iter()
+            .flat_map(|v| [v.position, v.normal, v.vorticity, v.lifetime])
+            .flat_map(|v| [v.x, v.y, v.z, v.w])
+            .map(f32::to_bits)
+            .collect()
+    }
+
+    #[test]
+    #[ignore = "needs an OpenGL 4.3 context"]
+    fn identical_runs_are_bit_identical() {
+        let (_glfw, _window, _events) = init_gl();
+        let camera = PerspectiveCamera::new(100, 100);
+        // Short lifetimes, so resets and mirrors are exercised too.
+        let initial = ActiveVorticies::get_random_vorticies(1000, 0.1, 0.5, 0.5, 1.0);
+
+        let run = || {
+            let mut scene = Scene::new().with_kernel(Kernel::rosenhead_moore(0.05));
+            scene.add(
+                ActiveVorticies::new(initial.clone(), 0.1, 0.5, 0.5, 1.0, 1)
+                    .with_integrator(Integrator::RungeKutta4)
+                    .with_seed(42),
+            );
+            for _ in 0..20 {
+                scene.step(1.0 / 60.0, &camera);
+            }
+            // The state the last pass wrote is bound for reading.
+            util::get_bound_buffer::<Vortex>(2)
+        };
+
+        let first = run();
+        let second = run();
+
+        assert_eq!(first.len(), 2000);
+        assert!(to_bits(&first) == to_bits(&second));
+    }
+
+    /// The CPU backend sums every vortex in its own order whatever the thread count. The
+    /// lifetimes outlast the run and there are no mirrors, both of which are random there.
+    #[test]
+    fn identical_cpu_runs_are_bit_identical() {
+        let camera = PerspectiveCamera::new(100, 100);
+        let initial = ActiveVorticies::get_random_vorticies(1000, 10.0, 10.0, 0.5, 1.0);
+
+        let run = |evaluator: Evaluator| {
+            let mut vorticies = CpuActiveVorticies::new(initial.clone(), 10.0, 10.0, 0.5, 1.0, 0)
+                .with_kernel(Kernel::rosenhead_moore(0.05))
+                .with_diffusion(Diffusion::new(0.01, 0.05))
+                .with_integrator(Integrator::RungeKutta4)
+                .with_evaluator(evaluator);
+            for _ in 0..20 {
+                vorticies.step(1.0 / 60.0, &camera);
+            }
+            vorticies.get_vorticies()
+        };
+
+        for evaluator in [Evaluator::Direct, Evaluator::treecode(0.3)] {
+            let first = run(evaluator);
+            let second = run(evaluator);
+
+            assert_eq!(first.len(), 1000);
+            assert!(to_bits(&first) == to_bits(&second), "{:?}", evaluator);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Vector3, Vector4, Zero};
+    use itertools::Itertools;
+
+    use crate::{
+        physics::diffusion::{Diffusion, ScalarDiffusion},
+        structures::vortex::Vortex,
+    };
+
+    use all_asserts::{assert_le, assert_lt};
+
+    #[test]
+    fn exchange_conserves_strength_and_spreads_peak() {
+        let spacing = 0.1f32;
+        let diffusion = Diffusion::new(0.01, spacing);
+        let vorticies = (-3..=3)
+            .cartesian_product(-3..=3)
+            .cartesian_product(-3..=3)
+            .map(|((x, y), z)| Vortex {
+                position: Vector4::new(x as f32, y as f32, z as f32, 1.) * spacing,
+                vorticity: if (x, y, z) == (0, 0, 0) {
+                    Vector4::new(0., 0., 1., 1.)
+                } else {
+                    Vector4::new(0., 0., 0., 1.)
+                },
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+
+        let rates = vorticies
+            .iter()
+            .map(|a| {
+                vorticies
+                    .iter()
+                    .map(|b| diffusion.get_exchange(a, b))
+                    .fold(Vector3::zero(), |sum, rate| sum + rate)
+            })
+            .collect::<Vec<_>>();
+
+        let total = rates.iter().fold(Vector3::zero(), |sum, rate| sum + rate);
+        assert_le!(total.magnitude(), 0.00001);
+
+        let center = vorticies.len() / 2;
+        assert_lt!(rates[center].z, 0.0);
+        assert_lt!(0.0, rates[center + 1].z);
+    }
+
+    /// A dye with a Schmidt number of 10 diffuses ten times slower than the vorticity, with
+    /// the same kernel.
+    #[test]
+    fn scalars_diffuse_with_their_own_diffusivity() {
+        let viscosity = 0.01;
+        let diffusion = Diffusion::new(viscosity, 0.1);
+        let scalar_diffusion = ScalarDiffusion::from_schmidt_number(10., viscosity, 0.1);
+        assert_le!((scalar_diffusion.diffusivity - 0.001).abs(), 1e-9);
+        for distance in [0., 0.05, 0.1, 0.3] {
+            let ratio =
+                diffusion.exchange_factor(distance) / scalar_diffusion.exchange_factor(distance);
+            assert_le!((ratio - 10.).abs(), 1e-4);
+        }
+        assert!(!ScalarDiffusion::default().enabled());
+    }
+}
@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Matrix3, Vector3};
+
+    use crate::{
+        cpu::field_sampler::CpuFieldSampler, objects::active_vorticies::ActiveVorticies,
+        physics::kernel::Kernel, structures::field_sample::FieldSample,
+        util::random_inside_unit_sphere,
+    };
+
+    use all_asserts::assert_le;
+
+    /// Rigid rotation `u = Ω × x` has vorticity `2Ω` and no divergence.
+    #[test]
+    fn rigid_rotation_has_twice_its_angular_velocity() {
+        let omega = Vector3::new(0.3, -1.2, 0.5);
+        let sample = FieldSample {
+            velocity: Vector3::new(0., 0., 0.),
+            gradient: Matrix3::from_cols(
+                omega.cross(Vector3::unit_x()),
+                omega.cross(Vector3::unit_y()),
+                omega.cross(Vector3::unit_z()),
+            ),
+        };
+
+        assert_le!((sample.vorticity() - omega * 2.).magnitude(), 1e-6);
+        assert_le!(sample.divergence().abs(), 1e-6);
+    }
+
+    /// The gradients are those of the sampled velocities, and the field of vortex particles
+    /// is divergence free.
+    #[test]
+    fn gradient_matches_finite_differences() {
+        let sampler = CpuFieldSampler::new(Kernel::rosenhead_moore(0.1));
+        let sources = ActiveVorticies::get_random_vorticies(200, 1.0, 2.0, 0.5, 1.0);
+        let points = (0..50)
+            .map(|_| random_inside_unit_sphere() * 1.5)
+            .collect::<Vec<_>>();
+        let samples = sampler.sample(&points, &sources);
+
+        let h = 1e-3;
+        for (point, sample) in points.iter().zip(&samples) {
+            let columns = [Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()].map(|axis| {
+                let shifted = sampler.velocities(&[point + axis * h, point - axis * h], &sources);
+                (shifted[0] - shifted[1]) / (2. * h)
+            });
+            let difference = FieldSample {
+                velocity: sample.velocity,
+                gradient: Matrix3::from_cols(columns[0], columns[1], columns[2]),
+            };
+            let scale = sample.vorticity().magnitude().max(1.);
+            for j in 0..3 {
+                assert_le!(
+                    (sample.gradient[j] - difference.gradient[j]).magnitude(),
+                    0.01 * scale
+                );
+            }
+            assert_le!(
+                (sample.vorticity() - difference.vorticity()).magnitude(),
+                0.01 * scale
+            );
+            assert_le!(sample.divergence().abs(), 1e-3 * scale);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+#![cfg(test)]
+
+use cgmath::{InnerSpace, Vector3};
+use nalgebra::DMatrix;
+
+use crate::{
+    objects::{
+        boundary_body::BoundaryBody,
+        boundary_vorticies::{BoundaryDiscretization, BoundaryVorticies},
+    },
+    physics::{
+        boundary_condition::BoundaryCondition,
+        kernel::{Kernel, STRENGTH_TO_CIRCULATION},
+    },
+    structures::vortex::Vortex,
+    util::get_triangles_from_gltf,
+};
+
+/// `BoundaryDiscretization::Panels` of the glTF file at `model_path`.
+pub fn panel_body(model_path: &str) -> BoundaryBody {
+    BoundaryBody::from_vorticies(vec![BoundaryVorticies::panels_from_triangles(
+        get_triangles_from_gltf(model_path),
+    )])
+    .with_discretization(BoundaryDiscretization::Panels)
+}
+
+/// Volume enclosed by the centroids of a closed mesh, `∮ x·n dA / 3`.
+pub fn enclosed_volume(centroids: &[Vortex]) -> f32 {
+    centroids
+        .iter()
+        .map(|v| v.position.truncate().dot(v.normal.truncate()) * v.normal.w / 3.)
+        .sum()
+}
+
+/// `BoundaryVorticies::create_inverse` of the first set of every body.
+pub fn inverses(
+    bodies: &[BoundaryBody],
+    boundary_condition: BoundaryCondition,
+) -> Vec<DMatrix<f32>> {
+    bodies
+        .iter()
+        .map(|body| {
+            BoundaryVorticies::create_inverse(
+                &body.vorticies[0],
+                &Kernel::default(),
+                boundary_condition,
+            )
+        })
+        .collect()
+}
+
+/// Centroids of the icosphere `sphere_3.glb`, the volume they enclose and the inverse of its
+/// slip condition.
+pub fn panel_sphere() -> (BoundaryBody, f32, Vec<DMatrix<f32>>) {
+    let body = panel_body("resources/models/sphere_3.glb");
+    let volume = enclosed_volume(&body.vorticies[0]);
+    let inverses = inverses(std::slice::from_ref(&body), BoundaryCondition::Slip);
+    (body, volume, inverses)
+}
+
+/// Vortex ring of `count` elements around `center` with the circulation `circulation`
+/// turning counterclockwise about `normal`, so its impulse is `Γ π R² n` up to the polygon.
+pub fn vortex_ring(
+    center: Vector3<f32>,
+    normal: Vector3<f32>,
+    radius: f32,
+    circulation: f32,
+    count: usize,
+) -> Vec<Vortex> {
+    let normal = normal.normalize();
+    let u = normal.cross(if normal.x.abs() < 0.9 {
+        Vector3::unit_x()
+    } else {
+        Vector3::unit_y()
+    });
+    let u = u.normalize();
+    let v = normal.cross(u);
+    let length = 2. * std::f32::consts::PI * radius / count as f32;
+    (0..count)
+        .map(|i| {
+            let (sin, cos) = (2. * std::f32::consts::PI * i as f32 / count as f32).sin_cos();
+            let tangent = v * cos - u * sin;
+            Vortex {
+                position: (center + (u * cos + v * sin) * radius).extend(1.),
+                vorticity: (tangent * (circulation * length / STRENGTH_TO_CIRCULATION)).extend(1.),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
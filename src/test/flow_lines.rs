@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Vector3};
+
+    use crate::physics::{
+        flow_lines::{self, Pathlines, Polyline, Streaklines, Streamlines},
+        integrator::Integrator,
+    };
+
+    use all_asserts::{assert_le, assert_lt};
+
+    /// Rigid rotation around the z axis with unit angular velocity.
+    fn rotation(points: &[Vector3<f32>]) -> Vec<Vector3<f32>> {
+        points
+            .iter()
+            .map(|point| Vector3::new(-point.y, point.x, 0.))
+            .collect()
+    }
+
+    fn uniform(points: &[Vector3<f32>]) -> Vec<Vector3<f32>> {
+        vec![Vector3::new(1., 0.5, 0.); points.len()]
+    }
+
+    #[test]
+    fn batch_integration_matches_single_points() {
+        let positions = flow_lines::rake(Vector3::new(0.5, 0., 0.), Vector3::new(1., 0.2, 0.3), 4);
+        for integrator in [
+            Integrator::Euler,
+            Integrator::Midpoint,
+            Integrator::LowStorageRungeKutta3,
+            Integrator::RungeKutta4,
+        ] {
+            let batch = integrator.integrate_batch(&positions, 0.1, rotation);
+            for (position, batch) in positions.iter().zip(batch) {
+                let single = integrator.integrate(*position, 0.1, |point| rotation(&[point])[0]);
+                assert_le!((single - batch).magnitude(), 1e-6, "{:?}", integrator);
+            }
+        }
+    }
+
+    #[test]
+    fn rake_spans_its_ends() {
+        let start = Vector3::new(0., 0., 0.);
+        let end = Vector3::new(1., 2., 0.);
+        let seeds = flow_lines::rake(start, end, 5);
+        assert_eq!(seeds.len(), 5);
+        assert_eq!(seeds[0], start);
+        assert_eq!(seeds[4], end);
+        assert_eq!(seeds[2], Vector3::new(0.5, 1., 0.));
+    }
+
+    #[test]
+    fn streamlines_of_rigid_rotation_are_circles() {
+        let seeds = flow_lines::rake(Vector3::new(0.5, 0., 0.), Vector3::new(1., 0., 0.), 3);
+        let step = 0.05;
+        let lines = Streamlines::new(step, 100).trace(&seeds, rotation);
+        for (seed, line) in seeds.iter().zip(&lines) {
+            assert_eq!(line.points.len(), 101);
+            for point in &line.points {
+                assert_le!((point.magnitude() - seed.magnitude()).abs(), 1e-4);
+            }
+            // The chords are a little shorter than the arcs they cut.
+            assert_le!((line.length() - 100. * step).abs(), 0.01 * 100. * step);
+        }
+    }
+
+    #[test]
+    fn streamlines_run_both_ways_and_stop_in_still_fluid() {
+        let seed = Vector3::new(0., 0., 0.);
+        let line = &Streamlines::new(0.1, 10)
+            .with_both_directions(true)
+            .trace(&[seed], uniform)[0];
+        assert_eq!(line.points.len(), 21);
+        assert_eq!(line.points[10], seed);
+        assert_lt!(line.points[0].x, 0.);
+        assert_lt!(0., line.points[20].x);
+
+        let still = Streamlines::new(0.1, 10).trace(&[seed], |points| {
+            vec![Vector3::new(0., 0., 0.); points.len()]
+        });
+        assert_eq!(still[0].points, vec![seed]);
+    }
+
+    /// The stop test reuses the first velocities the integrator asks for, so a fourth order
+    /// step costs four batches.
+    #[test]
+    fn streamlines_sample_the_velocity_once_per_stage() {
+        let mut batches = 0;
+        Streamlines::new(0.05, 10).trace(&[Vector3::new(1., 0., 0.)], |points| {
+            batches += 1;
+            rotation(points)
+        });
+        assert_eq!(batches, 4 * 10);
+    }
+
+    #[test]
+    fn pathlines_follow_the_flow_over_time() {
+        let mut pathlines = Pathlines::new(&[Vector3::new(1., 0., 0.)])
+            .with_integrator(Integrator::RungeKutta4)
+            .with_max_points(50);
+        let dt = 0.01;
+        for _ in 0..100 {
+            pathlines.step(dt, rotation);
+        }
+        let lines = pathlines.lines();
+        let line = &lines[0];
+        assert_eq!(line.points.len(), 50);
+        let end = Vector3::new(1f32.cos(), 1f32.sin(), 0.);
+        assert_le!((line.points[49] - end).magnitude(), 1e-4);
+    }
+
+    /// In a uniform flow the tracers of an injector are spaced by the distance the flow
+    /// covers in a release interval.
+    #[test]
+    fn streaklines_are_spaced_by_the_release_interval() {
+        let injector = Vector3::new(0., 0., 0.);
+        let mut streaklines = Streaklines::new(&[injector], 0.1).with_max_points(5);
+        for _ in 0..20 {
+            streaklines.step(0.05, uniform);
+        }
+        let line = &streaklines.lines[0];
+        assert_eq!(line.points.len(), 5);
+        assert_eq!(line.points[0], injector);
+        let spacing = uniform(&[injector])[0].magnitude() * 0.1;
+        for pair in line.points.windows(2) {
+            assert_le!(((pair[1] - pair[0]).magnitude() - spacing).abs(), 1e-4);
+        }
+    }
+
+    #[test]
+    fn tubes_surround_their_line() {
+        let line = Polyline {
+            points: flow_lines::rake(Vector3::new(0., 0., 0.), Vector3::new(0., 0., 1.), 4),
+        };
+        let (vertices, indices) = line.tube(0.1, 6);
+        assert_eq!(vertices.len(), 4 * 6);
+        assert_eq!(indices.len(), 3 * 6 * 6);
+        for (i, vertex) in vertices.iter().enumerate() {
+            let center = line.points[i / 6];
+            assert_le!(((vertex - center).magnitude() - 0.1).abs(), 1e-6);
+            assert_le!((vertex - center).z.abs(), 1e-6);
+        }
+        assert!(indices
+            .iter()
+            .all(|index| (*index as usize) < vertices.len()));
+    }
+
+    /// Repeated points, like a tracer resting at a stagnation point, get no ring of their own
+    /// and no twisted frame.
+    #[test]
+    fn tubes_skip_repeated_points() {
+        let line = Polyline {
+            points: vec![
+                Vector3::new(0., 0., 0.),
+                Vector3::new(0., 0., 0.),
+                Vector3::new(0., 0., 1.),
+                Vector3::new(0., 0., 1.),
+            ],
+        };
+        let (vertices, indices) = line.tube(0.1, 6);
+        assert_eq!(vertices.len(), 2 * 6);
+        assert_eq!(indices.len(), 3 * 2 * 6);
+        assert!(vertices
+            .iter()
+            .all(|vertex| vertex.x.is_finite() && vertex.y.is_finite() && vertex.z.is_finite()));
+        assert!(Polyline {
+            points: vec![Vector3::new(1., 0., 0.); 3]
+        }
+        .tube(0.1, 6)
+        .0
+        .is_empty());
+    }
+
+    #[test]
+    fn lines_are_written_as_obj() {
+        let lines = [
+            Polyline {
+                points: vec![Vector3::new(0., 0., 0.), Vector3::new(1., 0., 0.)],
+            },
+            Polyline {
+                points: vec![Vector3::new(0., 1., 0.), Vector3::new(0., 2., 0.5)],
+            },
+        ];
+        let mut obj = vec![];
+        flow_lines::write_obj(&lines, &mut obj).unwrap();
+        assert_eq!(
+            String::from_utf8(obj).unwrap(),
+            "v 0 0 0\nv 1 0 0\nl 1 2\nv 0 1 0\nv 0 2 0.5\nl 3 4\n"
+        );
+
+        let mut obj = vec![];
+        flow_lines::write_tube_obj(&lines, 0.1, 3, &mut obj).unwrap();
+        let obj = String::from_utf8(obj).unwrap();
+        assert_eq!(
+            obj.lines().filter(|line| line.starts_with("v ")).count(),
+            12
+        );
+        assert_eq!(
+            obj.lines().filter(|line| line.starts_with("f ")).count(),
+            12
+        );
+        assert!(obj.contains("f 7 8 10"));
+    }
+}
@@ -0,0 +1,164 @@
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use cgmath::{InnerSpace, Matrix3, Vector3, Zero};
+    use nalgebra::DMatrix;
+
+    use crate::{
+        objects::{boundary_body::BoundaryBody, boundary_vorticies::BoundaryVorticies},
+        physics::{
+            boundary_condition::BoundaryCondition,
+            forces::{DisplacedFluid, ForceHistory, ForceSample},
+            kernel::Kernel,
+            kinematics::Kinematics,
+        },
+        structures::vortex::Vortex,
+        test::fixtures,
+    };
+
+    use all_asserts::assert_le;
+
+    const BOUNDARY_CONDITION: BoundaryCondition = BoundaryCondition::Slip;
+
+    /// Moves `body` through still fluid with `kinematics` for `steps` steps of `dt`, adding
+    /// what `shed` returns for the body after every step to the free vorticity.
+    fn run<F>(
+        body: BoundaryBody,
+        inverses: &[DMatrix<f32>],
+        kinematics: Kinematics,
+        steps: usize,
+        dt: f32,
+        shed: F,
+    ) -> ForceHistory
+    where
+        F: Fn(&BoundaryBody) -> Vec<Vortex>,
+    {
+        let mut bodies = [body.with_kinematics(kinematics)];
+        let mut wake = vec![];
+        for step in 0..steps {
+            BoundaryVorticies::correct_bodies(
+                &mut bodies,
+                inverses,
+                &wake,
+                &Kernel::default(),
+                BOUNDARY_CONDITION,
+            );
+            let world = bodies[0].world_vorticies(0);
+            bodies[0].step(&world, &wake, (step + 1) as f32 * dt, dt);
+            wake.extend(shed(&bodies[0]));
+        }
+        bodies[0].force_history.take().unwrap()
+    }
+
+    #[test]
+    fn force_history_writes_csv() {
+        let mut history = ForceHistory::new(DisplacedFluid::new(1.));
+        history.samples.push(ForceSample {
+            time: 0.5,
+            force: Vector3::new(1., -2., 0.25),
+            moment: Vector3::new(0., 3., -1.5),
+        });
+        let mut csv = vec![];
+        history.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "time,force_x,force_y,force_z,moment_x,moment_y,moment_z\n0.5,1,-2,0.25,0,3,-1.5\n"
+        );
+    }
+
+    /// d'Alembert: a body moving steadily through an ideal fluid feels no force.
+    #[test]
+    fn steady_translation_has_no_force() {
+        let (body, volume, inverses) = fixtures::panel_sphere();
+        let fluid = DisplacedFluid::new(1.).with_volume(volume, Matrix3::zero());
+        let velocity = Vector3::new(0.4, -0.2, 1.);
+        let history = run(
+            body.with_force_history(ForceHistory::new(fluid)),
+            &inverses,
+            Kinematics::Translation { velocity },
+            10,
+            0.01,
+            |_| vec![],
+        );
+
+        assert_eq!(history.samples.len(), 9);
+        for sample in &history.samples {
+            println!("{:?}", sample);
+            assert_le!(
+                sample.force.magnitude(),
+                0.01 * volume * velocity.magnitude()
+            );
+            assert_le!(
+                sample.moment.magnitude(),
+                0.01 * volume * velocity.magnitude()
+            );
+        }
+    }
+
+    /// An oscillating sphere is pushed against its acceleration by its added mass, half
+    /// the displaced fluid, overestimated by a quarter by the coarse icosphere.
+    #[test]
+    fn oscillating_sphere_feels_its_added_mass() {
+        let (body, volume, inverses) = fixtures::panel_sphere();
+        let fluid = DisplacedFluid::new(1.).with_volume(volume, Matrix3::zero());
+        let amplitude = Vector3::new(0., 0.2, 0.);
+        let frequency = 0.5;
+        let history = run(
+            body.with_force_history(ForceHistory::new(fluid)),
+            &inverses,
+            Kinematics::Oscillation {
+                amplitude,
+                angular_amplitude: Vector3::zero(),
+                frequency,
+            },
+            100,
+            0.01,
+            |_| vec![],
+        );
+
+        let omega = 2. * PI * frequency;
+        let peak = 0.5 * volume * amplitude.magnitude() * omega * omega;
+        for sample in &history.samples {
+            let acceleration = -amplitude * omega * omega * (omega * sample.time).sin();
+            let expected = -0.5 * volume * acceleration;
+            println!("force {:?}, expected {:?}", sample.force, expected);
+            assert_le!((sample.force - expected).magnitude(), 0.3 * peak);
+        }
+    }
+
+    /// A sphere leaving a vortex ring of circulation `Γ` and radius `R` behind it every step
+    /// hands the wake the impulse `ρ Γ π R²` along its motion per step, a steady drag of that
+    /// over `dt`, which the boundary vorticies alone do not see.
+    #[test]
+    fn shed_wake_makes_steady_drag() {
+        let (body, volume, inverses) = fixtures::panel_sphere();
+        let fluid = DisplacedFluid::new(1.).with_volume(volume, Matrix3::zero());
+        let velocity = Vector3::new(0., 0., 1.);
+        let (circulation, radius, dt) = (0.1, 0.5, 0.01);
+        let history = run(
+            body.with_force_history(ForceHistory::new(fluid)),
+            &inverses,
+            Kinematics::Translation { velocity },
+            20,
+            dt,
+            |body| {
+                let behind = body.transform.translation - velocity * 3.;
+                fixtures::vortex_ring(behind, velocity, radius, circulation, 32)
+            },
+        );
+
+        let drag = circulation * PI * radius * radius / dt;
+        let sphere_radius = (volume * 3. / (4. * PI)).cbrt();
+        let area = PI * sphere_radius * sphere_radius;
+        for sample in &history.samples {
+            let (cd, cl) = sample.coefficients(1., velocity, area).unwrap();
+            println!("force {:?}, cd {cd}, cl {cl}", sample.force);
+            assert_le!((cd * 0.5 * area - drag).abs(), 0.05 * drag);
+            assert_le!(cl, 0.01 * cd);
+        }
+        assert!(history.samples[0]
+            .coefficients(1., Vector3::zero(), area)
+            .is_none());
+    }
+}
@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use cgmath::{InnerSpace, Vector3, Vector4};
+
+    use crate::{
+        cpu::{active_vorticies::CpuActiveVorticies, particles::CpuParticles},
+        physics::{
+            inertia::{DragLaw, Inertia},
+            integrator::Integrator,
+        },
+        structures::{particle::Particle, vortex::Vortex},
+        support::camera::PerspectiveCamera,
+        traits::steppable::Steppable,
+    };
+
+    use all_asserts::{assert_gt, assert_le, assert_lt};
+
+    fn at_rest(position: Vector3<f32>) -> Particle {
+        Particle {
+            position: position.extend(1.),
+            lifetime: Vector4::new(100., 100., 0., 0.),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn stokes_number_round_trips() {
+        let inertia = Inertia::from_stokes_number(0.5, 2., 0.01, 0.001);
+        assert_le!((inertia.stokes_number(2.) - 0.5).abs(), 1e-5);
+        assert_le!((inertia.response_time() - 1.).abs(), 1e-5);
+    }
+
+    /// A heavy particle dropped into still fluid reaches the Stokes settling velocity, and
+    /// settles slower under the nonlinear drag.
+    #[test]
+    fn particles_settle_at_terminal_velocity() {
+        let camera = PerspectiveCamera::new(100, 100);
+        let inertia = Inertia::new(1e-5, 0.01, 0.01).with_fluid_density(0.001);
+        let settling = inertia.settling_velocity();
+        assert_lt!(settling.y, 0.);
+        let settle = |inertia: Inertia| {
+            let mut particles = CpuParticles::new(
+                vec![at_rest(Vector3::new(0., 0., 0.))],
+                Rc::new(RefCell::new(vec![])),
+            )
+            .with_resetting(false)
+            .with_inertia(inertia);
+            for _ in 0..100 {
+                particles.step(0.1 * inertia.response_time(), &camera);
+            }
+            particles.particles[0].velocity.truncate()
+        };
+
+        let stokes = settle(inertia);
+        assert_le!((stokes - settling).magnitude(), 1e-3 * settling.magnitude());
+        let nonlinear = settle(inertia.with_drag_law(DragLaw::SchillerNaumann));
+        assert_gt!(inertia.reynolds_number(nonlinear), 0.1);
+        assert_lt!(nonlinear.magnitude(), 0.99 * settling.magnitude());
+        assert_le!(nonlinear.x.abs() + nonlinear.z.abs(), 1e-6);
+    }
+
+    /// Around a vortex, particles of a small Stokes number follow the circular streamlines
+    /// like tracers advected by the same integrator, even with a response time far below the
+    /// time step, while heavy ones are flung outwards.
+    #[test]
+    fn heavy_particles_are_centrifuged() {
+        let camera = PerspectiveCamera::new(100, 100);
+        let vorticies = CpuActiveVorticies::new(
+            vec![Vortex {
+                position: Vector4::new(0., 0., 0., 1.),
+                vorticity: Vector4::new(0., 1., 0., 1.),
+                lifetime: Vector4::new(100., 100., 0., 0.),
+                ..Default::default()
+            }],
+            100.0,
+            100.0,
+            1.0,
+            1.0,
+            0,
+        );
+        let radius_after = |inertia: Inertia, integrator: Integrator| {
+            let mut particles = CpuParticles::new(
+                vec![at_rest(Vector3::new(0.5, 0., 0.))],
+                vorticies.shared_vorticies(),
+            )
+            .with_resetting(false)
+            .with_integrator(integrator)
+            .with_inertia(inertia.with_gravity(Vector3::new(0., 0., 0.)));
+            for _ in 0..200 {
+                particles.step(0.01, &camera);
+            }
+            let position = particles.particles[0].position;
+            assert_eq!(position.y, 0.);
+            position.truncate().magnitude()
+        };
+
+        for integrator in [Integrator::Euler, Integrator::RungeKutta4] {
+            let tracer = radius_after(Inertia::default(), integrator);
+            let light = radius_after(
+                Inertia::from_stokes_number(1e-4, 0.1, 0.01, 0.001),
+                integrator,
+            );
+            assert_le!((light - tracer).abs(), 1e-3, "{:?}", integrator);
+            let heavy = radius_after(
+                Inertia::from_stokes_number(1., 0.1, 0.01, 0.001),
+                integrator,
+            );
+            assert_gt!(heavy, tracer + 0.1, "{:?}", integrator);
+        }
+        // Fourth order tracers stay on their circle, where Euler spirals out.
+        let tracer = radius_after(Inertia::default(), Integrator::RungeKutta4);
+        assert_le!((tracer - 0.5).abs(), 1e-3);
+    }
+}
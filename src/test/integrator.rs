@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Vector3, Vector4};
+
+    use crate::{
+        physics::{integrator::Integrator, kernel::Kernel},
+        structures::vortex::Vortex,
+    };
+
+    use all_asserts::{assert_gt, assert_le};
+
+    /// Error after orbiting a single vortex at radius 0.5 by one radian in `steps` steps.
+    /// The exact solution turns around the y axis with angular velocity `1 / r³`.
+    fn orbit_error(integrator: Integrator, steps: usize) -> f32 {
+        let kernel = Kernel::singular();
+        let vortex = Vortex {
+            position: Vector4::new(0., 0., 0., 1.),
+            vorticity: Vector4::new(0., 1., 0., 1.),
+            ..Default::default()
+        };
+        let radius = 0.5f32;
+        let dt = radius * radius * radius / steps as f32;
+        let mut position = Vector3::new(radius, 0., 0.);
+        for _ in 0..steps {
+            position =
+                integrator.integrate(position, dt, |point| kernel.get_velocity(point, &vortex));
+        }
+        let exact = Vector3::new(1f32.cos(), 0., 1f32.sin()) * radius;
+        (position - exact).magnitude()
+    }
+
+    #[test]
+    fn integrators_converge_with_their_order() {
+        for (integrator, order) in [
+            (Integrator::Euler, 1),
+            (Integrator::Midpoint, 2),
+            (Integrator::LowStorageRungeKutta3, 3),
+            (Integrator::RungeKutta4, 4),
+        ] {
+            let coarse = orbit_error(integrator, 8);
+            let fine = orbit_error(integrator, 16);
+            let observed = (coarse / fine).log2();
+            assert_gt!(observed, order as f32 - 0.3, "{:?}", integrator);
+        }
+    }
+
+    #[test]
+    fn higher_order_integrators_are_more_accurate() {
+        let errors = [
+            Integrator::Euler,
+            Integrator::Midpoint,
+            Integrator::LowStorageRungeKutta3,
+            Integrator::RungeKutta4,
+        ]
+        .map(|integrator| orbit_error(integrator, 16));
+        for pair in errors.windows(2) {
+            assert_le!(pair[1], pair[0]);
+        }
+    }
+}
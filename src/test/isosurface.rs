@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use cgmath::{InnerSpace, Vector3, Vector4};
+
+    use crate::{
+        cpu::field_sampler::CpuFieldSampler,
+        physics::{
+            isosurface::marching_cubes, kernel::Kernel, vortex_identification::Identification,
+        },
+        structures::{
+            grid::{Grid, ScalarVolume},
+            triangle_mesh::TriangleMesh,
+            vortex::Vortex,
+        },
+        util::get_triangles_from_gltf,
+    };
+
+    use all_asserts::{assert_gt, assert_le};
+
+    fn sphere(radius: f32) -> TriangleMesh {
+        let grid = Grid::from_bounds(Vector3::new(-1., -1., -1.), Vector3::new(1., 1., 1.), 0.1);
+        let volume = ScalarVolume {
+            grid,
+            values: grid
+                .points()
+                .iter()
+                .map(|point| 1. - point.magnitude2())
+                .collect(),
+        };
+        marching_cubes(&volume, 1. - radius * radius)
+    }
+
+    /// Number of triangles at every edge, which is two everywhere on a closed surface.
+    fn edge_counts(mesh: &TriangleMesh) -> HashMap<(u32, u32), usize> {
+        let mut counts = HashMap::new();
+        for triangle in mesh.indices.chunks(3) {
+            for i in 0..3 {
+                let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+                *counts.entry((a.min(b), a.max(b))).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    #[test]
+    fn sphere_is_closed_and_faces_out() {
+        let radius = 0.72;
+        let mesh = sphere(radius);
+        assert_gt!(mesh.triangle_count(), 100);
+        for (vertex, normal) in mesh.vertices.iter().zip(&mesh.normals) {
+            assert_le!((vertex.magnitude() - radius).abs(), 0.01);
+            assert_gt!(normal.dot(vertex.normalize()), 0.99);
+        }
+        for [a, b, c] in mesh.triangles() {
+            assert_gt!((b - a).cross(c - a).dot(a + b + c), 0.);
+        }
+        let area = 4. * std::f32::consts::PI * radius * radius;
+        assert_le!((mesh.area() - area).abs(), 0.03 * area);
+        let edges = edge_counts(&mesh);
+        assert!(edges.values().all(|count| *count == 2));
+        // A sphere has Euler characteristic 2.
+        let euler = mesh.vertices.len() as i64 - edges.len() as i64 + mesh.triangle_count() as i64;
+        assert_eq!(euler, 2);
+    }
+
+    /// The vorticity of a vortex ring is a torus, a closed surface with Euler characteristic 0.
+    #[test]
+    fn vortex_ring_is_a_torus() {
+        let count = 64;
+        let length = 2. * std::f32::consts::PI / count as f32;
+        let sources = (0..count)
+            .map(|i| {
+                let (sin, cos) = (2. * std::f32::consts::PI * i as f32 / count as f32).sin_cos();
+                Vortex {
+                    position: Vector4::new(cos, sin, 0., 1.),
+                    vorticity: Vector4::new(-sin * length, cos * length, 0., 0.),
+                    ..Default::default()
+                }
+            })
+            .collect::<Vec<_>>();
+        let grid = Grid::from_bounds(
+            Vector3::new(-1.5, -1.5, -0.5),
+            Vector3::new(1.5, 1.5, 0.5),
+            0.05,
+        );
+        let volume = CpuFieldSampler::new(Kernel::rosenhead_moore(0.2))
+            .sample_grid(grid, &sources)
+            .volume(Identification::VorticityMagnitude);
+        let (_, max) = volume.range();
+        let mesh = marching_cubes(&volume, 0.3 * max);
+
+        for vertex in &mesh.vertices {
+            let ring_distance = Vector3::new(vertex.truncate().magnitude() - 1., vertex.z, 0.);
+            assert_le!(ring_distance.magnitude(), 0.4);
+        }
+        let edges = edge_counts(&mesh);
+        assert!(edges.values().all(|count| *count == 2));
+        let euler = mesh.vertices.len() as i64 - edges.len() as i64 + mesh.triangle_count() as i64;
+        assert_eq!(euler, 0);
+    }
+
+    #[test]
+    fn mesh_is_written_as_obj() {
+        let mesh = TriangleMesh {
+            vertices: vec![
+                Vector3::new(0., 0., 0.),
+                Vector3::new(1., 0., 0.),
+                Vector3::new(0., 1., 0.),
+            ],
+            normals: vec![Vector3::new(0., 0., 1.); 3],
+            indices: vec![0, 1, 2],
+        };
+        let mut obj = vec![];
+        mesh.write_obj(&mut obj).unwrap();
+        assert_eq!(
+            String::from_utf8(obj).unwrap(),
+            "v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 1\nvn 0 0 1\nvn 0 0 1\nf 1//1 2//2 3//3\n"
+        );
+    }
+
+    /// The glTF loader used for the boundary models reads the exported surface back.
+    #[test]
+    fn mesh_round_trips_through_gltf() {
+        let mesh = sphere(0.45);
+        let path = std::env::temp_dir().join("vortex_isosurface_test.glb");
+        mesh.save(&path).unwrap();
+        let triangles = get_triangles_from_gltf(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(triangles.len(), mesh.triangle_count());
+        for (loaded, saved) in triangles.iter().zip(mesh.triangles()) {
+            assert_eq!(*loaded, saved);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Matrix3, Vector3, Vector4};
+
+    use crate::{
+        physics::{kernel::Kernel, stretching::StretchingScheme},
+        structures::vortex::Vortex,
+    };
+
+    use all_asserts::assert_le;
+
+    fn regularized_kernels(core_radius: f32) -> Vec<Kernel> {
+        vec![
+            Kernel::rosenhead_moore(core_radius),
+            Kernel::gaussian(core_radius),
+            Kernel::winckelmans_leonard(core_radius),
+        ]
+    }
+
+    #[test]
+    fn regularized_kernels_match_singular_far_away() {
+        let singular = Kernel::singular();
+        for kernel in regularized_kernels(0.05) {
+            for distance in [2.0f32, 5.0, 10.0] {
+                let relative = (kernel.factor(distance) - singular.factor(distance)).abs()
+                    / singular.factor(distance);
+                assert_le!(relative, 0.001, "{:?} at {}", kernel.smoothing, distance);
+            }
+        }
+    }
+
+    #[test]
+    fn regularized_kernels_are_bounded_near_vortex() {
+        let vortex = Vortex {
+            position: Vector4::new(0., 0., 0., 1.),
+            vorticity: Vector4::new(0., 1., 0., 1.),
+            ..Default::default()
+        };
+        for kernel in regularized_kernels(0.1) {
+            let peak = (1..200)
+                .map(|i| Vector3::new(i as f32 * 0.005, 0., 0.))
+                .map(|point| kernel.get_velocity(point, &vortex).magnitude())
+                .fold(0.0f32, f32::max);
+            assert_le!(peak, 1.0 / (0.1 * 0.1), "{:?}", kernel.smoothing);
+        }
+    }
+
+    #[test]
+    fn gaussian_series_matches_closed_form() {
+        let kernel = Kernel::gaussian(1.0);
+        let below = kernel.factor(0.4999);
+        let above = kernel.factor(0.5001);
+        assert_le!((below - above).abs() / below, 0.001);
+    }
+
+    #[test]
+    fn velocity_gradient_matches_finite_differences() {
+        let vortex = Vortex {
+            position: Vector4::new(0.1, -0.2, 0.3, 1.),
+            vorticity: Vector4::new(0.3, 1., -0.5, 1.),
+            ..Default::default()
+        };
+        let point = Vector3::new(0.5, 0.1, -0.2);
+        let h = 0.001;
+        let mut kernels = regularized_kernels(0.3);
+        kernels.push(Kernel::singular());
+        for kernel in kernels {
+            let gradient = kernel.get_velocity_gradient(point, &vortex);
+            for j in 0..3 {
+                let mut offset = Vector3::new(0., 0., 0.);
+                offset[j] = h;
+                let column = (kernel.get_velocity(point + offset, &vortex)
+                    - kernel.get_velocity(point - offset, &vortex))
+                    / (2. * h);
+                assert_le!(
+                    (column - gradient[j]).magnitude(),
+                    0.01 * gradient[j].magnitude().max(1.),
+                    "{:?} column {}",
+                    kernel.smoothing,
+                    j
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn stretching_schemes() {
+        let gradient = Matrix3::new(1., 2., 0., 0., -1., 0., 0., 0., 0.);
+        let vorticity = Vector3::new(1., 1., 0.);
+        assert_eq!(
+            StretchingScheme::Classical.stretching(gradient, vorticity),
+            Vector3::new(1., 1., 0.)
+        );
+        assert_eq!(
+            StretchingScheme::Transpose.stretching(gradient, vorticity),
+            Vector3::new(3., -1., 0.)
+        );
+        assert_eq!(
+            StretchingScheme::Mixed.stretching(gradient, vorticity),
+            Vector3::new(2., 0., 0.)
+        );
+        assert_eq!(
+            StretchingScheme::Disabled.stretching(gradient, vorticity),
+            Vector3::new(0., 0., 0.)
+        );
+    }
+}
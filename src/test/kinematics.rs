@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod tests {
+    use cgmath::{Deg, InnerSpace, Quaternion, Rotation3, Vector3};
+
+    use crate::{
+        objects::boundary_vorticies::BoundaryVorticies,
+        physics::{
+            boundary_condition::BoundaryCondition,
+            kernel::Kernel,
+            kinematics::{Keyframe, Kinematics},
+        },
+        structures::transform::Transform,
+        test::fixtures,
+    };
+
+    use all_asserts::assert_le;
+
+    fn initial() -> Transform {
+        Transform::new()
+            .with_translation(Vector3::new(1., 0.5, -2.))
+            .with_rotation(Quaternion::from_axis_angle(Vector3::unit_z(), Deg(20.)))
+    }
+
+    fn keyframes() -> Kinematics {
+        Kinematics::Keyframes(vec![
+            Keyframe {
+                time: 0.5,
+                transform: initial(),
+            },
+            Keyframe {
+                time: 1.5,
+                transform: Transform::new()
+                    .with_translation(Vector3::new(2., 0., 0.))
+                    .with_rotation(Quaternion::from_axis_angle(
+                        Vector3::new(1., 1., 0.).normalize(),
+                        Deg(120.),
+                    )),
+            },
+            Keyframe {
+                time: 2.0,
+                transform: Transform::new(),
+            },
+        ])
+    }
+
+    /// The velocity of a body point agrees with the central difference of its position.
+    #[test]
+    fn velocity_is_the_derivative_of_the_motion() {
+        let point = Vector3::new(0.3, -0.7, 0.4);
+        let h = 1e-3;
+        for kinematics in [
+            Kinematics::Translation {
+                velocity: Vector3::new(1., -2., 0.5),
+            },
+            Kinematics::Rotation {
+                angular_velocity: Vector3::new(0., 2., 1.),
+            },
+            Kinematics::Oscillation {
+                amplitude: Vector3::new(0., 0.3, 0.),
+                angular_amplitude: Vector3::new(0.5, 0., 0.),
+                frequency: 0.7,
+            },
+            keyframes(),
+        ] {
+            for time in [0.2, 0.9, 1.7] {
+                let at = |time| kinematics.transform_at(&initial(), time);
+                let world = |transform: Transform| {
+                    transform.rotation_matrix() * point + transform.translation
+                };
+                let difference = (world(at(time + h)) - world(at(time - h))) / (2. * h);
+                let velocity = kinematics
+                    .velocity_at(time)
+                    .at(world(at(time)), at(time).translation);
+                println!(
+                    "{:?} at {}: {:?} {:?}",
+                    kinematics, time, difference, velocity
+                );
+                assert_le!((difference - velocity).magnitude(), 2e-2);
+            }
+        }
+    }
+
+    #[test]
+    fn keyframes_are_passed_and_held() {
+        let kinematics = keyframes();
+        let Kinematics::Keyframes(frames) = &kinematics else {
+            unreachable!()
+        };
+        for (time, frame) in [(0.0, frames[0]), (1.5, frames[1]), (3.0, frames[2])] {
+            let transform = kinematics.transform_at(&Transform::new(), time);
+            assert_le!(
+                (transform.translation - frame.transform.translation).magnitude(),
+                1e-5
+            );
+            assert_le!(
+                (transform.rotation - frame.transform.rotation).magnitude(),
+                1e-5
+            );
+        }
+        assert_eq!(kinematics.velocity_at(3.0).linear, Vector3::new(0., 0., 0.));
+    }
+
+    /// A cube translating through still fluid pushes the fluid along its normals at the
+    /// speed of its faces.
+    #[test]
+    fn moving_body_displaces_the_fluid() {
+        let kernel = Kernel::default();
+        let boundary_condition = BoundaryCondition::Slip;
+        let velocity = Vector3::new(1., 0.5, 0.);
+        let mut bodies = [fixtures::panel_body("resources/models/cube.glb")
+            .with_transform(initial())
+            .with_kinematics(Kinematics::Translation { velocity })];
+        bodies[0].move_to(0.4);
+        let inverses = fixtures::inverses(&bodies, boundary_condition);
+        BoundaryVorticies::correct_bodies(&mut bodies, &inverses, &[], &kernel, boundary_condition);
+
+        let world = bodies[0].world_vorticies(0);
+        let fluid = BoundaryVorticies::create_error_vector(&world, &[], true, &kernel);
+        for (vortex, fluid) in world.iter().zip(fluid) {
+            let normal = vortex.normal.truncate();
+            let body = bodies[0].velocity_at(vortex.position.truncate());
+            assert_le!((fluid.dot(normal) - body.dot(normal)).abs(), 1e-3);
+        }
+        let strength = world
+            .iter()
+            .map(|v| v.vorticity.truncate().magnitude())
+            .sum::<f32>();
+        assert!(strength > 0.0);
+    }
+}
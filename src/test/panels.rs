@@ -0,0 +1,269 @@
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Quaternion, Rad, Rotation3, Vector3, Vector4};
+
+    use crate::{
+        objects::boundary_vorticies::{BoundaryVorticies, PSEUDO_INVERSE_TOLERANCE},
+        physics::{boundary_condition::BoundaryCondition, kernel::Kernel, panel},
+        structures::{transform::Transform, vortex::Vortex},
+        util::{get_triangles_from_gltf, get_vertices_and_normals_from_gltf},
+    };
+
+    use all_asserts::{assert_gt, assert_le, assert_lt};
+
+    fn coincident_pairs(vorticies: &[Vortex]) -> usize {
+        (0..vorticies.len())
+            .flat_map(|i| (i + 1..vorticies.len()).map(move |j| (i, j)))
+            .filter(|(i, j)| (vorticies[*i].position - vorticies[*j].position).magnitude() < 0.0001)
+            .count()
+    }
+
+    /// Relative velocity left on the boundary after correcting it for a vortex above it.
+    fn residual(boundary: &Vec<Vortex>) -> f32 {
+        let kernel = Kernel::default();
+        let active = vec![Vortex {
+            position: Vector4::new(0.2, 2.0, -0.3, 1.),
+            vorticity: Vector4::new(1., 0., 0.5, 1.),
+            ..Default::default()
+        }];
+        let inverse = BoundaryVorticies::create_inverse(boundary, &kernel, BoundaryCondition::Slip);
+        let before = BoundaryVorticies::create_error_vector(boundary, &active, false, &kernel);
+        let corrections = BoundaryVorticies::calculate_corrections(
+            &inverse,
+            &BoundaryCondition::Slip.constrain_errors(boundary, &before),
+        );
+        let corrected = boundary
+            .iter()
+            .zip(corrections)
+            .map(|(v, c)| Vortex {
+                vorticity: c.extend(1.),
+                ..*v
+            })
+            .collect::<Vec<_>>();
+        let after = BoundaryVorticies::create_error_vector(&corrected, &active, true, &kernel);
+        let norm = |errors: Vec<Vector3<f32>>| {
+            BoundaryCondition::Slip
+                .constrain_errors(boundary, &errors)
+                .iter()
+                .map(|e| e.magnitude2())
+                .sum::<f32>()
+                .sqrt()
+        };
+        norm(after) / norm(before)
+    }
+
+    #[test]
+    fn vorticies_sit_on_face_centroids() {
+        let panels = BoundaryVorticies::panels_from_triangles(vec![
+            [
+                Vector3::new(0., 0., 0.),
+                Vector3::new(2., 0., 0.),
+                Vector3::new(0., 2., 0.),
+            ],
+            // Degenerate, left out
+            [
+                Vector3::new(0., 0., 0.),
+                Vector3::new(1., 0., 0.),
+                Vector3::new(2., 0., 0.),
+            ],
+        ]);
+
+        assert_eq!(panels.len(), 1);
+        assert_le!(
+            (panels[0].position - Vector4::new(2. / 3., 2. / 3., 0., 1.)).magnitude(),
+            1e-6
+        );
+        assert_eq!(panels[0].normal, Vector4::new(0., 0., 1., 2.));
+        let corners = panel::corners(&panels[0]).unwrap();
+        let expected = [
+            Vector3::new(-2. / 3., -2. / 3., 0.),
+            Vector3::new(4. / 3., -2. / 3., 0.),
+            Vector3::new(-2. / 3., 4. / 3., 0.),
+        ];
+        for (corner, expected) in corners.iter().zip(expected) {
+            assert_le!((corner - expected).magnitude(), 1e-5);
+        }
+    }
+
+    fn triangle_panel() -> Vortex {
+        let mut panels = BoundaryVorticies::panels_from_triangles(vec![[
+            Vector3::new(0.1, -0.2, 0.3),
+            Vector3::new(0.5, 0.1, 0.2),
+            Vector3::new(-0.1, 0.3, 0.4),
+        ]]);
+        panels[0].vorticity = Vector4::new(0.3, -0.2, 0.5, 1.);
+        panels[0]
+    }
+
+    /// The panel spread into `n²` point vorticies over equal subtriangles.
+    fn subdivided(vortex: &Vortex, n: usize) -> Vec<Vortex> {
+        let [a, b, c] = panel::corners(vortex)
+            .unwrap()
+            .map(|corner| vortex.position.truncate() + corner);
+        let (u, v) = ((b - a) / n as f32, (c - a) / n as f32);
+        let strength = vortex.vorticity.truncate() / (n * n) as f32;
+        let mut points = vec![];
+        for i in 0..n {
+            for j in 0..n - i {
+                let corner = a + u * i as f32 + v * j as f32;
+                points.push(corner + (u + v) / 3.);
+                if i + j + 1 < n {
+                    points.push(corner + (u + v) * 2. / 3.);
+                }
+            }
+        }
+        points
+            .into_iter()
+            .map(|point| Vortex {
+                position: point.extend(1.),
+                vorticity: strength.extend(1.),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// The analytic velocity of a panel is the limit of the point vorticies it is made of,
+    /// near it and beyond `panel::NEAR_FIELD_RADII`.
+    #[test]
+    fn panel_velocity_matches_quadrature() {
+        let kernel = Kernel::singular();
+        let vortex = triangle_panel();
+        let points = subdivided(&vortex, 200);
+        let center = vortex.position.truncate();
+        let normal = vortex.normal.truncate();
+        for offset in [
+            normal * 0.2,
+            -normal * 0.05 + Vector3::new(0.1, 0., 0.),
+            Vector3::new(0.6, 0.4, -0.3),
+            Vector3::new(-3., 2., 1.),
+        ] {
+            let point = center + offset;
+            let exact = kernel.get_velocity(point, &vortex);
+            let quadrature = points
+                .iter()
+                // The pieces are weaker than `MIN_STRENGTH`.
+                .map(|p| kernel.get_linear_velocity(point, p))
+                .sum::<Vector3<f32>>();
+            assert_le!(
+                (exact - quadrature).magnitude(),
+                0.01 * quadrature.magnitude(),
+                "{:?}",
+                offset
+            );
+        }
+    }
+
+    /// At its own centroid a panel induces the mean of the velocities just above and below
+    /// it, which differ by the jump across the sheet.
+    #[test]
+    fn panel_self_influence_is_the_principal_value() {
+        let kernel = Kernel::singular();
+        let vortex = triangle_panel();
+        let center = vortex.position.truncate();
+        let normal = vortex.normal.truncate();
+        let at = |height: f32| kernel.get_velocity(center + normal * height, &vortex);
+        let (above, below) = (at(1e-4), at(-1e-4));
+        assert_le!((at(0.) - (above + below) / 2.).magnitude(), 1e-3);
+        let sheet = vortex.vorticity.truncate() / vortex.normal.w;
+        let jump = sheet.cross(normal) * 4. * std::f32::consts::PI;
+        assert_le!((above - below + jump).magnitude(), 1e-2 * jump.magnitude());
+    }
+
+    /// Moving a panel to the world frame rotates its corners along with its normal.
+    #[test]
+    fn panel_corners_follow_the_transform() {
+        let vortex = triangle_panel();
+        let transform = Transform::new()
+            .with_translation(Vector3::new(1., 2., 3.))
+            .with_rotation(Quaternion::from_axis_angle(
+                Vector3::new(1., 1., 0.).normalize(),
+                Rad(1.2),
+            ));
+        let world = transform.to_world(&vortex);
+        let rotation = transform.rotation_matrix();
+        let expected = panel::corners(&vortex).unwrap();
+        for (corner, expected) in panel::corners(&world).unwrap().iter().zip(expected) {
+            assert_le!((corner - rotation * expected).magnitude(), 1e-5);
+        }
+    }
+
+    #[test]
+    fn panels_avoid_coincident_vorticies_at_hard_edges() {
+        let model = "resources/models/cube.glb";
+        let vertices =
+            BoundaryVorticies::vorticies_from_positions(get_vertices_and_normals_from_gltf(model));
+        let panels = BoundaryVorticies::panels_from_triangles(get_triangles_from_gltf(model));
+        let (vertex_residual, panel_residual) = (residual(&vertices), residual(&panels));
+        println!(
+            "vertices: {}, coincident pairs {}, residual {}",
+            vertices.len(),
+            coincident_pairs(&vertices),
+            vertex_residual
+        );
+        println!(
+            "panels: {}, coincident pairs {}, residual {}",
+            panels.len(),
+            coincident_pairs(&panels),
+            panel_residual
+        );
+
+        assert_eq!(coincident_pairs(&vertices), 24);
+        assert_eq!(coincident_pairs(&panels), 0);
+        let total_area = panels.iter().map(|p| p.normal.w).sum::<f32>();
+        assert_le!((total_area - 24.).abs(), 1e-4);
+        assert_lt!(panel_residual, vertex_residual);
+    }
+
+    /// Singular values of the slip influence matrix above `PSEUDO_INVERSE_TOLERANCE`, the
+    /// ones the pseudo-inverse keeps, and their spread, the condition number it works with.
+    fn conditioning(vorticies: &Vec<Vortex>) -> (usize, f32) {
+        let singular_values = BoundaryVorticies::create_matrix(
+            vorticies,
+            &Kernel::default(),
+            BoundaryCondition::Slip,
+        )
+        .singular_values();
+        let kept = singular_values
+            .iter()
+            .filter(|value| **value > PSEUDO_INVERSE_TOLERANCE)
+            .copied()
+            .collect::<Vec<_>>();
+        let (min, max) = kept.iter().fold((f32::MAX, 0f32), |(min, max), value| {
+            (min.min(*value), max.max(*value))
+        });
+        (kept.len(), max / min)
+    }
+
+    /// On the car the vertices crowd together at its seams, which the slip condition cannot
+    /// tell apart, while the centroids keep one independent constraint each with a moderate
+    /// spread of singular values.
+    #[test]
+    fn car_panels_are_well_conditioned() {
+        let model = "resources/models/car/car_0_n.glb";
+        let vertices =
+            BoundaryVorticies::vorticies_from_positions(get_vertices_and_normals_from_gltf(model));
+        let panels = BoundaryVorticies::panels_from_triangles(get_triangles_from_gltf(model));
+        let (vertex_rank, vertex_condition) = conditioning(&vertices);
+        let (panel_rank, panel_condition) = conditioning(&panels);
+        println!(
+            "vertices: {}, coincident pairs {}, rank {}, condition {}",
+            vertices.len(),
+            coincident_pairs(&vertices),
+            vertex_rank,
+            vertex_condition
+        );
+        println!(
+            "panels: {}, coincident pairs {}, rank {}, condition {}",
+            panels.len(),
+            coincident_pairs(&panels),
+            panel_rank,
+            panel_condition
+        );
+
+        assert_eq!(coincident_pairs(&panels), 0);
+        assert_eq!(panel_rank, panels.len());
+        assert_lt!(panel_condition, 1e3);
+        assert_gt!(vertex_condition, 1e6);
+        assert_lt!(residual(&panels), residual(&vertices));
+    }
+}
@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use cgmath::{InnerSpace, Vector3, Vector4};
+
+    use crate::{
+        cpu::{active_vorticies::CpuActiveVorticies, particles::CpuParticles},
+        physics::{diffusion::ScalarDiffusion, integrator::Integrator},
+        structures::{particle::Particle, particle_emitter::ParticleEmitter, vortex::Vortex},
+        support::camera::PerspectiveCamera,
+        traits::steppable::Steppable,
+    };
+
+    use all_asserts::{assert_gt, assert_le, assert_lt};
+
+    const RED: Vector4<f32> = Vector4::new(1., 0., 0., 0.);
+    const BLUE: Vector4<f32> = Vector4::new(0., 0., 1., 1.);
+
+    fn particle(position: Vector3<f32>, lifetime: f32, scalars: Vector4<f32>) -> Particle {
+        Particle {
+            position: position.extend(1.),
+            lifetime: Vector4::new(lifetime, lifetime, 0., 0.),
+            scalars,
+            ..Default::default()
+        }
+    }
+
+    /// Expired particles respawn inside one of the emitters, taking over its scalars, and
+    /// every emitter gets some of them.
+    #[test]
+    fn particles_respawn_at_emitters() {
+        let camera = PerspectiveCamera::new(100, 100);
+        let emitters = vec![
+            ParticleEmitter::new(Vector3::new(-1., 0., 0.), 0.1, RED),
+            ParticleEmitter::new(Vector3::new(1., 0., 0.), 0.1, BLUE),
+        ];
+        let particles = (0..200)
+            .map(|_| {
+                particle(
+                    Vector3::new(0., 0., 0.),
+                    0.001,
+                    Vector4::new(0., 0., 0., 0.),
+                )
+            })
+            .collect();
+        let mut particles = CpuParticles::new(particles, Rc::new(RefCell::new(vec![])))
+            .with_lifetime(1., 2.)
+            .with_emitters(emitters.clone());
+        particles.step(0.01, &camera);
+
+        let mut counts = [0; 2];
+        for particle in &particles.particles {
+            let emitter = emitters
+                .iter()
+                .position(|emitter| emitter.scalars == particle.scalars)
+                .unwrap();
+            counts[emitter] += 1;
+            let distance = (particle.position.truncate() - emitters[emitter].center()).magnitude();
+            assert_le!(distance, emitters[emitter].radius());
+            assert_gt!(particle.lifetime.x, 0.);
+        }
+        assert_gt!(counts[0], 50);
+        assert_gt!(counts[1], 50);
+    }
+
+    /// Without diffusion the scalars are carried along unchanged.
+    #[test]
+    fn scalars_are_advected_unchanged() {
+        let camera = PerspectiveCamera::new(100, 100);
+        let vorticies = CpuActiveVorticies::new(
+            vec![Vortex {
+                position: Vector4::new(0., 0., 0., 1.),
+                vorticity: Vector4::new(0., 1., 0., 1.),
+                lifetime: Vector4::new(100., 100., 0., 0.),
+                ..Default::default()
+            }],
+            100.0,
+            100.0,
+            1.0,
+            1.0,
+            0,
+        );
+        let scalars = |i: usize| Vector4::new(i as f32, 0.5, -1., 2.);
+        let particles = (0..16)
+            .map(|i| {
+                let angle = i as f32 / 16. * std::f32::consts::TAU;
+                let position = Vector3::new(angle.cos() * 0.5, 0., angle.sin() * 0.5);
+                particle(position, 100., scalars(i))
+            })
+            .collect::<Vec<_>>();
+        let start = particles[0].position;
+        let mut particles = CpuParticles::new(particles, vorticies.shared_vorticies())
+            .with_resetting(false)
+            .with_integrator(Integrator::RungeKutta4);
+        for _ in 0..10 {
+            particles.step(0.01, &camera);
+        }
+
+        assert_gt!((particles.particles[0].position - start).magnitude(), 0.);
+        for (i, particle) in particles.particles.iter().enumerate() {
+            assert_eq!(particle.scalars, scalars(i));
+        }
+    }
+
+    /// Two dyes side by side in still fluid mix across their interface, while the total of
+    /// every channel stays the same.
+    #[test]
+    fn diffusion_mixes_two_dyes() {
+        let camera = PerspectiveCamera::new(100, 100);
+        let spacing = 0.1;
+        let n = 8;
+        let particles = (0..n * n * n)
+            .map(|index| {
+                let (i, j, k) = (index % n, (index / n) % n, index / (n * n));
+                let position = Vector3::new(i as f32, j as f32, k as f32) * spacing;
+                particle(position, 100., if i < n / 2 { RED } else { BLUE })
+            })
+            .collect::<Vec<_>>();
+        let total = |particles: &[Particle]| {
+            particles
+                .iter()
+                .fold(Vector4::new(0., 0., 0., 0.), |sum, p| sum + p.scalars)
+        };
+        let before = total(&particles);
+        let mut particles = CpuParticles::new(particles, Rc::new(RefCell::new(vec![])))
+            .with_resetting(false)
+            .with_scalar_diffusion(ScalarDiffusion::new(0.01, spacing));
+        for _ in 0..10 {
+            particles.step(0.01, &camera);
+        }
+
+        let after = total(&particles.particles);
+        assert_le!((after - before).magnitude(), 1e-3 * before.magnitude());
+        // The red side of the interface has taken up blue dye, and the far side has not.
+        let interface = &particles.particles[n / 2 - 1];
+        assert_lt!(interface.scalars.x, 1.);
+        assert_gt!(interface.scalars.z, 0.01);
+        assert_le!(particles.particles[0].scalars.z, interface.scalars.z);
+    }
+}
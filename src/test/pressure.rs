@@ -0,0 +1,159 @@
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Vector3, Vector4, Zero};
+    use nalgebra::DMatrix;
+
+    use crate::{
+        objects::{boundary_body::BoundaryBody, boundary_vorticies::BoundaryVorticies},
+        physics::{
+            boundary_condition::BoundaryCondition, kernel::Kernel, kinematics::Kinematics,
+            pressure::Pressure,
+        },
+        structures::vortex::Vortex,
+        test::fixtures,
+    };
+
+    use all_asserts::{assert_le, assert_range};
+
+    const BOUNDARY_CONDITION: BoundaryCondition = BoundaryCondition::Slip;
+
+    /// The sphere with `kinematics` at `time`, corrected in still fluid.
+    fn corrected(
+        body: &BoundaryBody,
+        inverses: &[DMatrix<f32>],
+        kinematics: &Kinematics,
+        time: f32,
+    ) -> BoundaryBody {
+        let mut bodies = [body.clone().with_kinematics(kinematics.clone())];
+        bodies[0].move_to(time);
+        BoundaryVorticies::correct_bodies(
+            &mut bodies,
+            inverses,
+            &[],
+            &Kernel::default(),
+            BOUNDARY_CONDITION,
+        );
+        bodies[0].clone()
+    }
+
+    /// Surface pressures of `body`, its strengths changing from `before` to `after` over `dt`
+    /// in the body frame.
+    fn surface_pressures(
+        body: &BoundaryBody,
+        before: &BoundaryBody,
+        after: &BoundaryBody,
+        dt: f32,
+    ) -> (Vec<Vortex>, Vec<f32>) {
+        let world = body.world_vorticies(0);
+        let velocities = world
+            .iter()
+            .map(|vortex| body.velocity_at(vortex.position.truncate()))
+            .collect::<Vec<_>>();
+        let rates = world
+            .iter()
+            .zip(before.vorticies[0].iter().zip(&after.vorticies[0]))
+            .map(|(vortex, (before, after))| Vortex {
+                vorticity: (body.transform.rotation
+                    * ((after.vorticity - before.vorticity).truncate() / dt))
+                    .extend(0.),
+                ..*vortex
+            })
+            .collect::<Vec<_>>();
+        let field = Pressure::new().field(&world, &velocities, &rates);
+        let pressures = field.surface_pressures(world.len());
+        (world, pressures)
+    }
+
+    /// A sphere moving steadily through still fluid has the pressure of the steady flow
+    /// around it, `Cp = 1 - 9/4 sin²θ`, up to the scatter of the coarse icosphere.
+    #[test]
+    fn translating_sphere_has_potential_flow_pressure() {
+        let (body, _, inverses) = fixtures::panel_sphere();
+        let velocity = Vector3::new(0., 0., 1.);
+        let kinematics = Kinematics::Translation { velocity };
+        let body = corrected(&body, &inverses, &kinematics, 0.);
+        let (world, pressures) = surface_pressures(&body, &body, &body, 1.);
+
+        let pressure = Pressure::new();
+        let mut squared_error = 0.;
+        for (vortex, p) in world.iter().zip(&pressures) {
+            let cos = vortex.normal.truncate().normalize().dot(velocity);
+            let expected = 1. - 2.25 * (1. - cos * cos);
+            let error = pressure.coefficient(*p) - expected;
+            assert_le!(error.abs(), 0.5);
+            squared_error += error * error;
+        }
+        let rms = (squared_error / world.len() as f32).sqrt();
+        println!("rms error {rms}");
+        assert_le!(rms, 0.2);
+    }
+
+    /// At the turning point of an oscillation the sphere is at rest, and all its pressure,
+    /// `ρ R / 2 a·n`, comes from the change of the boundary strengths. The icosphere
+    /// overestimates it like the added mass.
+    #[test]
+    fn accelerating_sphere_has_unsteady_pressure() {
+        let (body, _, inverses) = fixtures::panel_sphere();
+        let amplitude = Vector3::new(0., 0.2, 0.);
+        let frequency = 0.5;
+        let kinematics = Kinematics::Oscillation {
+            amplitude,
+            angular_amplitude: Vector3::zero(),
+            frequency,
+        };
+        let turn = 0.25 / frequency;
+        let dt = 0.01;
+        let now = corrected(&body, &inverses, &kinematics, turn);
+        let before = corrected(&body, &inverses, &kinematics, turn - dt / 2.);
+        let after = corrected(&body, &inverses, &kinematics, turn + dt / 2.);
+        let (world, pressures) = surface_pressures(&now, &before, &after, dt);
+
+        let omega = 2. * std::f32::consts::PI * frequency;
+        let acceleration = -amplitude * omega * omega;
+        let radius = world[0].position.truncate().magnitude();
+        let expected = world
+            .iter()
+            .map(|vortex| 0.5 * radius * acceleration.dot(vortex.normal.truncate().normalize()))
+            .collect::<Vec<_>>();
+        let scale = pressures
+            .iter()
+            .zip(&expected)
+            .map(|(p, e)| p * e)
+            .sum::<f32>()
+            / expected.iter().map(|e| e * e).sum::<f32>();
+        let residual = pressures
+            .iter()
+            .zip(&expected)
+            .map(|(p, e)| (p - scale * e).abs())
+            .fold(0., f32::max);
+        println!("scale {scale}, residual {residual}");
+        assert_range!(1.0..1.5, scale);
+        assert_le!(residual, 0.1 * 0.5 * radius * acceleration.magnitude());
+    }
+
+    /// A boundary vortex at the world origin without a normal, and one with a normal but no
+    /// face area, get the mean pressure instead of NaN.
+    #[test]
+    fn vorticies_without_normal_or_area_have_finite_pressure() {
+        let vorticies = [
+            Vortex {
+                position: Vector4::new(0., 0., 0., 1.),
+                vorticity: Vector4::new(0., 0., 0.1, 1.),
+                normal: Vector4::new(0., 0., 0., 1.),
+                ..Default::default()
+            },
+            Vortex {
+                position: Vector4::new(0.5, 0., 0., 1.),
+                vorticity: Vector4::new(0., 0.1, 0., 1.),
+                normal: Vector4::new(1., 0., 0., 0.),
+                ..Default::default()
+            },
+        ];
+        let velocities = [Vector3::zero(); 2];
+        let field = Pressure::new().field(&vorticies, &velocities, &vorticies);
+        for pressure in field.surface_pressures(2) {
+            assert!(pressure.is_finite());
+        }
+        assert!(field.pressure(Vector3::zero(), Vector3::zero()).is_finite());
+    }
+}
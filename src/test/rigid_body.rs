@@ -0,0 +1,138 @@
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Vector3};
+    use nalgebra::DMatrix;
+
+    use crate::{
+        objects::{boundary_body::BoundaryBody, boundary_vorticies::BoundaryVorticies},
+        physics::{
+            boundary_condition::BoundaryCondition, forces::impulse, kernel::Kernel,
+            kinematics::Kinematics, rigid_body::RigidBody, treecode::Evaluator,
+        },
+        structures::vortex::Vortex,
+        test::fixtures,
+    };
+
+    use all_asserts::{assert_le, assert_range};
+
+    const BOUNDARY_CONDITION: BoundaryCondition = BoundaryCondition::Slip;
+
+    /// The centroid sphere, with the radius of a sphere of the same volume.
+    fn sphere() -> (BoundaryBody, f32, Vec<DMatrix<f32>>) {
+        let (body, volume, inverses) = fixtures::panel_sphere();
+        let radius = (volume * 3. / (4. * std::f32::consts::PI)).cbrt();
+        (body, radius, inverses)
+    }
+
+    /// The momentum of a sphere moving through still fluid is that of its added mass, half
+    /// the displaced fluid, against the motion. The coarse icosphere overestimates it by a
+    /// quarter.
+    #[test]
+    fn moving_sphere_carries_its_added_mass() {
+        let (body, radius, inverses) = sphere();
+        let velocity = Vector3::new(0.4, -0.2, 1.);
+        let mut bodies = [body.with_kinematics(Kinematics::Translation { velocity })];
+        BoundaryVorticies::correct_bodies(
+            &mut bodies,
+            &inverses,
+            &[],
+            &Kernel::default(),
+            BOUNDARY_CONDITION,
+        );
+
+        let rigid_body = RigidBody::solid_sphere(radius, 1.);
+        let (linear, angular) = rigid_body.momentum(
+            &bodies[0].world_vorticies(0),
+            &[],
+            &bodies[0].transform,
+            &bodies[0].velocity,
+        );
+        let added_mass = -0.5 * rigid_body.volume * velocity;
+        println!("momentum {:?}, added mass {:?}", linear, added_mass);
+        assert_le!(
+            (linear - added_mass).magnitude(),
+            0.3 * added_mass.magnitude()
+        );
+        assert_le!(angular.magnitude(), 0.1 * added_mass.magnitude());
+    }
+
+    /// A sphere twice as dense as the fluid sinks with `(ρ_b - ρ) / (ρ_b + ρ / 2) g`, slower
+    /// than the `(ρ_b - ρ) / ρ_b g` it would without the fluid it has to push along.
+    #[test]
+    fn sinking_sphere_accelerates_the_fluid() {
+        let (body, radius, inverses) = sphere();
+        let gravity = Vector3::new(0., -1., 0.);
+        let rigid_body = RigidBody::solid_sphere(radius, 2.).with_gravity(gravity);
+        let mut bodies = [body.with_rigid_body(rigid_body)];
+
+        let dt = 0.01;
+        let steps = 40;
+        for step in 0..steps {
+            BoundaryVorticies::correct_bodies(
+                &mut bodies,
+                &inverses,
+                &[],
+                &Kernel::default(),
+                BOUNDARY_CONDITION,
+            );
+            let world = bodies[0].world_vorticies(0);
+            bodies[0].step(&world, &[], (step + 1) as f32 * dt, dt);
+        }
+
+        let acceleration = bodies[0].velocity.linear / (steps as f32 * dt);
+        let expected = gravity * (1. / 2.5);
+        println!("acceleration {:?}, expected {:?}", acceleration, expected);
+        assert_le!((acceleration - expected).magnitude(), 0.05);
+        assert_le!(bodies[0].velocity.angular.magnitude(), 1e-3);
+    }
+
+    /// A vortex ring running into a free sphere of the density of the fluid pushes it along
+    /// its axis with part of the impulse of the ring, counted with the boundary vorticies.
+    #[test]
+    fn vortex_ring_pushes_free_sphere() {
+        let (body, radius, inverses) = sphere();
+        let kernel = Kernel::default();
+        let mut ring = fixtures::vortex_ring(
+            Vector3::new(0., 0., -2.5 * radius),
+            Vector3::unit_z(),
+            0.6 * radius,
+            1.,
+            32,
+        );
+        let (ring_impulse, _) = impulse(ring.iter());
+        let rigid_body = RigidBody::solid_sphere(radius, 1.);
+        let mut bodies = [body.with_rigid_body(rigid_body)];
+
+        let dt = 0.02;
+        for step in 0..150 {
+            BoundaryVorticies::correct_bodies(
+                &mut bodies,
+                &inverses,
+                &ring,
+                &kernel,
+                BOUNDARY_CONDITION,
+            );
+            let world = bodies[0].world_vorticies(0);
+            bodies[0].step(&world, &ring, (step + 1) as f32 * dt, dt);
+            let sources = ring.iter().chain(&world).copied().collect::<Vec<_>>();
+            let field = Evaluator::default().prepare(&sources, &kernel);
+            ring = ring
+                .iter()
+                .enumerate()
+                .map(|(i, vortex)| Vortex {
+                    position: vortex.position
+                        + field
+                            .get_velocity(vortex.position.truncate(), Some(i))
+                            .extend(0.)
+                            * dt,
+                    ..*vortex
+                })
+                .collect();
+        }
+
+        let momentum = rigid_body.mass * bodies[0].velocity.linear;
+        println!("momentum {:?}, ring impulse {:?}", momentum, ring_impulse);
+        assert_range!(0.1 * ring_impulse.z..ring_impulse.z, momentum.z);
+        assert_le!(momentum.truncate().magnitude(), 0.01 * ring_impulse.z);
+    }
+}
@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Vector4};
+
+    use crate::{
+        cpu::active_vorticies::CpuActiveVorticies,
+        objects::boundary_vorticies::BoundaryVorticies,
+        physics::shedding::{Shedder, Shedding, SheddingCriterion},
+        structures::vortex::Vortex,
+        support::camera::PerspectiveCamera,
+        traits::steppable::Steppable,
+        util::get_vertices_and_normals_from_gltf,
+    };
+
+    use all_asserts::{assert_gt, assert_le};
+
+    const SHARP_EDGES: SheddingCriterion = SheddingCriterion::SharpEdges { angle: 0.5 };
+
+    fn boundary(model: &str) -> Vec<Vortex> {
+        BoundaryVorticies::vorticies_from_positions(
+            get_vertices_and_normals_from_gltf(model)
+                .into_iter()
+                .map(|(position, normal)| {
+                    if normal.magnitude2() > 0. {
+                        (position, normal)
+                    } else {
+                        (position, position)
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn only_hard_edges_are_sharp() {
+        let sphere = boundary("resources/models/sphere_3.glb");
+        let cube = boundary("resources/models/cube.glb");
+
+        let surface = Shedding::new(0..16);
+        let edges = Shedding::new(0..16).with_criterion(SHARP_EDGES);
+        assert_eq!(surface.candidates(&sphere).len(), sphere.len());
+        assert_eq!(edges.candidates(&sphere).len(), 0);
+        // Every corner of the cube is three coincident vertices with the face normals.
+        assert_eq!(edges.candidates(&cube).len(), cube.len());
+    }
+
+    /// The candidates take turns shedding within the budget, so on average each sheds at
+    /// `rate`, and the slots are reused in a ring.
+    #[test]
+    fn candidates_take_turns_within_the_budget() {
+        let vorticies = (0..10)
+            .map(|i| Vortex {
+                position: Vector4::new(i as f32, 0., 0., 1.),
+                normal: Vector4::new(0., 1., 0., 1.),
+                vorticity: Vector4::new(0., 0., 1., 1.),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let (rate, dt) = (0.5, 0.1);
+        let shedding = Shedding::new(100..106)
+            .with_rate(rate)
+            .with_budget(4)
+            .with_offset(0.2);
+        let mut shedder = Shedder::new(shedding, &[std::slice::from_ref(&vorticies)]);
+
+        let mut shed_strength = vec![0.0; vorticies.len()];
+        let mut slots = vec![];
+        for _ in 0..5 {
+            for (slot, vortex) in shedder.shed(0, 0, &vorticies, dt) {
+                let candidate = vortex.position.x as usize;
+                assert_eq!(vortex.position.y, 0.2);
+                shed_strength[candidate] += vortex.vorticity.truncate().magnitude();
+                slots.push(slot);
+            }
+        }
+
+        for strength in shed_strength {
+            assert_le!((strength - rate * 5. * dt).abs(), 1e-5);
+        }
+        assert_eq!(slots.len(), 20);
+        assert!(slots.iter().all(|slot| (100..106).contains(slot)));
+        assert_eq!(&slots[..8], &[100, 101, 102, 103, 104, 105, 100, 101]);
+    }
+
+    /// An expired shed vortex drops out of the flow instead of respawning at random, and
+    /// dormant slots do not disturb the others.
+    #[test]
+    fn expired_shed_vorticies_go_dormant() {
+        let camera = PerspectiveCamera::new(100, 100);
+        let vortex = Vortex {
+            position: Vector4::new(0., 0., 0., 1.),
+            vorticity: Vector4::new(0., 1., 0., 1.),
+            lifetime: Vector4::new(10., 10., 0., 0.),
+            ..Default::default()
+        };
+        let vorticies =
+            CpuActiveVorticies::new(vec![vortex], 1.0, 2.0, 0.1, 0.2, 0).with_shedding_capacity(2);
+        assert_eq!(vorticies.shedding_slots, 1..3);
+
+        let shedding = Shedding::new(vorticies.shedding_slots.clone()).with_lifetime(0.15);
+        let boundary = Vortex {
+            position: Vector4::new(1., 0., 0., 1.),
+            normal: Vector4::new(1., 0., 0., 1.),
+            vorticity: Vector4::new(0., 0., 2., 1.),
+            ..Default::default()
+        };
+        let shed = shedding.shed_vortex(&boundary, 0.5);
+        vorticies.insert_shed(&[(1, shed)]);
+
+        let mut vorticies = vorticies;
+        vorticies.step(0.1, &camera);
+        let state = vorticies.get_vorticies();
+        assert!(!Shedding::is_dormant(&state[1]));
+        assert_gt!(state[1].vorticity.truncate().magnitude(), 0.5);
+        assert!(Shedding::is_dormant(&state[2]));
+
+        vorticies.step(0.1, &camera);
+        let before = state;
+        let state = vorticies.get_vorticies();
+        assert!(Shedding::is_dormant(&state[1]));
+        assert_eq!(state[1].vorticity.truncate().magnitude(), 0.);
+        assert_le!(state[1].position.x, 1.2);
+        assert_eq!(state[0].vorticity, before[0].vorticity);
+    }
+}
@@ -8,6 +8,10 @@ mod tests {
         objects::{
             active_vorticies,
             boundary_vorticies::{self, BoundaryVorticies},
+            scene::Scene,
+        },
+        physics::{
+            boundary_condition::BoundaryCondition, boundary_solver::BoundarySolver, kernel::Kernel,
         },
         structures::vortex::Vortex,
         support::magnitude_statistics::MagnitudeStatistics,
@@ -37,8 +41,12 @@ mod tests {
             ..Default::default()
         }];
 
-        let cpu_errors =
-            BoundaryVorticies::create_error_vector(&vorticies, &active_vorticies, true);
+        let cpu_errors = BoundaryVorticies::create_error_vector(
+            &vorticies,
+            &active_vorticies,
+            true,
+            &Kernel::default(),
+        );
 
         let stats = MagnitudeStatistics::from_vectors(&cpu_errors);
 
@@ -122,15 +130,20 @@ mod tests {
     ) -> (Vec<Vector3<f32>>, Vec<Vector3<f32>>) {
         let (_glfw, _window, _events) = init_gl();
 
-        let mut boundary_vorticies =
-            boundary_vorticies::BoundaryVorticies::from_vorticies(vec![boundary_vorticies]);
+        let scene = Scene::new();
+        let mut boundary_vorticies = boundary_vorticies::BoundaryVorticies::from_vorticies(
+            &scene,
+            vec![boundary_vorticies],
+            BoundarySolver::default(),
+            BoundaryCondition::default(),
+        );
         let _active_vorticies =
             active_vorticies::ActiveVorticies::new(active_vorticies, 0., 0., 0., 0., 0);
         let dt = 0.1f32;
-        boundary_vorticies.step_errors(dt);
+        boundary_vorticies.step_errors(0, dt);
         let errors_before = boundary_vorticies.get_errors();
-        boundary_vorticies.step_correction(dt);
-        boundary_vorticies.step_errors(dt);
+        boundary_vorticies.step_correction(0, dt);
+        boundary_vorticies.step_errors(0, dt);
         let errors_after = boundary_vorticies.get_errors();
         let stats = MagnitudeStatistics::from_vectors(&errors_after);
         println!("Stats: {:?}", stats);
@@ -144,13 +157,21 @@ mod tests {
         boundary_vorticies: Vec<Vortex>,
         active_vorticies: Vec<Vortex>,
     ) -> (Vec<Vector3<f32>>, Vec<Vector3<f32>>) {
-        let matrix = BoundaryVorticies::create_matrix(&boundary_vorticies)
-            .pseudo_inverse(0.0001f32)
-            .unwrap();
+        let matrix = BoundaryVorticies::create_matrix(
+            &boundary_vorticies,
+            &Kernel::default(),
+            BoundaryCondition::default(),
+        )
+        .pseudo_inverse(0.0001f32)
+        .unwrap();
         // .purify();
         matrix.printstd();
-        let errors_before =
-            BoundaryVorticies::create_error_vector(&boundary_vorticies, &active_vorticies, false);
+        let errors_before = BoundaryVorticies::create_error_vector(
+            &boundary_vorticies,
+            &active_vorticies,
+            false,
+            &Kernel::default(),
+        );
 
         let corrections = BoundaryVorticies::calculate_corrections(&matrix, &errors_before);
 
@@ -165,6 +186,7 @@ mod tests {
                 .collect::<Vec<_>>(),
             &active_vorticies,
             true,
+            &Kernel::default(),
         );
 
         let stats = MagnitudeStatistics::from_vectors(&errors_after);
@@ -0,0 +1,144 @@
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, Vector3};
+
+    use crate::{
+        cpu::active_vorticies::CpuActiveVorticies,
+        objects::{active_vorticies::ActiveVorticies, boundary_vorticies::BoundaryVorticies},
+        physics::{
+            diffusion::Diffusion, kernel::Kernel, stretching::StretchingScheme, treecode::Evaluator,
+        },
+        structures::vortex::Vortex,
+        support::camera::PerspectiveCamera,
+        traits::steppable::Steppable,
+        util::random_inside_unit_sphere,
+    };
+
+    use all_asserts::{assert_le, assert_lt};
+
+    fn relative_error(approximate: &[Vector3<f32>], exact: &[Vector3<f32>]) -> f32 {
+        let error: f32 = approximate
+            .iter()
+            .zip(exact)
+            .map(|(a, e)| (a - e).magnitude2())
+            .sum();
+        let norm: f32 = exact.iter().map(|e| e.magnitude2()).sum();
+        (error / norm).sqrt()
+    }
+
+    fn sources(n: usize) -> Vec<Vortex> {
+        ActiveVorticies::get_random_vorticies(n, 1.0, 2.0, 0.5, 1.0)
+    }
+
+    #[test]
+    fn treecode_without_approximation_is_direct() {
+        let kernel = Kernel::rosenhead_moore(0.05);
+        let sources = sources(500);
+        let points = (0..200)
+            .map(|_| random_inside_unit_sphere() * 1.5)
+            .collect::<Vec<_>>();
+
+        let direct = Evaluator::Direct.get_velocities(&points, &sources, &kernel);
+        let tree = Evaluator::treecode(0.0).get_velocities(&points, &sources, &kernel);
+
+        assert_le!(relative_error(&tree, &direct), 0.00001);
+    }
+
+    #[test]
+    fn treecode_error_shrinks_with_theta() {
+        let kernel = Kernel::rosenhead_moore(0.05);
+        let sources = sources(4000);
+        let points = (0..300)
+            .map(|_| random_inside_unit_sphere() * 1.5)
+            .collect::<Vec<_>>();
+        let direct = Evaluator::Direct.get_velocities(&points, &sources, &kernel);
+
+        let errors = [0.8, 0.4, 0.2].map(|theta| {
+            let tree = Evaluator::treecode(theta).get_velocities(&points, &sources, &kernel);
+            relative_error(&tree, &direct)
+        });
+
+        assert_lt!(errors[1], errors[0]);
+        assert_lt!(errors[2], errors[1]);
+        assert_le!(errors[2], 0.05);
+    }
+
+    #[test]
+    fn error_vector_with_treecode_matches_direct() {
+        let kernel = Kernel::default();
+        let boundary = BoundaryVorticies::vorticies_from_positions(
+            (0..300)
+                .map(|_| {
+                    let position = random_inside_unit_sphere().normalize();
+                    (position, position)
+                })
+                .collect(),
+        );
+        let active = sources(1000);
+
+        let direct = BoundaryVorticies::create_error_vector(&boundary, &active, true, &kernel);
+        let tree = BoundaryVorticies::create_error_vector_with_evaluator(
+            &boundary,
+            &active,
+            true,
+            &kernel,
+            &Evaluator::treecode(0.3),
+        );
+
+        assert_le!(relative_error(&tree, &direct), 0.05);
+    }
+
+    /// The stretching gradient from the octree and the particle strength exchange over its
+    /// neighbours change the strengths like the all-pairs sums.
+    #[test]
+    fn stretching_and_diffusion_with_treecode_match_direct() {
+        let camera = PerspectiveCamera::new(100, 100);
+        let sources = ActiveVorticies::get_random_vorticies(2000, 10.0, 10.0, 0.5, 1.0);
+        let diffusion = Diffusion::new(0.5, 0.1);
+        let rates = |stretching: StretchingScheme, diffusion: Diffusion, evaluator: Evaluator| {
+            let mut vorticies = CpuActiveVorticies::new(sources.clone(), 10.0, 10.0, 0.5, 1.0, 0)
+                .with_kernel(Kernel::rosenhead_moore(0.1))
+                .with_stretching(stretching)
+                .with_diffusion(diffusion)
+                .with_evaluator(evaluator);
+            let dt = 0.01;
+            vorticies.step(dt, &camera);
+            vorticies
+                .get_vorticies()
+                .iter()
+                .zip(&sources)
+                .map(|(after, before)| (after.vorticity - before.vorticity).truncate() / dt)
+                .collect::<Vec<_>>()
+        };
+
+        let direct = rates(StretchingScheme::Classical, diffusion, Evaluator::Direct);
+        let without_diffusion = rates(
+            StretchingScheme::Classical,
+            Diffusion::default(),
+            Evaluator::Direct,
+        );
+        let without_stretching = rates(StretchingScheme::Disabled, diffusion, Evaluator::Direct);
+        let exact = rates(
+            StretchingScheme::Classical,
+            diffusion,
+            Evaluator::treecode(0.0),
+        );
+        let approximate = rates(
+            StretchingScheme::Classical,
+            diffusion,
+            Evaluator::treecode(0.3),
+        );
+        println!(
+            "without diffusion {}, without stretching {}, exact {}, approximate {}",
+            relative_error(&without_diffusion, &direct),
+            relative_error(&without_stretching, &direct),
+            relative_error(&exact, &direct),
+            relative_error(&approximate, &direct)
+        );
+
+        assert_le!(0.1, relative_error(&without_diffusion, &direct));
+        assert_le!(0.1, relative_error(&without_stretching, &direct));
+        assert_le!(relative_error(&exact, &direct), 0.001);
+        assert_le!(relative_error(&approximate, &direct), 0.05);
+    }
+}
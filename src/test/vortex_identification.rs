@@ -0,0 +1,147 @@
+#[cfg(test)]
+mod tests {
+    use cgmath::{Matrix3, Vector3, Vector4};
+
+    use crate::{
+        cpu::field_sampler::CpuFieldSampler,
+        physics::{
+            kernel::Kernel,
+            vortex_identification::{GridSamples, Identification},
+        },
+        structures::{field_sample::FieldSample, grid::Grid, vortex::Vortex},
+    };
+
+    use all_asserts::{assert_gt, assert_le, assert_lt};
+
+    fn sample(gradient: Matrix3<f32>) -> FieldSample {
+        FieldSample {
+            velocity: Vector3::new(1., 0., 0.),
+            gradient,
+        }
+    }
+
+    /// Rigid rotation is all spin: `Q = |Ω|²` and `λ2 = -|Ω|²`, with the vorticity normal to
+    /// the velocity.
+    #[test]
+    fn rigid_rotation_is_a_vortex() {
+        let omega = Vector3::new(0., 0., 2.);
+        let rotation = sample(Matrix3::from_cols(
+            omega.cross(Vector3::unit_x()),
+            omega.cross(Vector3::unit_y()),
+            omega.cross(Vector3::unit_z()),
+        ));
+        assert_le!((rotation.q_criterion() - 4.).abs(), 1e-5);
+        assert_le!((rotation.lambda2() + 4.).abs(), 1e-5);
+        assert_le!(rotation.helicity().abs(), 1e-6);
+        assert_le!((rotation.vorticity_magnitude() - 4.).abs(), 1e-6);
+    }
+
+    /// Pure strain has no vortex by either criterion.
+    #[test]
+    fn pure_strain_is_not_a_vortex() {
+        let strain = sample(Matrix3::new(1., 0., 0., 0., -1., 0., 0., 0., 0.));
+        assert_le!((strain.q_criterion() + 1.).abs(), 1e-6);
+        assert_le!((strain.lambda2() - 1.).abs(), 1e-6);
+        assert_eq!(strain.vorticity_magnitude(), 0.);
+    }
+
+    /// The singular kernel can give a NaN gradient next to a source, which must not panic.
+    #[test]
+    fn nan_gradient_does_not_panic() {
+        let singular = sample(Matrix3::new(f32::NAN, 0., 0., 0., 1., 0., 0., 0., 0.));
+        singular.lambda2();
+    }
+
+    /// A swirl along its own axis, `u = (-y, x, w)` with vorticity `(0, 0, 2)`, has helicity
+    /// `2w`.
+    #[test]
+    fn swirl_has_helicity() {
+        let swirl = FieldSample {
+            velocity: Vector3::new(0., 1., 0.5),
+            gradient: Matrix3::from_cols(
+                Vector3::new(0., 1., 0.),
+                Vector3::new(-1., 0., 0.),
+                Vector3::new(0., 0., 0.),
+            ),
+        };
+        assert_le!((swirl.helicity() - 1.).abs(), 1e-6);
+    }
+
+    fn ring(radius: f32, count: usize) -> Vec<Vortex> {
+        let length = 2. * std::f32::consts::PI * radius / count as f32;
+        (0..count)
+            .map(|i| {
+                let angle = 2. * std::f32::consts::PI * i as f32 / count as f32;
+                let (sin, cos) = angle.sin_cos();
+                Vortex {
+                    position: Vector4::new(cos * radius, sin * radius, 0., 1.),
+                    vorticity: Vector4::new(-sin * length, cos * length, 0., 0.),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    /// Across a vortex ring, `Q` peaks and `λ2` bottoms out at the core.
+    #[test]
+    fn ring_core_is_identified() {
+        let sources = ring(1., 128);
+        let grid = Grid::from_bounds(
+            Vector3::new(0., -0.05, -0.5),
+            Vector3::new(1.6, 0.05, 0.5),
+            0.05,
+        );
+        let samples =
+            CpuFieldSampler::new(Kernel::rosenhead_moore(0.2)).sample_grid(grid, &sources);
+        let q = samples.volume(Identification::QCriterion);
+        let lambda2 = samples.volume(Identification::Lambda2);
+        let points = grid.points();
+        let peak = (0..grid.count())
+            .max_by(|a, b| q.values[*a].total_cmp(&q.values[*b]))
+            .unwrap();
+        let core = points[peak];
+        assert_le!((core.x - 1.).abs(), 0.1);
+        assert_le!(core.z.abs(), 0.1);
+        assert_gt!(q.values[peak], 0.);
+        assert_lt!(lambda2.values[peak], 0.);
+        let (min, _) = lambda2.range();
+        assert_eq!(min, lambda2.values[peak]);
+        // Far from the core, at the center of the ring, strain dominates.
+        let center = grid.index(0, 1, grid.dimensions[2] / 2);
+        assert_lt!(q.values[center], q.values[peak] * 0.01);
+    }
+
+    #[test]
+    fn grid_points_run_x_fastest() {
+        let grid = Grid::from_bounds(Vector3::new(0., 0., 0.), Vector3::new(1., 0.5, 0.5), 0.5);
+        assert_eq!(grid.dimensions, [3, 2, 2]);
+        let points = grid.points();
+        assert_eq!(points.len(), grid.count());
+        assert_eq!(points[1], Vector3::new(0.5, 0., 0.));
+        assert_eq!(points[3], Vector3::new(0., 0.5, 0.));
+        assert_eq!(points[grid.index(2, 1, 1)], Vector3::new(1., 0.5, 0.5));
+    }
+
+    #[test]
+    fn fields_are_written_as_vtk() {
+        let grid = Grid::new(Vector3::new(0., 1., 2.), 0.5, [2, 1, 1]);
+        let samples = GridSamples {
+            grid,
+            samples: vec![FieldSample::default(); 2],
+        };
+        let mut vtk = vec![];
+        samples.write_vtk(&mut vtk).unwrap();
+        let vtk = String::from_utf8(vtk).unwrap();
+        let lines = vtk.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "# vtk DataFile Version 3.0");
+        assert_eq!(lines[3], "DATASET STRUCTURED_POINTS");
+        assert_eq!(lines[4], "DIMENSIONS 2 1 1");
+        assert_eq!(lines[5], "ORIGIN 0 1 2");
+        assert_eq!(lines[6], "SPACING 0.5 0.5 0.5");
+        assert_eq!(lines[7], "POINT_DATA 2");
+        for identification in Identification::ALL {
+            assert!(vtk.contains(&format!("SCALARS {} float 1", identification.name())));
+        }
+        assert_eq!(lines.len(), 8 + 4 * 4 + 1 + 2);
+    }
+}
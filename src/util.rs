@@ -1,4 +1,9 @@
-use std::f32::consts::PI;
+use std::{
+    f32::consts::PI,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+};
 
 use cgmath::Vector3;
 use easy_gltf::load;
@@ -6,6 +11,14 @@ use rand::distributions::{Distribution, Uniform};
 
 use crate::gl;
 
+/// `local_size_x` of the compute shaders using `get_tiled_velocity.glsl`, its `TILE_SIZE`.
+pub const TILE_SIZE: usize = 256;
+
+/// Number of `TILE_SIZE` wide workgroups covering `invocations`.
+pub fn number_of_workgroups(invocations: usize) -> u32 {
+    invocations.div_ceil(TILE_SIZE).max(1) as u32
+}
+
 pub fn create_buffer() -> u32 {
     unsafe {
         let mut vao = 0;
@@ -26,6 +39,43 @@ pub fn create_vao() -> u32 {
     }
 }
 
+/// Contents of the shader storage buffer bound to `binding`, empty if nothing is bound.
+pub fn get_bound_buffer<T: Copy + Default>(binding: u32) -> Vec<T> {
+    unsafe {
+        let mut buffer = 0;
+        gl::GetIntegeri_v(gl::SHADER_STORAGE_BUFFER_BINDING, binding, &mut buffer);
+        if buffer == 0 {
+            return vec![];
+        }
+        let mut size = 0;
+        gl::MemoryBarrier(gl::BUFFER_UPDATE_BARRIER_BIT);
+        gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, buffer as u32);
+        gl::GetBufferParameteriv(gl::SHADER_STORAGE_BUFFER, gl::BUFFER_SIZE, &mut size);
+        let mut data = vec![T::default(); size as usize / std::mem::size_of::<T>()];
+        gl::GetBufferSubData(
+            gl::SHADER_STORAGE_BUFFER,
+            0,
+            std::mem::size_of_val(data.as_slice()) as isize,
+            data.as_mut_ptr().cast(),
+        );
+        data
+    }
+}
+
+/// Creates the file at `path`, and any missing directories above it, and writes it with
+/// `write` through a buffer.
+pub fn save_with<F>(path: &Path, write: F) -> io::Result<()>
+where
+    F: FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+{
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut writer = BufWriter::new(File::create(path)?);
+    write(&mut writer)?;
+    writer.flush()
+}
+
 pub fn random_inside_unit_sphere() -> Vector3<f32> {
     let mut rng = rand::thread_rng();
     random_on_unit_sphere() * Uniform::new(0., 1.).sample(&mut rng)
@@ -58,3 +108,18 @@ pub fn get_vertices_and_normals_from_gltf(model_path: &str) -> Vec<(Vector3<f32>
         .map(|v| (v.position, v.normal))
         .collect::<Vec<_>>()
 }
+
+/// Corner positions of every triangle of the first scene, skipping models that are not
+/// drawn as triangles.
+pub fn get_triangles_from_gltf(model_path: &str) -> Vec<[Vector3<f32>; 3]> {
+    let scenes = load(model_path).expect("Failed to load gltf file");
+    let first_scene = scenes.into_iter().next().expect("No scenes in gltf file");
+
+    first_scene
+        .models
+        .iter()
+        .filter_map(|m| m.triangles().ok())
+        .flatten()
+        .map(|[a, b, c]| [a.position, b.position, c.position])
+        .collect::<Vec<_>>()
+}